@@ -0,0 +1,109 @@
+//! Compare `SpriteBatch` with a naive `Draw::draw_texture_pro` loop.
+//!
+//! Run with `cargo bench --bench sprite_batch` (a hidden window is created).
+use rand::Rng;
+use rfraylib::core::drawing::{SpriteBatch, SpriteInstance};
+use rfraylib::structs::Vector2;
+use rfraylib::texture::{Image, Texture};
+use rfraylib::{Color, Draw, Position, Rectangle, System, SystemBuilder};
+use std::time::{Duration, Instant};
+
+const SPRITES: i32 = 10_000;
+const FRAMES: u32 = 120;
+const SCREEN_WIDTH: i32 = 800;
+const SCREEN_HEIGHT: i32 = 450;
+
+fn main() -> anyhow::Result<()> {
+    let mut system = SystemBuilder::new()
+        .window_size((SCREEN_WIDTH, SCREEN_HEIGHT).into())
+        .headless()
+        .build()?;
+    system.set_target_fps(0);
+
+    let image = Image::generate_checked((64, 64).into(), 8, 8, Color::RED, Color::BLUE);
+    let texture = Texture::load_from_image(&image)
+        .ok_or_else(|| anyhow::anyhow!("failed to load texture"))?;
+
+    let mut rng = rand::thread_rng();
+    let instances = (0..SPRITES)
+        .map(|i| {
+            let source = Rectangle::new((i % 4) * 16, (i / 4 % 4) * 16, 16, 16);
+            let position = Position {
+                x: rng.gen_range(0..SCREEN_WIDTH),
+                y: rng.gen_range(0..SCREEN_HEIGHT),
+            };
+            let mut sprite = SpriteInstance::new(source, position);
+            sprite.origin = Position { x: 8, y: 8 };
+            sprite.rotation = rng.gen_range(0.0..360.0);
+            sprite.scale = Vector2::new(1.5, 1.5);
+            sprite.flip_x = i % 2 == 0;
+            sprite
+        })
+        .collect::<Vec<_>>();
+
+    let naive = bench(&mut system, |canvas| {
+        for sprite in &instances {
+            let width = if sprite.flip_x {
+                -sprite.source.size.width
+            } else {
+                sprite.source.size.width
+            };
+            let scale = sprite.scale.x();
+            canvas.draw_texture_pro(
+                &texture,
+                Rectangle {
+                    size: (width, sprite.source.size.height).into(),
+                    ..sprite.source
+                },
+                Rectangle {
+                    position: sprite.position,
+                    size: sprite
+                        .source
+                        .size
+                        .map(|w, h| ((w as f32 * scale) as i32, (h as f32 * scale) as i32)),
+                },
+                sprite
+                    .origin
+                    .map(|x, y| ((x as f32 * scale) as i32, (y as f32 * scale) as i32)),
+                sprite.rotation,
+                sprite.tint,
+            );
+        }
+    });
+    report("draw_texture_pro loop", naive);
+
+    let mut batch = SpriteBatch::new(texture.clone());
+    batch.extend_from_slice(&instances);
+    let batched = bench(&mut system, |canvas| batch.draw(canvas));
+    report("SpriteBatch", batched);
+
+    let sliced = bench(&mut system, |canvas| {
+        canvas.draw_sprites(&texture, &instances)
+    });
+    report("Draw::draw_sprites", sliced);
+
+    Ok(())
+}
+
+fn bench<F>(system: &mut System, mut f: F) -> Duration
+where
+    F: FnMut(&mut rfraylib::core::drawing::WindowCanvas),
+{
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        let mut canvas = system.next_frame();
+        canvas.clear_background(Color::RAYWHITE);
+        f(&mut canvas);
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<24} {} sprites x {} frames: {:>8.3} ms/frame",
+        name,
+        SPRITES,
+        FRAMES,
+        elapsed.as_secs_f64() * 1000.0 / FRAMES as f64
+    );
+}
@@ -1,48 +1,67 @@
 use rand::Rng;
-use rfraylib::{Camera, Color, Draw, Key, Rectangle};
+use rfraylib::core::drawing::WindowCanvas;
+use rfraylib::{App, Camera, Color, Draw, Key, Rectangle, System};
+use std::time::Duration;
 
 const MAX_BUILDINGS: usize = 100;
 const SCREEN_WIDTH: i32 = 800;
 const SCREEN_HEIGHT: i32 = 450;
 
-fn main() -> anyhow::Result<()> {
-    let mut system = rfraylib::SystemBuilder::new()
-        .window_size((SCREEN_WIDTH, SCREEN_HEIGHT).into())
-        .window_title("raylib [core] example - 2d camera")
-        .target_fps(60)
-        .build()?;
+struct Camera2d {
+    player: Rectangle,
+    buildings: Vec<Rectangle>,
+    build_colors: Vec<Color>,
+    camera: Camera,
+}
+
+impl Camera2d {
+    fn new() -> Self {
+        let player = Rectangle::new(400, 280, 40, 40);
+        let mut buildings = Vec::new();
+        let mut build_colors = Vec::new();
+        let mut spacing = 0;
+        let mut rng = rand::thread_rng();
+        for _ in 0..MAX_BUILDINGS {
+            let width = rng.gen_range(50..200);
+            let height = rng.gen_range(100..800);
+            buildings.push(Rectangle::new(
+                -6000 + spacing,
+                SCREEN_HEIGHT - 130 - height,
+                width,
+                height,
+            ));
+            spacing += width;
+
+            build_colors.push(Color::rgb(
+                rng.gen_range(200..240),
+                rng.gen_range(200..240),
+                rng.gen_range(200..250),
+            ));
+        }
 
-    let mut player = Rectangle::new(400, 280, 40, 40);
-    let mut buildings = Vec::new();
-    let mut build_colors = Vec::new();
-    let mut spacing = 0;
-    let mut rng = rand::thread_rng();
-    for _ in 0..MAX_BUILDINGS {
-        let width = rng.gen_range(50..200);
-        let height = rng.gen_range(100..800);
-        buildings.push(Rectangle::new(
-            -6000 + spacing,
-            SCREEN_HEIGHT - 130 - height,
-            width,
-            height,
-        ));
-        spacing += width;
-
-        build_colors.push(Color::rgb(
-            rng.gen_range(200..240),
-            rng.gen_range(200..240),
-            rng.gen_range(200..250),
-        ));
+        let camera = Camera {
+            target: player.position.map(|x, y| (x + 20, y + 20)),
+            offset: (SCREEN_WIDTH as i32 / 2, SCREEN_HEIGHT as i32 / 2).into(),
+            rotation: 0.0,
+            zoom: 1.0,
+        };
+
+        Self {
+            player,
+            buildings,
+            build_colors,
+            camera,
+        }
     }
+}
+
+impl App for Camera2d {
+    type Error = anyhow::Error;
 
-    let mut camera = Camera {
-        target: player.position.map(|x, y| (x + 20, y + 20)),
-        offset: (SCREEN_WIDTH as i32 / 2, SCREEN_HEIGHT as i32 / 2).into(),
-        rotation: 0.0,
-        zoom: 1.0,
-    };
+    fn update(&mut self, system: &mut System, _dt: Duration) -> anyhow::Result<()> {
+        let player = &mut self.player;
+        let camera = &mut self.camera;
 
-    while !system.window().should_close() {
         // Player movement
         if system.keyboard().is_key_down(Key::Right) {
             player.position.x += 2;
@@ -82,17 +101,27 @@ fn main() -> anyhow::Result<()> {
             camera.rotation = 0.0;
         }
 
-        let mut canvas = system.next_frame();
+        Ok(())
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas, _alpha: f32) -> anyhow::Result<()> {
+        let camera = self.camera;
+
         canvas.clear_background(Color::RAYWHITE);
         {
             let mut canvas = canvas.with_camera(camera);
             canvas.draw_rectangle(Rectangle::new(-6000, 320, 13000, 8000), Color::DARKGRAY);
 
-            for (b, c) in buildings.iter().copied().zip(build_colors.iter().copied()) {
+            for (b, c) in self
+                .buildings
+                .iter()
+                .copied()
+                .zip(self.build_colors.iter().copied())
+            {
                 canvas.draw_rectangle(b, c);
             }
 
-            canvas.draw_rectangle(player, Color::RED);
+            canvas.draw_rectangle(self.player, Color::RED);
 
             canvas.draw_line(
                 (camera.target.x, -(SCREEN_HEIGHT as i32) * 10).into(),
@@ -146,7 +175,17 @@ fn main() -> anyhow::Result<()> {
             10,
             Color::DARKGRAY,
         )?;
+
+        Ok(())
     }
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut system = rfraylib::SystemBuilder::new()
+        .window_size((SCREEN_WIDTH, SCREEN_HEIGHT).into())
+        .window_title("raylib [core] example - 2d camera")
+        .target_fps(60)
+        .build()?;
 
-    Ok(())
+    system.run(&mut Camera2d::new())
 }
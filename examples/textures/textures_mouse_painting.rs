@@ -65,13 +65,11 @@ fn main() -> anyhow::Result<()> {
         // Update
         //
         let mouse_pos = system.mouse().get_position();
-        let keyboard = system.keyboard();
-        let mouse = system.mouse();
 
         // Move between colors with keys
-        if keyboard.is_key_pressed(Key::Right) {
+        if system.keyboard().is_key_pressed(Key::Right) {
             color_selected = std::cmp::min(colors.len() - 1, color_selected + 1);
-        } else if keyboard.is_key_pressed(Key::Left) {
+        } else if system.keyboard().is_key_pressed(Key::Left) {
             color_selected = color_selected.saturating_sub(1);
         }
 
@@ -81,25 +79,25 @@ fn main() -> anyhow::Result<()> {
             .position(|r| mouse_pos.check_collision_point_rec(*r));
 
         if let Some(i) = color_mouse_hover {
-            if mouse.is_button_pressed(MouseButton::Left) {
+            if system.mouse().is_button_pressed(MouseButton::Left) {
                 color_selected = i;
                 color_selected_prev = color_selected;
             }
         }
 
         // Change brush size
-        brush_size = (brush_size + mouse.get_wheel_move() * 5.0)
+        brush_size = (brush_size + system.mouse().get_wheel_move() * 5.0)
             .max(2.0)
             .min(50.0);
 
-        if keyboard.is_key_pressed(Key::C) {
+        if system.keyboard().is_key_pressed(Key::C) {
             // Clear render texture to clear color
             system
                 .create_texture_canvas(&mut target)
                 .clear_background(colors[0]);
         }
 
-        if mouse.is_button_down(MouseButton::Left)
+        if system.mouse().is_button_down(MouseButton::Left)
             || system.touch().get_gesture_detected() == Gesture::Drag
         {
             // Paint circle into render texture
@@ -113,7 +111,7 @@ fn main() -> anyhow::Result<()> {
                 );
             }
         }
-        if mouse.is_button_down(MouseButton::Right) {
+        if system.mouse().is_button_down(MouseButton::Right) {
             if !mouse_was_pressed {
                 color_selected_prev = color_selected;
                 color_selected = 0;
@@ -127,7 +125,7 @@ fn main() -> anyhow::Result<()> {
                     .create_texture_canvas(&mut target)
                     .draw_circle(mouse_pos, brush_size, colors[0]);
             }
-        } else if mouse.is_button_released(MouseButton::Right) && mouse_was_pressed {
+        } else if system.mouse().is_button_released(MouseButton::Right) && mouse_was_pressed {
             color_selected = color_selected_prev;
             mouse_was_pressed = false;
         }
@@ -137,8 +135,8 @@ fn main() -> anyhow::Result<()> {
 
         // Image saving logic
         // NOTE: Saving painted texture to a default named image
-        if btn_save_mouse_hover && mouse.is_button_released(MouseButton::Left)
-            || keyboard.is_key_pressed(Key::S)
+        if btn_save_mouse_hover && system.mouse().is_button_released(MouseButton::Left)
+            || system.keyboard().is_key_pressed(Key::S)
         {
             let mut image = Image::load_from_texture(target.texture()).expect("TODO");
             image.flip_vertical();
@@ -154,7 +152,7 @@ fn main() -> anyhow::Result<()> {
             }
         }
 
-        let is_right_button_down = mouse.is_button_down(MouseButton::Right);
+        let is_right_button_down = system.mouse().is_button_down(MouseButton::Right);
 
         //
         // Draw
@@ -0,0 +1,38 @@
+use crate::core::drawing::WindowCanvas;
+use crate::{Size, System};
+use std::time::Duration;
+
+/// Application driven by [`System::run`].
+pub trait App {
+    type Error;
+
+    /// Update the application state (not called while the loop is paused).
+    fn update(&mut self, system: &mut System, dt: Duration) -> Result<(), Self::Error>;
+
+    /// Advance the simulation by one fixed step (see [`System::set_fixed_timestep`]).
+    fn fixed_update(&mut self, _system: &mut System, _step: Duration) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Draw a frame.
+    ///
+    /// `alpha` is the interpolation factor between the last two fixed steps (`1.0` if no fixed timestep is set).
+    fn draw(&mut self, canvas: &mut WindowCanvas, alpha: f32) -> Result<(), Self::Error>;
+
+    /// Called when the window has been resized.
+    fn on_resize(&mut self, _system: &mut System, _size: Size) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called when the window gains or loses focus.
+    fn on_focus_change(&mut self, _system: &mut System, _focused: bool) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called when the window is requested to close (KEY_ESCAPE pressed or Close icon pressed).
+    ///
+    /// Returns `false` to keep running.
+    fn on_close_requested(&mut self, _system: &mut System) -> bool {
+        true
+    }
+}
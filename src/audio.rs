@@ -1,16 +1,41 @@
+use std::cell::Cell;
 use std::ffi::CString;
 use std::os::raw::{c_int, c_void};
 use std::path::Path;
 use std::time::Duration;
 
 #[derive(Debug)]
-pub struct AudioDevice(pub(crate) ()); // TODO
+pub struct AudioDevice {
+    is_null: bool,
+    clock_paused: bool,
+}
 
 impl AudioDevice {
     pub(crate) fn new() -> Self {
         // TODO: check duplicate
         unsafe { raylib4_sys::InitAudioDevice() };
-        Self(())
+        Self {
+            is_null: false,
+            clock_paused: false,
+        }
+    }
+
+    pub(crate) fn null() -> Self {
+        Self {
+            is_null: true,
+            clock_paused: false,
+        }
+    }
+
+    pub(crate) fn set_clock_paused(&mut self, paused: bool) {
+        self.clock_paused = paused;
+    }
+
+    /// Check if this is a null device that doesn't output any sound.
+    ///
+    /// NOTE: Sounds and music streams cannot be loaded while a null device is in use.
+    pub fn is_null(&self) -> bool {
+        self.is_null
     }
 
     /// Set master volume (listener).
@@ -70,6 +95,7 @@ impl AudioDevice {
 
     /// Start music playing.
     pub fn play_music_stream(&mut self, music: &Music) {
+        music.1.set(false);
         unsafe { raylib4_sys::PlayMusicStream(music.0) };
     }
 
@@ -79,17 +105,31 @@ impl AudioDevice {
     }
 
     /// Updates buffers for music streaming.
+    ///
+    /// NOTE: While the game clock is paused, the music is paused too (and resumed along with the clock).
     pub fn update_music_stream(&mut self, music: &Music) {
+        if self.clock_paused {
+            if !music.1.get() && unsafe { raylib4_sys::IsMusicStreamPlaying(music.0) } {
+                unsafe { raylib4_sys::PauseMusicStream(music.0) };
+                music.1.set(true);
+            }
+            return;
+        }
+        if music.1.replace(false) {
+            unsafe { raylib4_sys::ResumeMusicStream(music.0) };
+        }
         unsafe { raylib4_sys::UpdateMusicStream(music.0) };
     }
 
     /// Stop music playing.
     pub fn stop_music_stream(&mut self, music: &Music) {
+        music.1.set(false);
         unsafe { raylib4_sys::StopMusicStream(music.0) };
     }
 
     /// Pause music playing.
     pub fn pause_music_stream(&mut self, music: &Music) {
+        music.1.set(false);
         unsafe { raylib4_sys::PauseMusicStream(music.0) };
     }
 
@@ -162,7 +202,9 @@ impl AudioDevice {
 
 impl Drop for AudioDevice {
     fn drop(&mut self) {
-        unsafe { raylib4_sys::CloseAudioDevice() };
+        if !self.is_null {
+            unsafe { raylib4_sys::CloseAudioDevice() };
+        }
     }
 }
 
@@ -299,7 +341,10 @@ fn path_to_cstring<P: AsRef<Path>>(path: P) -> Option<CString> {
 }
 
 #[derive(Debug)]
-pub struct Music(raylib4_sys::Music);
+pub struct Music(
+    raylib4_sys::Music,
+    Cell<bool>, // Paused by the game clock.
+);
 
 impl Music {
     /// Load music stream from file.
@@ -309,7 +354,7 @@ impl Music {
         if music.ctxData == std::ptr::null_mut() {
             None
         } else {
-            Some(Self(music))
+            Some(Self(music, Cell::new(false)))
         }
     }
 
@@ -326,7 +371,7 @@ impl Music {
         if music.ctxData == std::ptr::null_mut() {
             None
         } else {
-            Some(Self(music))
+            Some(Self(music, Cell::new(false)))
         }
     }
 
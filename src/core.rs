@@ -1,5 +1,9 @@
+pub mod capture;
 pub mod cursor;
 pub mod drawing;
 pub mod input;
 pub mod monitor;
+pub mod postprocess;
+pub mod shader;
+pub mod virtual_screen;
 pub mod window;
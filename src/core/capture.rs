@@ -0,0 +1,711 @@
+//! Screen recording (animated GIF or PNG sequence).
+//!
+//! ```no_run
+//! use rfraylib::core::capture::{RecordingFormat, RecordingOptions};
+//! use rfraylib::{Color, Draw, Key};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! while !system.window().should_close() {
+//!     if system.keyboard().is_key_pressed(Key::F9) {
+//!         if system.is_recording() {
+//!             system.stop_recording()?;
+//!         } else {
+//!             let options = RecordingOptions {
+//!                 format: RecordingFormat::Gif,
+//!                 fps: 20,
+//!                 scale: 0.5,
+//!                 ..Default::default()
+//!             };
+//!             system.start_recording("capture.gif", options)?;
+//!         }
+//!     }
+//!
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::texture::Image;
+use crate::{Color, Size, Window};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+extern "C" {
+    // rlgl.h (not included in the raylib4-sys bindings).
+    fn rlDrawRenderBatchActive();
+}
+
+/// Output format of [`System::start_recording`](crate::System::start_recording).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordingFormat {
+    /// Animated GIF (looping forever).
+    #[default]
+    Gif,
+
+    /// Numbered PNG files (`frame_00000.png`, `frame_00001.png`, ...) in the given directory.
+    PngSequence,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordingOptions {
+    pub format: RecordingFormat,
+
+    /// Number of frames captured per second (default: 15).
+    pub fps: u32,
+
+    /// Scale factor of the recorded frames relative to the screen (default: 1.0).
+    pub scale: f32,
+
+    /// Dither GIF frames that have more than 256 colors (default: true).
+    pub dither: bool,
+
+    /// Maximum number of captured frames waiting to be encoded (default: 8).
+    ///
+    /// Captures are skipped (without reading the screen back) while the queue is full
+    /// so that encoding never stalls the game loop.
+    pub max_queued_frames: usize,
+}
+
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        Self {
+            format: RecordingFormat::default(),
+            fps: 15,
+            scale: 1.0,
+            dither: true,
+            max_queued_frames: 8,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CapturedFrame {
+    size: Size,
+    colors: Vec<Color>,
+    time: Duration,
+}
+
+/// Number of captured frames that the encoder has not received yet (shared with the encoder thread).
+#[derive(Debug, Clone)]
+struct QueuedFrames {
+    count: Arc<AtomicUsize>,
+    max: usize,
+}
+
+impl QueuedFrames {
+    fn new(max: usize) -> Self {
+        Self {
+            count: Arc::new(AtomicUsize::new(0)),
+            max,
+        }
+    }
+
+    /// Reserve a place for a frame in the queue (`false` if the queue is full).
+    fn reserve(&self) -> bool {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .is_ok()
+    }
+
+    /// Release a place (the frame has been received by the encoder or has not been sent).
+    fn release(&self) {
+        self.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ScreenRecorder {
+    sender: Option<SyncSender<CapturedFrame>>,
+    queued: QueuedFrames,
+    worker: Option<JoinHandle<std::io::Result<()>>>,
+    interval: Duration,
+    start: Instant,
+    next_capture: Duration,
+    captured: usize,
+    dropped: usize,
+}
+
+impl ScreenRecorder {
+    pub(crate) fn start<P: AsRef<Path>>(
+        path: P,
+        options: RecordingOptions,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let interval = Duration::from_secs(1) / options.fps.max(1);
+        let queued = QueuedFrames::new(options.max_queued_frames.max(1));
+        let (sender, receiver) = mpsc::sync_channel(queued.max);
+        let received = queued.clone();
+        let frames = receiver.into_iter().inspect(move |_| received.release());
+        let worker = match options.format {
+            RecordingFormat::Gif => {
+                let file = BufWriter::new(File::create(&path)?);
+                std::thread::spawn(move || encode_gif(file, frames, &options, interval))
+            }
+            RecordingFormat::PngSequence => {
+                std::fs::create_dir_all(&path)?;
+                std::thread::spawn(move || encode_png_sequence(&path, frames, &options))
+            }
+        };
+        Ok(Self {
+            sender: Some(sender),
+            queued,
+            worker: Some(worker),
+            interval,
+            start: Instant::now(),
+            next_capture: Duration::ZERO,
+            captured: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Capture the screen if it is time to (must be called before the frame buffers are swapped).
+    ///
+    /// Returns `false` if the encoder has stopped.
+    pub(crate) fn capture(&mut self, window: &Window) -> bool {
+        let time = self.start.elapsed();
+        if time < self.next_capture {
+            return true;
+        }
+        // Skip the captures missed by slow frames instead of catching up.
+        self.next_capture += self.interval;
+        if self.next_capture < time {
+            self.next_capture = time + self.interval;
+        }
+
+        let sender = match &self.sender {
+            Some(sender) => sender,
+            None => return false,
+        };
+        // Check the queue before the (slow) readback of the screen.
+        if !self.queued.reserve() {
+            self.dropped += 1;
+            return true;
+        }
+        let image = unsafe {
+            rlDrawRenderBatchActive();
+            Image::load_from_screen(window)
+        };
+        let image = match image {
+            Some(image) => image,
+            None => {
+                self.queued.release();
+                return true;
+            }
+        };
+        let frame = CapturedFrame {
+            size: image.size(),
+            colors: image.load_colors(),
+            time,
+        };
+        match sender.try_send(frame) {
+            Ok(()) => self.captured += 1,
+            Err(TrySendError::Full(_)) => {
+                self.queued.release();
+                self.dropped += 1;
+            }
+            Err(TrySendError::Disconnected(_)) => return false,
+        }
+        true
+    }
+
+    /// Wait for the queued frames to be encoded.
+    pub(crate) fn finish(mut self) -> std::io::Result<()> {
+        self.join()
+    }
+
+    fn join(&mut self) -> std::io::Result<()> {
+        self.sender = None;
+        let worker = match self.worker.take() {
+            Some(worker) => worker,
+            None => return Ok(()),
+        };
+        if self.dropped > 0 {
+            log::warn!(
+                "screen recording dropped {} of {} frames",
+                self.dropped,
+                self.captured + self.dropped
+            );
+        }
+        worker
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::other("screen recording thread panicked")))
+    }
+}
+
+impl Drop for ScreenRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.join() {
+            log::warn!("failed to finish screen recording: {}", e);
+        }
+    }
+}
+
+/// Make an image of the frame scaled to `size` (decided by the first frame).
+fn scaled_image(frame: &CapturedFrame, size: &mut Option<Size>, scale: f32) -> Option<Image> {
+    let mut image = Image::from_colors(frame.size, &frame.colors)?;
+    let size = *size.get_or_insert_with(|| Size {
+        width: ((frame.size.width as f32 * scale).round() as i32).max(1),
+        height: ((frame.size.height as f32 * scale).round() as i32).max(1),
+    });
+    if image.size() != size {
+        image.resize(size);
+    }
+    Some(image)
+}
+
+fn export_error(path: &Path) -> std::io::Error {
+    std::io::Error::other(format!("failed to export image {:?}", path))
+}
+
+fn encode_png_sequence(
+    dir: &Path,
+    frames: impl Iterator<Item = CapturedFrame>,
+    options: &RecordingOptions,
+) -> std::io::Result<()> {
+    let mut size = None;
+    for (i, frame) in frames.enumerate() {
+        let path: PathBuf = dir.join(format!("frame_{:05}.png", i));
+        let image =
+            scaled_image(&frame, &mut size, options.scale).ok_or_else(|| export_error(&path))?;
+        if !image.export(&path) {
+            return Err(export_error(&path));
+        }
+    }
+    Ok(())
+}
+
+fn encode_gif<W: Write>(
+    writer: W,
+    frames: impl Iterator<Item = CapturedFrame>,
+    options: &RecordingOptions,
+    interval: Duration,
+) -> std::io::Result<()> {
+    let mut gif = None;
+    let mut size = None;
+    let mut quantizer = Quantizer::new(options.dither);
+    let mut writer = Some(writer);
+    // A frame is written when the next one arrives, as its delay is the time until then.
+    let mut pending: Option<(Vec<Color>, Vec<u8>)> = None;
+    let mut written = 0; // In centiseconds (rounding errors do not accumulate).
+    let mut first_time = None;
+    for frame in frames {
+        let image = match scaled_image(&frame, &mut size, options.scale) {
+            Some(image) => image,
+            None => continue,
+        };
+        let time = frame.time - *first_time.get_or_insert(frame.time);
+        let (palette, indices) = quantizer.quantize(&image);
+
+        let gif = match &mut gif {
+            Some(gif) => gif,
+            None => gif.insert(GifWriter::new(
+                writer.take().expect("unreachable"),
+                image.size(),
+            )?),
+        };
+        if let Some((palette, indices)) = pending.take() {
+            let end = centiseconds(time).max(written + 1);
+            gif.write_frame(&palette, &indices, (end - written) as u16)?;
+            written = end;
+        }
+        pending = Some((palette, indices));
+    }
+    if let (Some(gif), Some((palette, indices))) = (&mut gif, pending) {
+        gif.write_frame(&palette, &indices, centiseconds(interval).max(1) as u16)?;
+    }
+    match gif {
+        Some(gif) => gif.finish(),
+        None => Ok(()),
+    }
+}
+
+fn centiseconds(d: Duration) -> u64 {
+    (d.as_millis() as u64 + 5) / 10
+}
+
+/// Color quantization to at most 256 colors.
+///
+/// Frames whose colors fit in a palette (see [`Image::load_palette`]) are stored losslessly.
+/// Once a frame exceeds 256 colors, the rest of the recording uses a fixed 6x7x6 palette
+/// (with Floyd-Steinberg dithering if enabled).
+#[derive(Debug)]
+struct Quantizer {
+    dither: bool,
+    fixed: bool,
+}
+
+impl Quantizer {
+    fn new(dither: bool) -> Self {
+        Self {
+            dither,
+            fixed: false,
+        }
+    }
+
+    fn quantize(&mut self, image: &Image) -> (Vec<Color>, Vec<u8>) {
+        let colors = image.load_colors();
+        if !self.fixed {
+            // `load_palette()` skips transparent pixels, but screen captures are opaque.
+            let palette = image.load_palette(257);
+            if palette.len() <= 256 {
+                let lookup = palette
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| ((c.r, c.g, c.b), i as u8))
+                    .collect::<HashMap<_, _>>();
+                let indices = colors
+                    .iter()
+                    .map(|c| lookup.get(&(c.r, c.g, c.b)).copied().unwrap_or(0))
+                    .collect();
+                return (palette, indices);
+            }
+            self.fixed = true;
+        }
+
+        let palette = (0..256)
+            .map(|i| {
+                if i >= 6 * 7 * 6 {
+                    return Color::BLACK;
+                }
+                let (r, g, b) = (i / 42, i / 6 % 7, i % 6);
+                Color::rgba((r * 51) as u8, (g * 255 / 6) as u8, (b * 51) as u8, 255)
+            })
+            .collect::<Vec<_>>();
+        let nearest = |r: f32, g: f32, b: f32| {
+            let r = (r / 51.0).round().clamp(0.0, 5.0) as usize;
+            let g = (g * 6.0 / 255.0).round().clamp(0.0, 6.0) as usize;
+            let b = (b / 51.0).round().clamp(0.0, 5.0) as usize;
+            (r * 42 + g * 6 + b) as u8
+        };
+
+        let width = image.size().width.max(1) as usize;
+        let mut indices = Vec::with_capacity(colors.len());
+        if !self.dither {
+            for c in &colors {
+                indices.push(nearest(c.r as f32, c.g as f32, c.b as f32));
+            }
+            return (palette, indices);
+        }
+
+        let mut pixels = colors
+            .iter()
+            .map(|c| [c.r as f32, c.g as f32, c.b as f32])
+            .collect::<Vec<_>>();
+        for i in 0..pixels.len() {
+            let [r, g, b] = pixels[i];
+            let index = nearest(r, g, b);
+            indices.push(index);
+            let q = palette[index as usize];
+            let error = [r - q.r as f32, g - q.g as f32, b - q.b as f32];
+            let (x, y) = (i % width, i / width);
+            let mut spread = |dx: isize, dy: usize, weight: f32| {
+                let nx = x as isize + dx;
+                if nx < 0 || nx >= width as isize {
+                    return;
+                }
+                if let Some(p) = pixels.get_mut((y + dy) * width + nx as usize) {
+                    for c in 0..3 {
+                        p[c] += error[c] * weight;
+                    }
+                }
+            };
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+        (palette, indices)
+    }
+}
+
+/// Minimal GIF89a encoder (one local color table per frame).
+#[derive(Debug)]
+struct GifWriter<W: Write> {
+    writer: W,
+    size: Size,
+}
+
+impl<W: Write> GifWriter<W> {
+    fn new(mut writer: W, size: Size) -> std::io::Result<Self> {
+        writer.write_all(b"GIF89a")?;
+        writer.write_all(&(size.width as u16).to_le_bytes())?;
+        writer.write_all(&(size.height as u16).to_le_bytes())?;
+        writer.write_all(&[0, 0, 0])?; // No global color table.
+
+        // Loop forever.
+        writer.write_all(&[0x21, 0xFF, 11])?;
+        writer.write_all(b"NETSCAPE2.0")?;
+        writer.write_all(&[3, 1, 0, 0, 0])?;
+        Ok(Self { writer, size })
+    }
+
+    fn write_frame(
+        &mut self,
+        palette: &[Color],
+        indices: &[u8],
+        delay: u16,
+    ) -> std::io::Result<()> {
+        let w = &mut self.writer;
+
+        // Graphic control extension.
+        w.write_all(&[0x21, 0xF9, 4, 0])?;
+        w.write_all(&delay.to_le_bytes())?;
+        w.write_all(&[0, 0])?;
+
+        // Image descriptor with a 256 entries local color table.
+        w.write_all(&[0x2C, 0, 0, 0, 0])?;
+        w.write_all(&(self.size.width as u16).to_le_bytes())?;
+        w.write_all(&(self.size.height as u16).to_le_bytes())?;
+        w.write_all(&[0x87])?;
+        for i in 0..256 {
+            let c = palette.get(i).copied().unwrap_or(Color::BLACK);
+            w.write_all(&[c.r, c.g, c.b])?;
+        }
+
+        w.write_all(&[8])?;
+        for block in lzw_encode(indices, 8).chunks(255) {
+            w.write_all(&[block.len() as u8])?;
+            w.write_all(block)?;
+        }
+        w.write_all(&[0])
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        self.writer.write_all(&[0x3B])?;
+        self.writer.flush()
+    }
+}
+
+/// Variable-length-code LZW compression as used by GIF.
+fn lzw_encode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+    let mut encoder = LzwEncoder::new(min_code_size);
+    let mut iter = data.iter();
+    if let Some(&first) = iter.next() {
+        let mut code = first as u32;
+        for &literal in iter {
+            if let Some(&next) = encoder.table.get(&(code, literal)) {
+                code = next;
+                continue;
+            }
+            encoder.write(code);
+            if encoder.inc_hi() {
+                encoder.table.insert((code, literal), encoder.hi);
+            }
+            code = literal as u32;
+        }
+        encoder.write(code);
+        encoder.inc_hi();
+    }
+    encoder.write(encoder.clear + 1);
+    encoder.finish()
+}
+
+#[derive(Debug)]
+struct LzwEncoder {
+    min_code_size: u32,
+    clear: u32,
+    table: HashMap<(u32, u8), u32>,
+    width: u32,
+    hi: u32, // The last assigned code.
+    overflow: u32,
+    bits: u32,
+    nbits: u32,
+    out: Vec<u8>,
+}
+
+impl LzwEncoder {
+    const MAX_CODE: u32 = 4095;
+
+    fn new(min_code_size: u32) -> Self {
+        let clear = 1 << min_code_size;
+        let mut this = Self {
+            min_code_size,
+            clear,
+            table: HashMap::new(),
+            width: min_code_size + 1,
+            hi: clear + 1,
+            overflow: clear << 1,
+            bits: 0,
+            nbits: 0,
+            out: Vec::new(),
+        };
+        this.write(clear);
+        this
+    }
+
+    fn write(&mut self, code: u32) {
+        self.bits |= code << self.nbits;
+        self.nbits += self.width;
+        while self.nbits >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.nbits -= 8;
+        }
+    }
+
+    /// Advance to the next code, returning `false` if the table has been reset.
+    fn inc_hi(&mut self) -> bool {
+        self.hi += 1;
+        if self.hi == self.overflow {
+            self.width += 1;
+            self.overflow <<= 1;
+        }
+        if self.hi == Self::MAX_CODE {
+            self.write(self.clear);
+            self.table.clear();
+            self.width = self.min_code_size + 1;
+            self.hi = self.clear + 1;
+            self.overflow = self.clear << 1;
+            return false;
+        }
+        true
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.nbits > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode GIF LZW data.
+    fn lzw_decode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1 << min_code_size;
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            table.clear();
+            table.extend((0..clear + 2).map(|i| vec![i as u8]));
+        };
+        let mut table = Vec::new();
+        reset(&mut table);
+        let mut width = min_code_size + 1;
+        let (mut bits, mut nbits) = (0u32, 0);
+        let mut bytes = data.iter();
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        loop {
+            while nbits < width {
+                bits |= (*bytes.next().expect("missing end code") as u32) << nbits;
+                nbits += 8;
+            }
+            let code = (bits & ((1 << width) - 1)) as usize;
+            bits >>= width;
+            nbits -= width;
+            if code == clear {
+                reset(&mut table);
+                width = min_code_size + 1;
+                prev = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+            let entry = match (table.get(code), &prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) if code == table.len() => [&prev[..], &prev[..1]].concat(),
+                _ => panic!("invalid code {}", code),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(prev) = prev {
+                table.push([&prev[..], &entry[..1]].concat());
+            }
+            if table.len() == 1 << width && width < 12 {
+                width += 1;
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trips() {
+        let noise = (0..20_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+        let runs = (0..20_000u32)
+            .map(|i| (i / 37 % 5) as u8)
+            .collect::<Vec<_>>();
+        let inputs = [vec![], vec![0], vec![1; 10_000], noise, runs];
+        for data in &inputs {
+            assert_eq!(&lzw_decode(&lzw_encode(data, 8), 8), data);
+        }
+        let small = inputs[4].iter().map(|&i| i & 3).collect::<Vec<_>>();
+        assert_eq!(lzw_decode(&lzw_encode(&small, 2), 2), small);
+    }
+
+    #[test]
+    fn gif_writer_output_parses() {
+        let size = Size {
+            width: 4,
+            height: 3,
+        };
+        let palette = [Color::RED, Color::BLUE];
+        let frames = [[0, 1, 0, 1, 1, 0, 1, 0, 0, 0, 1, 1], [1; 12]];
+        let mut data = Vec::new();
+        let mut writer = GifWriter::new(&mut data, size).unwrap();
+        for (i, indices) in frames.iter().enumerate() {
+            writer.write_frame(&palette, indices, 5 + i as u16).unwrap();
+        }
+        writer.finish().unwrap();
+
+        assert_eq!(&data[..6], b"GIF89a");
+        assert_eq!(data[6..10], [4, 0, 3, 0]);
+        assert_eq!(data.last(), Some(&0x3B));
+
+        // Header, NETSCAPE2.0 extension, then a graphic control extension before each frame.
+        let mut pos = 13 + 19;
+        for (i, indices) in frames.iter().enumerate() {
+            assert_eq!(data[pos..pos + 4], [0x21, 0xF9, 4, 0]);
+            assert_eq!(data[pos + 4], 5 + i as u8);
+            pos += 8;
+            assert_eq!(data[pos], 0x2C);
+            pos += 10;
+            let red = &data[pos..pos + 3];
+            assert_eq!(red, [Color::RED.r, Color::RED.g, Color::RED.b]);
+            pos += 256 * 3;
+            assert_eq!(data[pos], 8);
+            pos += 1;
+            let mut lzw = Vec::new();
+            while data[pos] != 0 {
+                let len = data[pos] as usize;
+                lzw.extend_from_slice(&data[pos + 1..pos + 1 + len]);
+                pos += 1 + len;
+            }
+            pos += 1;
+            assert_eq!(lzw_decode(&lzw, 8), indices);
+        }
+        assert_eq!(pos, data.len() - 1);
+    }
+
+    #[test]
+    fn centiseconds_round_to_nearest() {
+        assert_eq!(centiseconds(Duration::from_millis(16)), 2);
+        assert_eq!(centiseconds(Duration::from_millis(14)), 1);
+        assert_eq!(centiseconds(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn queued_frames_are_limited_until_received() {
+        let queued = QueuedFrames::new(2);
+        assert!(queued.reserve());
+        assert!(queued.reserve());
+        assert!(!queued.reserve());
+        queued.clone().release();
+        assert!(queued.reserve());
+        assert!(!queued.reserve());
+    }
+}
@@ -1,9 +1,26 @@
+use crate::core::shader::Shader;
 use crate::structs::Rectangle;
 use crate::text::Font;
 use crate::texture::{NpatchInfo, PixelFormat, RenderTexture, Texture};
 use crate::{Position, Size};
-use std::ffi::CString;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, NulError};
 use std::os::raw::{c_int, c_void};
+use std::sync::{Arc, Once};
+
+pub use self::batch::{SpriteBatch, SpriteInstance};
+pub use self::command::{DrawCommand, DrawMode};
+pub use self::image_canvas::ImageCanvas;
+pub use self::layered::{LayeredCanvas, SortMode};
+pub use self::list::DrawList;
+
+pub mod batch;
+pub mod command;
+pub mod image_canvas;
+pub mod layered;
+pub mod list;
+mod state;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Color {
@@ -137,10 +154,39 @@ impl From<raylib4_sys::Color> for Color {
     }
 }
 
-pub trait Draw {
+mod sealed {
+    use super::{DrawCommand, DrawMode};
+
+    /// Prevents `Draw` from being implemented outside this crate
+    /// (so raylib drawing functions are only called while a frame or texture canvas is alive).
+    ///
+    /// The methods take a [`Token`] because supertrait methods can be called through `Draw` bounds:
+    /// only this crate can make one, so modes are only begun and ended by the mode canvases.
+    pub trait Sealed {
+        /// Submit a draw command (the drawing methods of `Draw` are implemented on top of this).
+        fn draw_command(&mut self, token: Token, command: DrawCommand<'_>);
+
+        /// Begin a drawing mode (called by the mode canvases such as `WithCamera`).
+        fn begin_mode(&mut self, token: Token, mode: &DrawMode);
+
+        /// End a drawing mode that has been begun by `begin_mode`.
+        fn end_mode(&mut self, token: Token, mode: &DrawMode);
+    }
+
+    /// Proof that a [`Sealed`] method is called from this crate.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Token;
+}
+
+use self::sealed::Token;
+
+/// Drawing methods of canvases.
+///
+/// This trait is sealed: it is implemented only by the canvases of this crate.
+pub trait Draw: sealed::Sealed {
     /// Set background color (framebuffer clear color).
     fn clear_background(&mut self, color: Color) {
-        unsafe { raylib4_sys::ClearBackground(color.into()) };
+        self.draw_command(Token, DrawCommand::ClearBackground { color });
     }
 
     /// Begin 2D mode with custom camera (2D).
@@ -158,10 +204,33 @@ pub trait Draw {
     where
         Self: Sized,
     {
-        BlendModeCanvas::new(self, mode)
+        BlendModeCanvas::new(self, DrawMode::Blend(mode))
+    }
+
+    /// Begin blending mode with custom blend factors and equations.
+    ///
+    /// On drop: End blending mode (reset to default: alpha blending)
+    fn begin_custom_blend_mode(&mut self, factors: BlendFactors) -> BlendModeCanvas<Self>
+    where
+        Self: Sized,
+    {
+        BlendModeCanvas::new(self, DrawMode::CustomBlend(factors))
+    }
+
+    /// Begin custom shader drawing.
+    ///
+    /// On drop: End custom shader drawing (use default shader).
+    fn begin_shader_mode(&mut self, shader: &Shader) -> ShaderModeCanvas<Self>
+    where
+        Self: Sized,
+    {
+        ShaderModeCanvas::new(self, shader)
     }
 
     /// Begin scissor mode (define screen area for following drawing).
+    ///
+    /// Nested scissor areas are intersected with the enclosing area,
+    /// and the enclosing area is restored on drop (so are nested cameras, blend modes and shaders).
     fn begin_scissor_mode(&mut self, position: Position, size: Size) -> ScissorModeCanvas<Self>
     where
         Self: Sized,
@@ -171,22 +240,38 @@ pub trait Draw {
 
     /// Draw a pixel.
     fn draw_pixel(&mut self, position: Position, color: Color) {
-        unsafe { raylib4_sys::DrawPixelV(position.into(), color.into()) };
+        self.draw_command(Token, DrawCommand::Pixel { position, color });
     }
 
     /// Draw a line.
     fn draw_line(&mut self, start: Position, end: Position, color: Color) {
-        unsafe { raylib4_sys::DrawLineV(start.into(), end.into(), color.into()) };
+        self.draw_command(Token, DrawCommand::Line { start, end, color });
     }
 
     /// Draw a line defining thickness.
     fn draw_line_ex(&mut self, start: Position, end: Position, thick: f32, color: Color) {
-        unsafe { raylib4_sys::DrawLineEx(start.into(), end.into(), thick, color.into()) };
+        self.draw_command(
+            Token,
+            DrawCommand::LineEx {
+                start,
+                end,
+                thick,
+                color,
+            },
+        );
     }
 
     /// Draw a line using cubic-bezier curves in-out.
     fn draw_line_bezier(&mut self, start: Position, end: Position, thick: f32, color: Color) {
-        unsafe { raylib4_sys::DrawLineBezier(start.into(), end.into(), thick, color.into()) };
+        self.draw_command(
+            Token,
+            DrawCommand::LineBezier {
+                start,
+                end,
+                thick,
+                color,
+            },
+        );
     }
 
     /// Draw line using quadratic bezier curves with a control point.
@@ -198,15 +283,16 @@ pub trait Draw {
         thick: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawLineBezierQuad(
-                start.into(),
-                end.into(),
-                control.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::LineBezierQuad {
+                start,
+                end,
+                control,
                 thick,
-                color.into(),
-            )
-        };
+                color,
+            },
+        );
     }
 
     /// Draw line using cubic bezier curves with 2 control points.
@@ -219,29 +305,35 @@ pub trait Draw {
         thick: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawLineBezierCubic(
-                start.into(),
-                end.into(),
-                start_control.into(),
-                end_control.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
                 thick,
-                color.into(),
-            )
-        };
+                color,
+            },
+        );
     }
 
     /// Draw lines sequence.
     fn draw_line_strip(&mut self, points: impl Iterator<Item = Position>, color: Color) {
-        let mut points = points.map(raylib4_sys::Vector2::from).collect::<Vec<_>>();
-        unsafe {
-            raylib4_sys::DrawLineStrip(points.as_mut_ptr(), points.len() as c_int, color.into())
-        };
+        let points = points.collect();
+        self.draw_command(Token, DrawCommand::LineStrip { points, color });
     }
 
     /// Draw a color-filled circle.
     fn draw_circle(&mut self, center: Position, radius: f32, color: Color) {
-        unsafe { raylib4_sys::DrawCircleV(center.into(), radius, color.into()) };
+        self.draw_command(
+            Token,
+            DrawCommand::Circle {
+                center,
+                radius,
+                color,
+            },
+        );
     }
 
     /// Draw a piece of a circle.
@@ -254,16 +346,17 @@ pub trait Draw {
         segments: usize,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawCircleSector(
-                center.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::CircleSector {
+                center,
                 radius,
                 start_angle,
                 end_angle,
-                segments as c_int,
-                color.into(),
-            );
-        }
+                segments,
+                color,
+            },
+        );
     }
 
     /// Draw circle sector outline.
@@ -276,16 +369,17 @@ pub trait Draw {
         segments: usize,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawCircleSectorLines(
-                center.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::CircleSectorLines {
+                center,
                 radius,
                 start_angle,
                 end_angle,
-                segments as c_int,
-                color.into(),
-            );
-        }
+                segments,
+                color,
+            },
+        );
     }
 
     /// Draw a gradient-filled circle.
@@ -296,48 +390,53 @@ pub trait Draw {
         color1: Color,
         color2: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawCircleGradient(
-                center.x as c_int,
-                center.y as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::CircleGradient {
+                center,
                 radius,
-                color1.into(),
-                color2.into(),
-            );
-        }
+                color1,
+                color2,
+            },
+        );
     }
 
     /// Draw circle outline.
     fn draw_circle_lines(&mut self, center: Position, radius: f32, color: Color) {
-        unsafe {
-            raylib4_sys::DrawCircleLines(center.x as c_int, center.y as c_int, radius, color.into())
-        };
+        self.draw_command(
+            Token,
+            DrawCommand::CircleLines {
+                center,
+                radius,
+                color,
+            },
+        );
     }
 
     /// Draw ellipse.
     fn draw_ellipse(&mut self, center: Position, radius_h: f32, radius_v: f32, color: Color) {
-        unsafe {
-            raylib4_sys::DrawEllipse(
-                center.x as c_int,
-                center.y as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::Ellipse {
+                center,
                 radius_h,
                 radius_v,
-                color.into(),
-            )
-        };
+                color,
+            },
+        );
     }
 
     /// Draw ellipse outline.
     fn draw_ellipse_lines(&mut self, center: Position, radius_h: f32, radius_v: f32, color: Color) {
-        unsafe {
-            raylib4_sys::DrawEllipseLines(
-                center.x as c_int,
-                center.y as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::EllipseLines {
+                center,
                 radius_h,
                 radius_v,
-                color.into(),
-            )
-        };
+                color,
+            },
+        );
     }
 
     /// Draw ring.
@@ -351,17 +450,18 @@ pub trait Draw {
         segments: usize,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRing(
-                center.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::Ring {
+                center,
                 inner_radius,
                 outer_radius,
                 start_angle,
                 end_angle,
-                segments as c_int,
-                color.into(),
-            );
-        }
+                segments,
+                color,
+            },
+        );
     }
 
     /// Draw ring outline.
@@ -375,22 +475,23 @@ pub trait Draw {
         segments: usize,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRingLines(
-                center.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::RingLines {
+                center,
                 inner_radius,
                 outer_radius,
                 start_angle,
                 end_angle,
-                segments as c_int,
-                color.into(),
-            );
-        }
+                segments,
+                color,
+            },
+        );
     }
 
     /// Draw a color-filled rectangle.
     fn draw_rectangle(&mut self, rectangle: Rectangle, color: Color) {
-        unsafe { raylib4_sys::DrawRectangleRec(rectangle.into(), color.into()) };
+        self.draw_command(Token, DrawCommand::Rectangle { rectangle, color });
     }
 
     /// Draw a color-filled rectangle with pro parameters.
@@ -401,37 +502,39 @@ pub trait Draw {
         rotation: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRectanglePro(rectangle.into(), origin.into(), rotation, color.into());
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::RectanglePro {
+                rectangle,
+                origin,
+                rotation,
+                color,
+            },
+        );
     }
 
     /// Draw a vertical-gradient-filled rectangle.
     fn draw_rectangle_gradient_v(&mut self, rectangle: Rectangle, color1: Color, color2: Color) {
-        unsafe {
-            raylib4_sys::DrawRectangleGradientV(
-                rectangle.position.x,
-                rectangle.position.y,
-                rectangle.size.width as c_int,
-                rectangle.size.height as c_int,
-                color1.into(),
-                color2.into(),
-            );
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleGradientV {
+                rectangle,
+                color1,
+                color2,
+            },
+        );
     }
 
     /// Draw a horizontal-gradient-filled rectangle.
     fn draw_rectangle_gradient_h(&mut self, rectangle: Rectangle, color1: Color, color2: Color) {
-        unsafe {
-            raylib4_sys::DrawRectangleGradientH(
-                rectangle.position.x,
-                rectangle.position.y,
-                rectangle.size.width as c_int,
-                rectangle.size.height as c_int,
-                color1.into(),
-                color2.into(),
-            );
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleGradientH {
+                rectangle,
+                color1,
+                color2,
+            },
+        );
     }
 
     /// Draw a gradient-filled rectangle with custom vertex colors.
@@ -443,35 +546,33 @@ pub trait Draw {
         color3: Color,
         color4: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRectangleGradientEx(
-                rectangle.into(),
-                color1.into(),
-                color2.into(),
-                color3.into(),
-                color4.into(),
-            );
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleGradientEx {
+                rectangle,
+                color1,
+                color2,
+                color3,
+                color4,
+            },
+        );
     }
 
     /// Draw rectangle outline.
     fn draw_rectangle_lines(&mut self, rectangle: Rectangle, color: Color) {
-        unsafe {
-            raylib4_sys::DrawRectangleLines(
-                rectangle.position.x,
-                rectangle.position.y,
-                rectangle.size.width as c_int,
-                rectangle.size.height as c_int,
-                color.into(),
-            );
-        }
+        self.draw_command(Token, DrawCommand::RectangleLines { rectangle, color });
     }
 
     /// Draw rectangle outline with extended parameters.
     fn draw_rectangle_lines_ex(&mut self, rectangle: Rectangle, line_thick: f32, color: Color) {
-        unsafe {
-            raylib4_sys::DrawRectangleLinesEx(rectangle.into(), line_thick, color.into());
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleLinesEx {
+                rectangle,
+                line_thick,
+                color,
+            },
+        );
     }
 
     /// Draw rectangle with rounded edges.
@@ -482,14 +583,15 @@ pub trait Draw {
         segments: usize,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRectangleRounded(
-                rectangle.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleRounded {
+                rectangle,
                 roundness,
-                segments as c_int,
-                color.into(),
-            );
-        }
+                segments,
+                color,
+            },
+        );
     }
 
     /// Draw rectangle with rounded edges outline.
@@ -501,49 +603,38 @@ pub trait Draw {
         line_thick: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawRectangleRoundedLines(
-                rectangle.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::RectangleRoundedLines {
+                rectangle,
                 roundness,
-                segments as c_int,
+                segments,
                 line_thick,
-                color.into(),
-            );
-        }
+                color,
+            },
+        );
     }
 
     /// Draw a color-filled triangle (vertex in counter-clockwise order!).
     fn draw_triangle(&mut self, v1: Position, v2: Position, v3: Position, color: Color) {
-        unsafe {
-            raylib4_sys::DrawTriangle(v1.into(), v2.into(), v3.into(), color.into());
-        }
+        self.draw_command(Token, DrawCommand::Triangle { v1, v2, v3, color });
     }
 
     /// Draw triangle outline (vertex in counter-clockwise order!).
     fn draw_triangle_lines(&mut self, v1: Position, v2: Position, v3: Position, color: Color) {
-        unsafe {
-            raylib4_sys::DrawTriangleLines(v1.into(), v2.into(), v3.into(), color.into());
-        }
+        self.draw_command(Token, DrawCommand::TriangleLines { v1, v2, v3, color });
     }
 
     /// Draw a triangle fan defined by points (first vertex is the center).
     fn draw_triangle_fan(&mut self, points: impl Iterator<Item = Position>, color: Color) {
-        let mut points = points.map(raylib4_sys::Vector2::from).collect::<Vec<_>>();
-        unsafe {
-            raylib4_sys::DrawTriangleFan(points.as_mut_ptr(), points.len() as c_int, color.into());
-        }
+        let points = points.collect();
+        self.draw_command(Token, DrawCommand::TriangleFan { points, color });
     }
 
     /// Draw a triangle strip defined by points.
     fn draw_triangle_strip(&mut self, points: impl Iterator<Item = Position>, color: Color) {
-        let mut points = points.map(raylib4_sys::Vector2::from).collect::<Vec<_>>();
-        unsafe {
-            raylib4_sys::DrawTriangleStrip(
-                points.as_mut_ptr(),
-                points.len() as c_int,
-                color.into(),
-            );
-        }
+        let points = points.collect();
+        self.draw_command(Token, DrawCommand::TriangleStrip { points, color });
     }
 
     /// Draw a regular polygon (Vector version).
@@ -555,15 +646,16 @@ pub trait Draw {
         rotation: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawPoly(
-                center.into(),
-                sides as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::Poly {
+                center,
+                sides,
                 radius,
                 rotation,
-                color.into(),
-            );
-        }
+                color,
+            },
+        );
     }
 
     /// Draw a polygon outline of n sides.
@@ -575,15 +667,16 @@ pub trait Draw {
         rotation: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawPolyLines(
-                center.into(),
-                sides as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::PolyLines {
+                center,
+                sides,
                 radius,
                 rotation,
-                color.into(),
-            );
-        }
+                color,
+            },
+        );
     }
 
     /// Draw a polygon outline of n sides with extended parameters.
@@ -596,21 +689,22 @@ pub trait Draw {
         line_thick: f32,
         color: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawPolyLinesEx(
-                center.into(),
-                sides as c_int,
+        self.draw_command(
+            Token,
+            DrawCommand::PolyLinesEx {
+                center,
+                sides,
                 radius,
                 rotation,
                 line_thick,
-                color.into(),
-            );
-        }
+                color,
+            },
+        );
     }
 
     /// Draw current FPS.
     fn draw_fps(&mut self, position: Position) {
-        unsafe { raylib4_sys::DrawFPS(position.x as c_int, position.y as c_int) };
+        self.draw_command(Token, DrawCommand::Fps { position });
     }
 
     /// Draw text (using default font).
@@ -621,17 +715,17 @@ pub trait Draw {
         font_size: usize,
         color: Color,
     ) -> Result<(), std::ffi::NulError> {
-        let text = CString::new(text)?;
-        unsafe {
-            raylib4_sys::DrawText(
-                text.as_ptr(),
-                position.x as c_int,
-                position.y as c_int,
-                font_size as c_int,
-                color.into(),
-            );
-        }
-        Ok(())
+        with_c_str(text, |text| {
+            self.draw_command(
+                Token,
+                DrawCommand::Text {
+                    text: Cow::Borrowed(text),
+                    position,
+                    font_size,
+                    color,
+                },
+            )
+        })
     }
 
     /// Draw text using font and additional parameters.
@@ -644,18 +738,19 @@ pub trait Draw {
         spacing: f32,
         tint: Color,
     ) -> Result<(), std::ffi::NulError> {
-        let text = CString::new(text)?;
-        unsafe {
-            raylib4_sys::DrawTextEx(
-                font.0,
-                text.as_ptr(),
-                position.into(),
-                font_size,
-                spacing,
-                tint.into(),
-            );
-        }
-        Ok(())
+        with_c_str(text, |text| {
+            self.draw_command(
+                Token,
+                DrawCommand::TextEx {
+                    font: Cow::Borrowed(font),
+                    text: Cow::Borrowed(text),
+                    position,
+                    font_size,
+                    spacing,
+                    tint,
+                },
+            )
+        })
     }
 
     /// Draw text using Font and pro parameters (rotation).
@@ -670,38 +765,47 @@ pub trait Draw {
         spacing: f32,
         tint: Color,
     ) -> Result<(), std::ffi::NulError> {
-        let text = CString::new(text)?;
-        unsafe {
-            raylib4_sys::DrawTextPro(
-                font.0,
-                text.as_ptr(),
-                position.into(),
-                origin.into(),
-                rotation,
-                font_size,
-                spacing,
-                tint.into(),
-            );
-        }
-        Ok(())
+        with_c_str(text, |text| {
+            self.draw_command(
+                Token,
+                DrawCommand::TextPro {
+                    font: Cow::Borrowed(font),
+                    text: Cow::Borrowed(text),
+                    position,
+                    origin,
+                    rotation,
+                    font_size,
+                    spacing,
+                    tint,
+                },
+            )
+        })
     }
 
     /// Draw one character (codepoint).
     fn draw_char(&mut self, font: &Font, c: char, position: Position, font_size: f32, tint: Color) {
-        unsafe {
-            raylib4_sys::DrawTextCodepoint(
-                font.0,
-                u32::from(c) as c_int,
-                position.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::Char {
+                font: Cow::Borrowed(font),
+                c,
+                position,
                 font_size,
-                tint.into(),
-            );
-        }
+                tint,
+            },
+        );
     }
 
     /// Draw a Texture2D.
     fn draw_texture(&mut self, texture: &Texture, position: Position, tint: Color) {
-        unsafe { raylib4_sys::DrawTextureV(texture.0, position.into(), tint.into()) };
+        self.draw_command(
+            Token,
+            DrawCommand::Texture {
+                texture: Cow::Borrowed(texture),
+                position,
+                tint,
+            },
+        );
     }
 
     /// Draw a Texture2D with extended parameters.
@@ -713,9 +817,16 @@ pub trait Draw {
         scale: f32,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTextureEx(texture.0, position.into(), rotation, scale, tint.into())
-        };
+        self.draw_command(
+            Token,
+            DrawCommand::TextureEx {
+                texture: Cow::Borrowed(texture),
+                position,
+                rotation,
+                scale,
+                tint,
+            },
+        );
     }
 
     /// Draw a part of a texture defined by a rectangle.
@@ -726,9 +837,15 @@ pub trait Draw {
         position: Position,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTextureRec(texture.0, source.into(), position.into(), tint.into());
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::TextureRec {
+                texture: Cow::Borrowed(texture),
+                source,
+                position,
+                tint,
+            },
+        );
     }
 
     /// Draw texture quad with tiling and offset parameters.
@@ -740,15 +857,16 @@ pub trait Draw {
         quad: Rectangle,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTextureQuad(
-                texture.0,
-                tiling.into(),
-                offset.into(),
-                quad.into(),
-                tint.into(),
-            );
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::TextureQuad {
+                texture: Cow::Borrowed(texture),
+                tiling,
+                offset,
+                quad,
+                tint,
+            },
+        );
     }
 
     /// Draw part of a texture (defined by a rectangle) with rotation and scale tiled into dest.
@@ -762,17 +880,18 @@ pub trait Draw {
         scale: f32,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTextureTiled(
-                texture.0,
-                source.into(),
-                dest.into(),
-                origin.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::TextureTiled {
+                texture: Cow::Borrowed(texture),
+                source,
+                dest,
+                origin,
                 rotation,
                 scale,
-                tint.into(),
-            );
-        }
+                tint,
+            },
+        );
     }
 
     /// Draw a part of a texture defined by a rectangle with 'pro' parameters.
@@ -785,16 +904,31 @@ pub trait Draw {
         rotation: f32,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTexturePro(
-                texture.0,
-                source.into(),
-                dest.into(),
-                origin.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::TexturePro {
+                texture: Cow::Borrowed(texture),
+                source,
+                dest,
+                origin,
                 rotation,
-                tint.into(),
-            );
-        }
+                tint,
+            },
+        );
+    }
+
+    /// Draw many sprites of a texture (or texture atlas) in as few GPU batches as possible.
+    ///
+    /// By default the instances are copied into a [`DrawCommand::Sprites`] command
+    /// (immediate canvases emit them directly instead).
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        self.draw_command(
+            Token,
+            DrawCommand::Sprites {
+                texture: Cow::Borrowed(texture),
+                instances: Arc::new(instances.to_vec()),
+            },
+        );
     }
 
     /// Draws a texture (or part of it) that stretches or shrinks nicely.
@@ -807,16 +941,17 @@ pub trait Draw {
         rotation: f32,
         tint: Color,
     ) {
-        unsafe {
-            raylib4_sys::DrawTextureNPatch(
-                texture.0,
-                info.into(),
-                dest.into(),
-                origin.into(),
+        self.draw_command(
+            Token,
+            DrawCommand::TextureNpatch {
+                texture: Cow::Borrowed(texture),
+                info,
+                dest,
+                origin,
                 rotation,
-                tint.into(),
-            );
-        }
+                tint,
+            },
+        );
     }
 
     /// Draw a textured polygon.
@@ -828,73 +963,142 @@ pub trait Draw {
         texcoord: &[Position],
         tint: Color,
     ) {
-        let mut points = points
-            .iter()
-            .copied()
-            .map(raylib4_sys::Vector2::from)
-            .collect::<Vec<_>>();
-        let mut texcoord = texcoord
-            .iter()
-            .copied()
-            .map(raylib4_sys::Vector2::from)
-            .collect::<Vec<_>>();
-        unsafe {
-            raylib4_sys::DrawTexturePoly(
-                texture.0,
-                centor.into(),
-                points.as_mut_ptr(),
-                texcoord.as_mut_ptr(),
-                points.len() as c_int,
-                tint.into(),
-            );
-        }
+        self.draw_command(
+            Token,
+            DrawCommand::TexturePoly {
+                texture: Cow::Borrowed(texture),
+                center: centor,
+                points: Cow::Borrowed(points),
+                texcoord: Cow::Borrowed(texcoord),
+                tint,
+            },
+        );
     }
 }
 
+/// Call `f` with `text` as a C string (converted in a per-thread buffer instead of a new `CString` per draw).
+fn with_c_str(text: &str, f: impl FnOnce(&CStr)) -> Result<(), NulError> {
+    thread_local! {
+        static BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+    if text.contains('\0') {
+        // Only `CString::new` makes a `NulError`.
+        return CString::new(text).map(drop);
+    }
+    BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut buffer) => {
+            buffer.clear();
+            buffer.extend_from_slice(text.as_bytes());
+            buffer.push(0);
+            f(CStr::from_bytes_with_nul(&buffer).expect("unreachable"));
+        }
+        // Called from `f`.
+        Err(_) => f(&CString::new(text).expect("unreachable")),
+    });
+    Ok(())
+}
+
 // CustomCameraCanvas
 #[derive(Debug)]
-pub struct WithCamera<'a, T> {
-    #[allow(dead_code)]
-    canvas: &'a T,
+pub struct WithCamera<'a, T: Draw> {
+    canvas: &'a mut T,
+    mode: DrawMode,
+}
+
+impl<'a, T: Draw> WithCamera<'a, T> {
+    fn new(canvas: &'a mut T, camera: Camera) -> Self {
+        let mode = DrawMode::Camera(camera);
+        canvas.begin_mode(Token, &mode);
+        Self { canvas, mode }
+    }
 }
 
-impl<'a, T> WithCamera<'a, T> {
-    fn new(canvas: &'a T, camera: Camera) -> Self {
-        unsafe { raylib4_sys::BeginMode2D(camera.into()) };
-        Self { canvas }
+impl<'a, T: Draw> sealed::Sealed for WithCamera<'a, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.canvas.draw_command(Token, command);
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.canvas.begin_mode(Token, mode);
+    }
+
+    fn end_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.canvas.end_mode(Token, mode);
     }
 }
 
-impl<'a, T: Draw> Draw for WithCamera<'a, T> {}
+impl<'a, T: Draw> Draw for WithCamera<'a, T> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        self.canvas.draw_sprites(texture, instances);
+    }
+}
 
-impl<'a, T> Drop for WithCamera<'a, T> {
+impl<'a, T: Draw> Drop for WithCamera<'a, T> {
     fn drop(&mut self) {
-        unsafe { raylib4_sys::EndMode2D() };
+        self.canvas.end_mode(Token, &self.mode);
     }
 }
 
 #[derive(Debug)]
 pub struct WindowCanvas<'a> {
-    #[allow(dead_code)]
-    system: &'a crate::System,
+    system: &'a mut crate::System,
 }
 
 impl<'a> WindowCanvas<'a> {
-    pub(crate) fn new(system: &'a crate::System) -> Self {
+    pub(crate) fn new(system: &'a mut crate::System) -> Self {
         unsafe { raylib4_sys::BeginDrawing() };
+        state::push_target();
         Self { system }
     }
+
+    pub(crate) fn system(&self) -> &crate::System {
+        self.system
+    }
+
+    /// Begin drawing to render texture.
+    ///
+    /// The window canvas is mutably borrowed while the texture canvas is alive,
+    /// so texture modes cannot be nested.
+    ///
+    /// On drop: Ends drawing to render texture.
+    pub fn begin_texture_mode<'b>(
+        &mut self,
+        target: &'b mut RenderTexture,
+    ) -> TextureCanvas<'_, 'b, Self> {
+        TextureCanvas::new(self, target)
+    }
 }
 
-impl<'a> Draw for WindowCanvas<'a> {}
+impl<'a> sealed::Sealed for WindowCanvas<'a> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        command.execute();
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        state::begin_mode(mode);
+    }
+
+    fn end_mode(&mut self, _: Token, _mode: &DrawMode) {
+        state::end_mode();
+    }
+}
+
+impl<'a> Draw for WindowCanvas<'a> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        batch::draw_sprites(texture, instances);
+    }
+}
 
 impl<'a> Drop for WindowCanvas<'a> {
     fn drop(&mut self) {
+        state::pop_target();
+        self.system.capture_frame();
         unsafe { raylib4_sys::EndDrawing() };
+        self.system.end_frame();
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Camera {
     pub offset: Position,
     pub target: Position,
@@ -925,31 +1129,74 @@ impl From<Camera> for raylib4_sys::Camera2D {
     }
 }
 
+/// Canvas to draw to a render texture.
+///
+/// `T` is the type of the parent (i.e., [`WindowCanvas`] or [`System`](crate::System) for offscreen rendering outside frames).
+/// Both the parent and the target are mutably borrowed, so neither can be used for drawing while this canvas is alive.
+///
+/// The target texture can still be reached through clones of its [`Texture`] or recorded [`DrawList`]s;
+/// commands that would draw the target into itself are skipped (with a warning).
 #[derive(Debug)]
 pub struct TextureCanvas<'a, 'b, T> {
     #[allow(dead_code)]
-    parent: &'a T,
-    #[allow(dead_code)]
-    target: &'b RenderTexture,
+    parent: &'a mut T,
+    target: &'b mut RenderTexture,
 }
 
 impl<'a, 'b, T> TextureCanvas<'a, 'b, T> {
-    pub(crate) fn new(parent: &'a T, target: &'b RenderTexture) -> Self {
+    pub(crate) fn new(parent: &'a mut T, target: &'b mut RenderTexture) -> Self {
+        state::push_target();
         unsafe { raylib4_sys::BeginTextureMode(target.0) };
         Self { parent, target }
     }
+
+    /// Check if drawing `texture` would read from the target while it is being drawn to.
+    fn is_target(&self, texture: &Texture) -> bool {
+        static WARN_TARGET: Once = Once::new();
+        let is_target = texture.to_raw().id == self.target.texture().to_raw().id;
+        if is_target {
+            WARN_TARGET.call_once(|| {
+                log::warn!("a render texture cannot be drawn into itself (the drawing is skipped)");
+            });
+        }
+        is_target
+    }
 }
 
-impl<'a, 'b, T> Draw for TextureCanvas<'a, 'b, T> {}
+impl<'a, 'b, T> sealed::Sealed for TextureCanvas<'a, 'b, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        if !matches!(command.texture(), Some(texture) if self.is_target(texture)) {
+            command.execute();
+        }
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        state::begin_mode(mode);
+    }
+
+    fn end_mode(&mut self, _: Token, _mode: &DrawMode) {
+        state::end_mode();
+    }
+}
+
+impl<'a, 'b, T> Draw for TextureCanvas<'a, 'b, T> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        if !self.is_target(texture) {
+            batch::draw_sprites(texture, instances);
+        }
+    }
+}
 
 impl<'a, 'b, T> Drop for TextureCanvas<'a, 'b, T> {
     fn drop(&mut self) {
         unsafe { raylib4_sys::EndTextureMode() };
+        state::pop_target();
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum BlendMode {
+    #[default]
     Alpha,
     Additive,
     Multiplied,
@@ -958,14 +1205,8 @@ pub enum BlendMode {
     Custom,
 }
 
-impl Default for BlendMode {
-    fn default() -> Self {
-        Self::Alpha
-    }
-}
-
 impl BlendMode {
-    fn to_raw_value(self) -> raylib4_sys::BlendMode {
+    pub(crate) fn to_raw_value(self) -> raylib4_sys::BlendMode {
         match self {
             Self::Alpha => 0,
             Self::Additive => 1,
@@ -977,51 +1218,274 @@ impl BlendMode {
     }
 }
 
-#[derive(Debug)]
-pub struct BlendModeCanvas<'a, T> {
-    #[allow(dead_code)]
-    parent: &'a T,
+/// OpenGL blend factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlphaSaturate,
 }
 
-impl<'a, T> BlendModeCanvas<'a, T> {
-    pub(crate) fn new(parent: &'a T, mode: BlendMode) -> Self {
-        unsafe { raylib4_sys::BeginBlendMode(mode.to_raw_value() as c_int) };
-        Self { parent }
+impl BlendFactor {
+    pub(crate) fn to_raw_value(self) -> c_int {
+        match self {
+            Self::Zero => 0,
+            Self::One => 1,
+            Self::SrcColor => 0x0300,
+            Self::OneMinusSrcColor => 0x0301,
+            Self::SrcAlpha => 0x0302,
+            Self::OneMinusSrcAlpha => 0x0303,
+            Self::DstAlpha => 0x0304,
+            Self::OneMinusDstAlpha => 0x0305,
+            Self::DstColor => 0x0306,
+            Self::OneMinusDstColor => 0x0307,
+            Self::SrcAlphaSaturate => 0x0308,
+        }
     }
 }
 
-impl<'a, T: Draw> Draw for BlendModeCanvas<'a, T> {}
+/// OpenGL blend equation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendEquation {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
 
-impl<'a, T> Drop for BlendModeCanvas<'a, T> {
-    fn drop(&mut self) {
-        unsafe { raylib4_sys::EndBlendMode() };
+impl BlendEquation {
+    pub(crate) fn to_raw_value(self) -> c_int {
+        match self {
+            Self::Add => 0x8006,
+            Self::Subtract => 0x800A,
+            Self::ReverseSubtract => 0x800B,
+            Self::Min => 0x8007,
+            Self::Max => 0x8008,
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct ScissorModeCanvas<'a, T> {
-    #[allow(dead_code)]
-    parent: &'a T,
+/// Blend factors and equations for [`Draw::begin_custom_blend_mode`].
+///
+/// NOTE: Separate alpha factors and equations are not supported by raylib 4.0, which has a single set of factors for all channels.
+/// If they differ from the RGB ones, the RGB ones are used for alpha too (a warning is logged once).
+/// [`ImageCanvas`] applies them as given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlendFactors {
+    pub src_rgb: BlendFactor,
+    pub dst_rgb: BlendFactor,
+    pub src_alpha: BlendFactor,
+    pub dst_alpha: BlendFactor,
+    pub equation_rgb: BlendEquation,
+    pub equation_alpha: BlendEquation,
 }
 
-impl<'a, T> ScissorModeCanvas<'a, T> {
-    pub(crate) fn new(parent: &'a T, position: Position, size: Size) -> Self {
+impl BlendFactors {
+    /// Blending for textures whose colors are premultiplied by alpha (`src + dst * (1 - src_alpha)`).
+    pub const PREMULTIPLIED_ALPHA: Self = Self::new(
+        BlendFactor::One,
+        BlendFactor::OneMinusSrcAlpha,
+        BlendEquation::Add,
+    );
+
+    /// Screen blending (`src + dst * (1 - src)`), brightens the destination (e.g., for light sources).
+    pub const SCREEN: Self = Self::new(
+        BlendFactor::One,
+        BlendFactor::OneMinusSrcColor,
+        BlendEquation::Add,
+    );
+
+    /// Multiply blending weighted by the source alpha (`src * dst + dst * (1 - src_alpha)`), e.g., for shadows and light maps.
+    pub const MULTIPLY_WITH_ALPHA: Self = Self::new(
+        BlendFactor::DstColor,
+        BlendFactor::OneMinusSrcAlpha,
+        BlendEquation::Add,
+    );
+
+    /// Make blend factors that apply the same factors and equation to RGB and alpha.
+    pub const fn new(src: BlendFactor, dst: BlendFactor, equation: BlendEquation) -> Self {
+        Self {
+            src_rgb: src,
+            dst_rgb: dst,
+            src_alpha: src,
+            dst_alpha: dst,
+            equation_rgb: equation,
+            equation_alpha: equation,
+        }
+    }
+
+    fn is_separate(&self) -> bool {
+        self.src_rgb != self.src_alpha
+            || self.dst_rgb != self.dst_alpha
+            || self.equation_rgb != self.equation_alpha
+    }
+
+    pub(crate) fn apply(&self) {
+        static WARN_SEPARATE: Once = Once::new();
+        if self.is_separate() {
+            WARN_SEPARATE.call_once(|| {
+                log::warn!(
+                    "separate alpha blend factors are not supported by raylib 4.0 (RGB factors are used): {:?}",
+                    self
+                );
+            });
+        }
         unsafe {
-            raylib4_sys::BeginScissorMode(
-                position.x as c_int,
-                position.y as c_int,
-                size.width as c_int,
-                size.height as c_int,
+            rlSetBlendFactors(
+                self.src_rgb.to_raw_value(),
+                self.dst_rgb.to_raw_value(),
+                self.equation_rgb.to_raw_value(),
             )
         };
-        Self { parent }
     }
 }
 
-impl<'a, T: Draw> Draw for ScissorModeCanvas<'a, T> {}
+extern "C" {
+    // rlgl.h (not included in the raylib4-sys bindings).
+    fn rlSetBlendFactors(gl_src_factor: c_int, gl_dst_factor: c_int, gl_equation: c_int);
+}
+
+#[derive(Debug)]
+pub struct BlendModeCanvas<'a, T: Draw> {
+    parent: &'a mut T,
+    mode: DrawMode,
+}
+
+impl<'a, T: Draw> BlendModeCanvas<'a, T> {
+    fn new(parent: &'a mut T, mode: DrawMode) -> Self {
+        parent.begin_mode(Token, &mode);
+        Self { parent, mode }
+    }
+}
+
+impl<'a, T: Draw> sealed::Sealed for BlendModeCanvas<'a, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.parent.draw_command(Token, command);
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.begin_mode(Token, mode);
+    }
+
+    fn end_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.end_mode(Token, mode);
+    }
+}
 
-impl<'a, T> Drop for ScissorModeCanvas<'a, T> {
+impl<'a, T: Draw> Draw for BlendModeCanvas<'a, T> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        self.parent.draw_sprites(texture, instances);
+    }
+}
+
+impl<'a, T: Draw> Drop for BlendModeCanvas<'a, T> {
     fn drop(&mut self) {
-        unsafe { raylib4_sys::EndScissorMode() };
+        self.parent.end_mode(Token, &self.mode);
+    }
+}
+
+#[derive(Debug)]
+pub struct ShaderModeCanvas<'a, T: Draw> {
+    parent: &'a mut T,
+    mode: DrawMode,
+}
+
+impl<'a, T: Draw> ShaderModeCanvas<'a, T> {
+    pub(crate) fn new(parent: &'a mut T, shader: &Shader) -> Self {
+        let mode = DrawMode::Shader(shader.clone());
+        parent.begin_mode(Token, &mode);
+        Self { parent, mode }
+    }
+}
+
+impl<'a, T: Draw> sealed::Sealed for ShaderModeCanvas<'a, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.parent.draw_command(Token, command);
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.begin_mode(Token, mode);
+    }
+
+    fn end_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.end_mode(Token, mode);
+    }
+}
+
+impl<'a, T: Draw> Draw for ShaderModeCanvas<'a, T> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        self.parent.draw_sprites(texture, instances);
+    }
+}
+
+impl<'a, T: Draw> Drop for ShaderModeCanvas<'a, T> {
+    fn drop(&mut self) {
+        self.parent.end_mode(Token, &self.mode);
+    }
+}
+
+#[derive(Debug)]
+pub struct ScissorModeCanvas<'a, T: Draw> {
+    parent: &'a mut T,
+    mode: DrawMode,
+}
+
+impl<'a, T: Draw> ScissorModeCanvas<'a, T> {
+    pub(crate) fn new(parent: &'a mut T, position: Position, size: Size) -> Self {
+        let mode = DrawMode::Scissor(Rectangle { position, size });
+        parent.begin_mode(Token, &mode);
+        Self { parent, mode }
+    }
+}
+
+impl<'a, T: Draw> sealed::Sealed for ScissorModeCanvas<'a, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.parent.draw_command(Token, command);
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.begin_mode(Token, mode);
+    }
+
+    fn end_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.parent.end_mode(Token, mode);
+    }
+}
+
+impl<'a, T: Draw> Draw for ScissorModeCanvas<'a, T> {
+    fn draw_sprites(&mut self, texture: &Texture, instances: &[SpriteInstance]) {
+        self.parent.draw_sprites(texture, instances);
+    }
+}
+
+impl<'a, T: Draw> Drop for ScissorModeCanvas<'a, T> {
+    fn drop(&mut self) {
+        self.parent.end_mode(Token, &self.mode);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c_str_conversion() {
+        let mut texts = Vec::new();
+        with_c_str("abc", |text| {
+            texts.push(text.to_bytes().to_vec());
+            with_c_str("nested", |text| texts.push(text.to_bytes().to_vec())).unwrap();
+        })
+        .unwrap();
+        assert_eq!(texts, [&b"abc"[..], b"nested"]);
+        assert!(with_c_str("a\0b", |_| unreachable!()).is_err());
     }
 }
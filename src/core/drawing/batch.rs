@@ -0,0 +1,231 @@
+//! Sprite batching.
+//!
+//! ```no_run
+//! use rfraylib::core::drawing::{SpriteBatch, SpriteInstance};
+//! use rfraylib::{Color, Draw, Position, Rectangle};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! let texture = rfraylib::texture::Texture::load("sprites.png").ok_or("failed to load texture")?;
+//!
+//! let mut batch = SpriteBatch::new(texture);
+//! for i in 0..10_000 {
+//!     let position = Position { x: i % 800, y: i / 800 * 16 };
+//!     let mut sprite = SpriteInstance::new(Rectangle::new(0, 0, 16, 16), position);
+//!     sprite.flip_x = i % 2 == 0;
+//!     batch.push(sprite);
+//! }
+//!
+//! while !system.window().should_close() {
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!     batch.draw(&mut canvas);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::sealed::Token;
+use super::{Color, Draw, DrawCommand};
+use crate::structs::Vector2;
+use crate::texture::Texture;
+use crate::{Position, Rectangle};
+use std::borrow::Cow;
+use std::os::raw::{c_int, c_uint};
+use std::sync::Arc;
+
+extern "C" {
+    // rlgl.h (not included in the raylib4-sys bindings).
+    fn rlCheckRenderBatchLimit(v_count: c_int) -> bool;
+    fn rlSetTexture(id: c_uint);
+    fn rlBegin(mode: c_int);
+    fn rlEnd();
+    fn rlColor4ub(r: u8, g: u8, b: u8, a: u8);
+    fn rlNormal3f(x: f32, y: f32, z: f32);
+    fn rlTexCoord2f(x: f32, y: f32);
+    fn rlVertex2f(x: f32, y: f32);
+}
+
+const RL_QUADS: c_int = 0x0007;
+
+// Small enough to fit in the default render batch (8192 quads).
+const QUADS_PER_CHUNK: usize = 1024;
+
+/// Sprite drawn by [`SpriteBatch`].
+#[derive(Debug, Clone, Copy)]
+pub struct SpriteInstance {
+    /// Part of the texture to draw (negative width / height flips the sprite as in [`Draw::draw_texture_pro`]).
+    pub source: Rectangle,
+
+    /// Position of the origin on the screen.
+    pub position: Position,
+
+    /// Rotation and scaling origin (in source pixels, relative to the top-left corner of the sprite).
+    pub origin: Position,
+
+    /// Rotation in degrees (clockwise).
+    pub rotation: f32,
+
+    pub scale: Vector2,
+    pub tint: Color,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl SpriteInstance {
+    /// Make an unrotated, unscaled and untinted sprite whose origin is the top-left corner.
+    pub fn new(source: Rectangle, position: Position) -> Self {
+        Self {
+            source,
+            position,
+            origin: Position { x: 0, y: 0 },
+            rotation: 0.0,
+            scale: Vector2::new(1.0, 1.0),
+            tint: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    fn bottom(&self) -> i32 {
+        let height = self.source.size.height.abs() as f32 * self.scale.y();
+        let origin = self.origin.y as f32 * self.scale.y();
+        self.position.y + (height - origin) as i32
+    }
+}
+
+/// Texture (or texture atlas) with sprite instances that are drawn in as few GPU batches as possible.
+///
+/// Instances are shared with the [`DrawCommand`]s made by [`SpriteBatch::draw`]
+/// (i.e., drawing a batch onto a [`DrawList`](super::DrawList) does not copy the instances unless they are modified later).
+#[derive(Debug, Clone)]
+pub struct SpriteBatch {
+    texture: Texture,
+    instances: Arc<Vec<SpriteInstance>>,
+}
+
+impl SpriteBatch {
+    pub fn new(texture: Texture) -> Self {
+        Self {
+            texture,
+            instances: Arc::default(),
+        }
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    pub fn instances(&self) -> &[SpriteInstance] {
+        &self.instances
+    }
+
+    pub fn instances_mut(&mut self) -> &mut Vec<SpriteInstance> {
+        Arc::make_mut(&mut self.instances)
+    }
+
+    pub fn push(&mut self, instance: SpriteInstance) {
+        self.instances_mut().push(instance);
+    }
+
+    pub fn extend_from_slice(&mut self, instances: &[SpriteInstance]) {
+        self.instances_mut().extend_from_slice(instances);
+    }
+
+    pub fn clear(&mut self) {
+        self.instances_mut().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Draw all instances onto `canvas`.
+    pub fn draw<T: Draw>(&self, canvas: &mut T) {
+        canvas.draw_command(
+            Token,
+            DrawCommand::Sprites {
+                texture: Cow::Borrowed(&self.texture),
+                instances: Arc::clone(&self.instances),
+            },
+        );
+    }
+}
+
+/// Emit the quads of `instances` to the current render batch.
+pub(crate) fn draw_sprites(texture: &Texture, instances: &[SpriteInstance]) {
+    let raw = texture.to_raw();
+    if raw.id == 0 {
+        return;
+    }
+    let (tex_width, tex_height) = (raw.width as f32, raw.height as f32);
+
+    for chunk in instances.chunks(QUADS_PER_CHUNK) {
+        unsafe {
+            rlCheckRenderBatchLimit((chunk.len() * 4) as c_int);
+            rlSetTexture(raw.id);
+            rlBegin(RL_QUADS);
+            rlNormal3f(0.0, 0.0, 1.0);
+        }
+        for sprite in chunk {
+            let source = sprite.source;
+            let width = source.size.width.abs() as f32;
+            let height = source.size.height.abs() as f32;
+
+            let mut u0 = source.position.x as f32 / tex_width;
+            let mut u1 = (source.position.x as f32 + width) / tex_width;
+            if (source.size.width < 0) != sprite.flip_x {
+                std::mem::swap(&mut u0, &mut u1);
+            }
+            let mut v0 = source.position.y as f32 / tex_height;
+            let mut v1 = (source.position.y as f32 + height) / tex_height;
+            if (source.size.height < 0) != sprite.flip_y {
+                std::mem::swap(&mut v0, &mut v1);
+            }
+
+            let (sx, sy) = (sprite.scale.x(), sprite.scale.y());
+            let left = -sprite.origin.x as f32 * sx;
+            let top = -sprite.origin.y as f32 * sy;
+            let right = left + width * sx;
+            let bottom = top + height * sy;
+            let (sin, cos) = sprite.rotation.to_radians().sin_cos();
+            let (px, py) = (sprite.position.x as f32, sprite.position.y as f32);
+            let vertex = |x: f32, y: f32| (px + x * cos - y * sin, py + x * sin + y * cos);
+
+            let corners = [
+                (vertex(left, top), (u0, v0)),
+                (vertex(left, bottom), (u0, v1)),
+                (vertex(right, bottom), (u1, v1)),
+                (vertex(right, top), (u1, v0)),
+            ];
+            let tint = sprite.tint;
+            unsafe {
+                rlColor4ub(tint.r, tint.g, tint.b, tint.a);
+                for ((x, y), (u, v)) in corners {
+                    rlTexCoord2f(u, v);
+                    rlVertex2f(x, y);
+                }
+            }
+        }
+        unsafe {
+            rlEnd();
+            rlSetTexture(0);
+        }
+    }
+}
+
+/// Get the bottom y-coordinate of the sprites (used for y-sorting).
+pub(crate) fn bottom(instances: &[SpriteInstance]) -> i32 {
+    instances
+        .iter()
+        .map(SpriteInstance::bottom)
+        .max()
+        .unwrap_or(i32::MIN)
+}
@@ -0,0 +1,1658 @@
+//! Draw commands that the [`Draw`](super::Draw) methods are implemented on top of.
+use super::batch::{self, SpriteInstance};
+use super::{BlendFactors, BlendMode, Camera, Color};
+use crate::core::shader::Shader;
+use crate::structs::Rectangle;
+use crate::text::Font;
+use crate::texture::{NpatchInfo, Texture};
+use crate::{Position, Size};
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::c_int;
+use std::sync::Arc;
+
+/// A call of a [`Draw`](super::Draw) method.
+///
+/// Commands borrow their textures, fonts, texts and points from the caller;
+/// canvases that keep commands past the call (e.g., [`DrawList`](super::DrawList)) store them with [`DrawCommand::into_owned`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DrawCommand<'a> {
+    ClearBackground {
+        color: Color,
+    },
+    Pixel {
+        position: Position,
+        color: Color,
+    },
+    Line {
+        start: Position,
+        end: Position,
+        color: Color,
+    },
+    LineEx {
+        start: Position,
+        end: Position,
+        thick: f32,
+        color: Color,
+    },
+    LineBezier {
+        start: Position,
+        end: Position,
+        thick: f32,
+        color: Color,
+    },
+    LineBezierQuad {
+        start: Position,
+        end: Position,
+        control: Position,
+        thick: f32,
+        color: Color,
+    },
+    LineBezierCubic {
+        start: Position,
+        end: Position,
+        start_control: Position,
+        end_control: Position,
+        thick: f32,
+        color: Color,
+    },
+    LineStrip {
+        points: Cow<'a, [Position]>,
+        color: Color,
+    },
+    Circle {
+        center: Position,
+        radius: f32,
+        color: Color,
+    },
+    CircleSector {
+        center: Position,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: usize,
+        color: Color,
+    },
+    CircleSectorLines {
+        center: Position,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: usize,
+        color: Color,
+    },
+    CircleGradient {
+        center: Position,
+        radius: f32,
+        color1: Color,
+        color2: Color,
+    },
+    CircleLines {
+        center: Position,
+        radius: f32,
+        color: Color,
+    },
+    Ellipse {
+        center: Position,
+        radius_h: f32,
+        radius_v: f32,
+        color: Color,
+    },
+    EllipseLines {
+        center: Position,
+        radius_h: f32,
+        radius_v: f32,
+        color: Color,
+    },
+    Ring {
+        center: Position,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: usize,
+        color: Color,
+    },
+    RingLines {
+        center: Position,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: usize,
+        color: Color,
+    },
+    Rectangle {
+        rectangle: Rectangle,
+        color: Color,
+    },
+    RectanglePro {
+        rectangle: Rectangle,
+        origin: Position,
+        rotation: f32,
+        color: Color,
+    },
+    RectangleGradientV {
+        rectangle: Rectangle,
+        color1: Color,
+        color2: Color,
+    },
+    RectangleGradientH {
+        rectangle: Rectangle,
+        color1: Color,
+        color2: Color,
+    },
+    RectangleGradientEx {
+        rectangle: Rectangle,
+        color1: Color,
+        color2: Color,
+        color3: Color,
+        color4: Color,
+    },
+    RectangleLines {
+        rectangle: Rectangle,
+        color: Color,
+    },
+    RectangleLinesEx {
+        rectangle: Rectangle,
+        line_thick: f32,
+        color: Color,
+    },
+    RectangleRounded {
+        rectangle: Rectangle,
+        roundness: f32,
+        segments: usize,
+        color: Color,
+    },
+    RectangleRoundedLines {
+        rectangle: Rectangle,
+        roundness: f32,
+        segments: usize,
+        line_thick: f32,
+        color: Color,
+    },
+    Triangle {
+        v1: Position,
+        v2: Position,
+        v3: Position,
+        color: Color,
+    },
+    TriangleLines {
+        v1: Position,
+        v2: Position,
+        v3: Position,
+        color: Color,
+    },
+    TriangleFan {
+        points: Cow<'a, [Position]>,
+        color: Color,
+    },
+    TriangleStrip {
+        points: Cow<'a, [Position]>,
+        color: Color,
+    },
+    Poly {
+        center: Position,
+        sides: usize,
+        radius: f32,
+        rotation: f32,
+        color: Color,
+    },
+    PolyLines {
+        center: Position,
+        sides: usize,
+        radius: f32,
+        rotation: f32,
+        color: Color,
+    },
+    PolyLinesEx {
+        center: Position,
+        sides: usize,
+        radius: f32,
+        rotation: f32,
+        line_thick: f32,
+        color: Color,
+    },
+    Fps {
+        position: Position,
+    },
+    Text {
+        text: Cow<'a, CStr>,
+        position: Position,
+        font_size: usize,
+        color: Color,
+    },
+    TextEx {
+        font: Cow<'a, Font>,
+        text: Cow<'a, CStr>,
+        position: Position,
+        font_size: f32,
+        spacing: f32,
+        tint: Color,
+    },
+    TextPro {
+        font: Cow<'a, Font>,
+        text: Cow<'a, CStr>,
+        position: Position,
+        origin: Position,
+        rotation: f32,
+        font_size: f32,
+        spacing: f32,
+        tint: Color,
+    },
+    Char {
+        font: Cow<'a, Font>,
+        c: char,
+        position: Position,
+        font_size: f32,
+        tint: Color,
+    },
+    Texture {
+        texture: Cow<'a, Texture>,
+        position: Position,
+        tint: Color,
+    },
+    TextureEx {
+        texture: Cow<'a, Texture>,
+        position: Position,
+        rotation: f32,
+        scale: f32,
+        tint: Color,
+    },
+    TextureRec {
+        texture: Cow<'a, Texture>,
+        source: Rectangle,
+        position: Position,
+        tint: Color,
+    },
+    TextureQuad {
+        texture: Cow<'a, Texture>,
+        tiling: Size,
+        offset: Position,
+        quad: Rectangle,
+        tint: Color,
+    },
+    TextureTiled {
+        texture: Cow<'a, Texture>,
+        source: Rectangle,
+        dest: Rectangle,
+        origin: Position,
+        rotation: f32,
+        scale: f32,
+        tint: Color,
+    },
+    TexturePro {
+        texture: Cow<'a, Texture>,
+        source: Rectangle,
+        dest: Rectangle,
+        origin: Position,
+        rotation: f32,
+        tint: Color,
+    },
+    TextureNpatch {
+        texture: Cow<'a, Texture>,
+        info: NpatchInfo,
+        dest: Rectangle,
+        origin: Position,
+        rotation: f32,
+        tint: Color,
+    },
+    TexturePoly {
+        texture: Cow<'a, Texture>,
+        center: Position,
+        points: Cow<'a, [Position]>,
+        texcoord: Cow<'a, [Position]>,
+        tint: Color,
+    },
+    Sprites {
+        texture: Cow<'a, Texture>,
+        instances: Arc<Vec<SpriteInstance>>,
+    },
+}
+
+impl<'a> DrawCommand<'a> {
+    /// Execute this command immediately.
+    pub(crate) fn execute(&self) {
+        match self {
+            Self::ClearBackground { color } => unsafe {
+                raylib4_sys::ClearBackground((*color).into())
+            },
+            Self::Pixel { position, color } => unsafe {
+                raylib4_sys::DrawPixelV((*position).into(), (*color).into())
+            },
+            Self::Line { start, end, color } => unsafe {
+                raylib4_sys::DrawLineV((*start).into(), (*end).into(), (*color).into())
+            },
+            Self::LineEx {
+                start,
+                end,
+                thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawLineEx((*start).into(), (*end).into(), *thick, (*color).into())
+            },
+            Self::LineBezier {
+                start,
+                end,
+                thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawLineBezier((*start).into(), (*end).into(), *thick, (*color).into())
+            },
+            Self::LineBezierQuad {
+                start,
+                end,
+                control,
+                thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawLineBezierQuad(
+                    (*start).into(),
+                    (*end).into(),
+                    (*control).into(),
+                    *thick,
+                    (*color).into(),
+                )
+            },
+            Self::LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawLineBezierCubic(
+                    (*start).into(),
+                    (*end).into(),
+                    (*start_control).into(),
+                    (*end_control).into(),
+                    *thick,
+                    (*color).into(),
+                )
+            },
+            Self::LineStrip { points, color } => {
+                let mut points = to_vectors(points);
+                unsafe {
+                    raylib4_sys::DrawLineStrip(
+                        points.as_mut_ptr(),
+                        points.len() as c_int,
+                        (*color).into(),
+                    )
+                };
+            }
+            Self::Circle {
+                center,
+                radius,
+                color,
+            } => unsafe { raylib4_sys::DrawCircleV((*center).into(), *radius, (*color).into()) },
+            Self::CircleSector {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawCircleSector(
+                    (*center).into(),
+                    *radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::CircleSectorLines {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawCircleSectorLines(
+                    (*center).into(),
+                    *radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::CircleGradient {
+                center,
+                radius,
+                color1,
+                color2,
+            } => unsafe {
+                raylib4_sys::DrawCircleGradient(
+                    center.x as c_int,
+                    center.y as c_int,
+                    *radius,
+                    (*color1).into(),
+                    (*color2).into(),
+                )
+            },
+            Self::CircleLines {
+                center,
+                radius,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawCircleLines(
+                    center.x as c_int,
+                    center.y as c_int,
+                    *radius,
+                    (*color).into(),
+                )
+            },
+            Self::Ellipse {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawEllipse(
+                    center.x as c_int,
+                    center.y as c_int,
+                    *radius_h,
+                    *radius_v,
+                    (*color).into(),
+                )
+            },
+            Self::EllipseLines {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawEllipseLines(
+                    center.x as c_int,
+                    center.y as c_int,
+                    *radius_h,
+                    *radius_v,
+                    (*color).into(),
+                )
+            },
+            Self::Ring {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRing(
+                    (*center).into(),
+                    *inner_radius,
+                    *outer_radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::RingLines {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRingLines(
+                    (*center).into(),
+                    *inner_radius,
+                    *outer_radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::Rectangle { rectangle, color } => unsafe {
+                raylib4_sys::DrawRectangleRec((*rectangle).into(), (*color).into())
+            },
+            Self::RectanglePro {
+                rectangle,
+                origin,
+                rotation,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRectanglePro(
+                    (*rectangle).into(),
+                    (*origin).into(),
+                    *rotation,
+                    (*color).into(),
+                )
+            },
+            Self::RectangleGradientV {
+                rectangle,
+                color1,
+                color2,
+            } => unsafe {
+                raylib4_sys::DrawRectangleGradientV(
+                    rectangle.position.x,
+                    rectangle.position.y,
+                    rectangle.size.width as c_int,
+                    rectangle.size.height as c_int,
+                    (*color1).into(),
+                    (*color2).into(),
+                )
+            },
+            Self::RectangleGradientH {
+                rectangle,
+                color1,
+                color2,
+            } => unsafe {
+                raylib4_sys::DrawRectangleGradientH(
+                    rectangle.position.x,
+                    rectangle.position.y,
+                    rectangle.size.width as c_int,
+                    rectangle.size.height as c_int,
+                    (*color1).into(),
+                    (*color2).into(),
+                )
+            },
+            Self::RectangleGradientEx {
+                rectangle,
+                color1,
+                color2,
+                color3,
+                color4,
+            } => unsafe {
+                raylib4_sys::DrawRectangleGradientEx(
+                    (*rectangle).into(),
+                    (*color1).into(),
+                    (*color2).into(),
+                    (*color3).into(),
+                    (*color4).into(),
+                )
+            },
+            Self::RectangleLines { rectangle, color } => unsafe {
+                raylib4_sys::DrawRectangleLines(
+                    rectangle.position.x,
+                    rectangle.position.y,
+                    rectangle.size.width as c_int,
+                    rectangle.size.height as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::RectangleLinesEx {
+                rectangle,
+                line_thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRectangleLinesEx((*rectangle).into(), *line_thick, (*color).into())
+            },
+            Self::RectangleRounded {
+                rectangle,
+                roundness,
+                segments,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRectangleRounded(
+                    (*rectangle).into(),
+                    *roundness,
+                    *segments as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::RectangleRoundedLines {
+                rectangle,
+                roundness,
+                segments,
+                line_thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawRectangleRoundedLines(
+                    (*rectangle).into(),
+                    *roundness,
+                    *segments as c_int,
+                    *line_thick,
+                    (*color).into(),
+                )
+            },
+            Self::Triangle { v1, v2, v3, color } => unsafe {
+                raylib4_sys::DrawTriangle((*v1).into(), (*v2).into(), (*v3).into(), (*color).into())
+            },
+            Self::TriangleLines { v1, v2, v3, color } => unsafe {
+                raylib4_sys::DrawTriangleLines(
+                    (*v1).into(),
+                    (*v2).into(),
+                    (*v3).into(),
+                    (*color).into(),
+                )
+            },
+            Self::TriangleFan { points, color } => {
+                let mut points = to_vectors(points);
+                unsafe {
+                    raylib4_sys::DrawTriangleFan(
+                        points.as_mut_ptr(),
+                        points.len() as c_int,
+                        (*color).into(),
+                    )
+                };
+            }
+            Self::TriangleStrip { points, color } => {
+                let mut points = to_vectors(points);
+                unsafe {
+                    raylib4_sys::DrawTriangleStrip(
+                        points.as_mut_ptr(),
+                        points.len() as c_int,
+                        (*color).into(),
+                    )
+                };
+            }
+            Self::Poly {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawPoly(
+                    (*center).into(),
+                    *sides as c_int,
+                    *radius,
+                    *rotation,
+                    (*color).into(),
+                )
+            },
+            Self::PolyLines {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawPolyLines(
+                    (*center).into(),
+                    *sides as c_int,
+                    *radius,
+                    *rotation,
+                    (*color).into(),
+                )
+            },
+            Self::PolyLinesEx {
+                center,
+                sides,
+                radius,
+                rotation,
+                line_thick,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawPolyLinesEx(
+                    (*center).into(),
+                    *sides as c_int,
+                    *radius,
+                    *rotation,
+                    *line_thick,
+                    (*color).into(),
+                )
+            },
+            Self::Fps { position } => unsafe {
+                raylib4_sys::DrawFPS(position.x as c_int, position.y as c_int)
+            },
+            Self::Text {
+                text,
+                position,
+                font_size,
+                color,
+            } => unsafe {
+                raylib4_sys::DrawText(
+                    text.as_ptr(),
+                    position.x as c_int,
+                    position.y as c_int,
+                    *font_size as c_int,
+                    (*color).into(),
+                )
+            },
+            Self::TextEx {
+                font,
+                text,
+                position,
+                font_size,
+                spacing,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextEx(
+                    font.to_raw(),
+                    text.as_ptr(),
+                    (*position).into(),
+                    *font_size,
+                    *spacing,
+                    (*tint).into(),
+                )
+            },
+            Self::TextPro {
+                font,
+                text,
+                position,
+                origin,
+                rotation,
+                font_size,
+                spacing,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextPro(
+                    font.to_raw(),
+                    text.as_ptr(),
+                    (*position).into(),
+                    (*origin).into(),
+                    *rotation,
+                    *font_size,
+                    *spacing,
+                    (*tint).into(),
+                )
+            },
+            Self::Char {
+                font,
+                c,
+                position,
+                font_size,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextCodepoint(
+                    font.to_raw(),
+                    u32::from(*c) as c_int,
+                    (*position).into(),
+                    *font_size,
+                    (*tint).into(),
+                )
+            },
+            Self::Texture {
+                texture,
+                position,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureV(texture.0, (*position).into(), (*tint).into())
+            },
+            Self::TextureEx {
+                texture,
+                position,
+                rotation,
+                scale,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureEx(
+                    texture.0,
+                    (*position).into(),
+                    *rotation,
+                    *scale,
+                    (*tint).into(),
+                )
+            },
+            Self::TextureRec {
+                texture,
+                source,
+                position,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureRec(
+                    texture.0,
+                    (*source).into(),
+                    (*position).into(),
+                    (*tint).into(),
+                )
+            },
+            Self::TextureQuad {
+                texture,
+                tiling,
+                offset,
+                quad,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureQuad(
+                    texture.0,
+                    (*tiling).into(),
+                    (*offset).into(),
+                    (*quad).into(),
+                    (*tint).into(),
+                )
+            },
+            Self::TextureTiled {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                scale,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureTiled(
+                    texture.0,
+                    (*source).into(),
+                    (*dest).into(),
+                    (*origin).into(),
+                    *rotation,
+                    *scale,
+                    (*tint).into(),
+                )
+            },
+            Self::TexturePro {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTexturePro(
+                    texture.0,
+                    (*source).into(),
+                    (*dest).into(),
+                    (*origin).into(),
+                    *rotation,
+                    (*tint).into(),
+                )
+            },
+            Self::TextureNpatch {
+                texture,
+                info,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => unsafe {
+                raylib4_sys::DrawTextureNPatch(
+                    texture.0,
+                    info.clone().into(),
+                    (*dest).into(),
+                    (*origin).into(),
+                    *rotation,
+                    (*tint).into(),
+                )
+            },
+            Self::TexturePoly {
+                texture,
+                center,
+                points,
+                texcoord,
+                tint,
+            } => {
+                let mut points = to_vectors(points);
+                let mut texcoord = to_vectors(texcoord);
+                unsafe {
+                    raylib4_sys::DrawTexturePoly(
+                        texture.0,
+                        (*center).into(),
+                        points.as_mut_ptr(),
+                        texcoord.as_mut_ptr(),
+                        points.len() as c_int,
+                        (*tint).into(),
+                    )
+                };
+            }
+            Self::Sprites { texture, instances } => batch::draw_sprites(texture, instances),
+        }
+    }
+
+    /// Move the drawing position of this command by `offset`.
+    ///
+    /// Relative values (e.g., origins, texture sources and the points of textured polygons) are unchanged.
+    pub fn translate(&mut self, offset: Position) {
+        let mv = |p: &mut Position| {
+            p.x += offset.x;
+            p.y += offset.y;
+        };
+        match self {
+            Self::ClearBackground { .. } => {}
+            Self::Pixel { position, .. }
+            | Self::Fps { position }
+            | Self::Text { position, .. }
+            | Self::TextEx { position, .. }
+            | Self::TextPro { position, .. }
+            | Self::Char { position, .. }
+            | Self::Texture { position, .. }
+            | Self::TextureEx { position, .. }
+            | Self::TextureRec { position, .. } => mv(position),
+            Self::Line { start, end, .. }
+            | Self::LineEx { start, end, .. }
+            | Self::LineBezier { start, end, .. } => {
+                mv(start);
+                mv(end);
+            }
+            Self::LineBezierQuad {
+                start,
+                end,
+                control,
+                ..
+            } => {
+                mv(start);
+                mv(end);
+                mv(control);
+            }
+            Self::LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                ..
+            } => {
+                mv(start);
+                mv(end);
+                mv(start_control);
+                mv(end_control);
+            }
+            Self::LineStrip { points, .. }
+            | Self::TriangleFan { points, .. }
+            | Self::TriangleStrip { points, .. } => points.to_mut().iter_mut().for_each(mv),
+            Self::Circle { center, .. }
+            | Self::CircleSector { center, .. }
+            | Self::CircleSectorLines { center, .. }
+            | Self::CircleGradient { center, .. }
+            | Self::CircleLines { center, .. }
+            | Self::Ellipse { center, .. }
+            | Self::EllipseLines { center, .. }
+            | Self::Ring { center, .. }
+            | Self::RingLines { center, .. }
+            | Self::Poly { center, .. }
+            | Self::PolyLines { center, .. }
+            | Self::PolyLinesEx { center, .. }
+            | Self::TexturePoly { center, .. } => mv(center),
+            Self::Rectangle { rectangle, .. }
+            | Self::RectanglePro { rectangle, .. }
+            | Self::RectangleGradientV { rectangle, .. }
+            | Self::RectangleGradientH { rectangle, .. }
+            | Self::RectangleGradientEx { rectangle, .. }
+            | Self::RectangleLines { rectangle, .. }
+            | Self::RectangleLinesEx { rectangle, .. }
+            | Self::RectangleRounded { rectangle, .. }
+            | Self::RectangleRoundedLines { rectangle, .. }
+            | Self::TextureQuad {
+                quad: rectangle, ..
+            }
+            | Self::TextureTiled {
+                dest: rectangle, ..
+            }
+            | Self::TexturePro {
+                dest: rectangle, ..
+            }
+            | Self::TextureNpatch {
+                dest: rectangle, ..
+            } => mv(&mut rectangle.position),
+            Self::Triangle { v1, v2, v3, .. } | Self::TriangleLines { v1, v2, v3, .. } => {
+                mv(v1);
+                mv(v2);
+                mv(v3);
+            }
+            Self::Sprites { instances, .. } => Arc::make_mut(instances)
+                .iter_mut()
+                .for_each(|sprite| mv(&mut sprite.position)),
+        }
+    }
+
+    /// Get the bottom y-coordinate of the drawn area (used for y-sorting).
+    ///
+    /// Rotations are ignored.
+    pub fn bottom(&self) -> i32 {
+        let max_y = |points: &[Position]| points.iter().map(|p| p.y).max().unwrap_or(0);
+        match self {
+            Self::ClearBackground { .. } => i32::MIN,
+            Self::Pixel { position, .. }
+            | Self::Fps { position }
+            | Self::Text { position, .. }
+            | Self::TextEx { position, .. }
+            | Self::Char { position, .. } => position.y,
+            Self::TextPro {
+                position, origin, ..
+            } => position.y - origin.y,
+            Self::Texture {
+                texture, position, ..
+            } => position.y + texture.size().height,
+            Self::TextureEx {
+                texture,
+                position,
+                scale,
+                ..
+            } => position.y + (texture.size().height as f32 * scale) as i32,
+            Self::TextureRec {
+                source, position, ..
+            } => position.y + source.size.height.abs(),
+            Self::Line { start, end, .. }
+            | Self::LineEx { start, end, .. }
+            | Self::LineBezier { start, end, .. } => start.y.max(end.y),
+            Self::LineBezierQuad {
+                start,
+                end,
+                control,
+                ..
+            } => max_y(&[*start, *end, *control]),
+            Self::LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                ..
+            } => max_y(&[*start, *end, *start_control, *end_control]),
+            Self::LineStrip { points, .. }
+            | Self::TriangleFan { points, .. }
+            | Self::TriangleStrip { points, .. } => max_y(points),
+            Self::Circle { center, radius, .. }
+            | Self::CircleSector { center, radius, .. }
+            | Self::CircleSectorLines { center, radius, .. }
+            | Self::CircleGradient { center, radius, .. }
+            | Self::CircleLines { center, radius, .. }
+            | Self::Poly { center, radius, .. }
+            | Self::PolyLines { center, radius, .. }
+            | Self::PolyLinesEx { center, radius, .. }
+            | Self::Ring {
+                center,
+                outer_radius: radius,
+                ..
+            }
+            | Self::RingLines {
+                center,
+                outer_radius: radius,
+                ..
+            }
+            | Self::Ellipse {
+                center,
+                radius_v: radius,
+                ..
+            }
+            | Self::EllipseLines {
+                center,
+                radius_v: radius,
+                ..
+            } => center.y + *radius as i32,
+            Self::TexturePoly { center, points, .. } => center.y + max_y(points),
+            Self::Rectangle { rectangle, .. }
+            | Self::RectangleGradientV { rectangle, .. }
+            | Self::RectangleGradientH { rectangle, .. }
+            | Self::RectangleGradientEx { rectangle, .. }
+            | Self::RectangleLines { rectangle, .. }
+            | Self::RectangleLinesEx { rectangle, .. }
+            | Self::RectangleRounded { rectangle, .. }
+            | Self::RectangleRoundedLines { rectangle, .. }
+            | Self::TextureQuad {
+                quad: rectangle, ..
+            } => rectangle.position.y + rectangle.size.height,
+            Self::RectanglePro {
+                rectangle, origin, ..
+            }
+            | Self::TextureTiled {
+                dest: rectangle,
+                origin,
+                ..
+            }
+            | Self::TexturePro {
+                dest: rectangle,
+                origin,
+                ..
+            }
+            | Self::TextureNpatch {
+                dest: rectangle,
+                origin,
+                ..
+            } => rectangle.position.y - origin.y + rectangle.size.height,
+            Self::Triangle { v1, v2, v3, .. } | Self::TriangleLines { v1, v2, v3, .. } => {
+                max_y(&[*v1, *v2, *v3])
+            }
+            Self::Sprites { instances, .. } => batch::bottom(instances),
+        }
+    }
+
+    /// Get the texture used by this command (if any).
+    pub fn texture(&self) -> Option<&Texture> {
+        match self {
+            Self::Texture { texture, .. }
+            | Self::TextureEx { texture, .. }
+            | Self::TextureRec { texture, .. }
+            | Self::TextureQuad { texture, .. }
+            | Self::TextureTiled { texture, .. }
+            | Self::TexturePro { texture, .. }
+            | Self::TextureNpatch { texture, .. }
+            | Self::TexturePoly { texture, .. }
+            | Self::Sprites { texture, .. } => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Convert this command into one that owns its data (textures and fonts are shared, the rest is copied).
+    pub fn into_owned(self) -> DrawCommand<'static> {
+        use DrawCommand::*;
+        fn own<T: ToOwned + ?Sized>(v: Cow<'_, T>) -> Cow<'static, T> {
+            Cow::Owned(v.into_owned())
+        }
+        match self {
+            ClearBackground { color } => ClearBackground { color },
+            Pixel { position, color } => Pixel { position, color },
+            Line { start, end, color } => Line { start, end, color },
+            LineEx {
+                start,
+                end,
+                thick,
+                color,
+            } => LineEx {
+                start,
+                end,
+                thick,
+                color,
+            },
+            LineBezier {
+                start,
+                end,
+                thick,
+                color,
+            } => LineBezier {
+                start,
+                end,
+                thick,
+                color,
+            },
+            LineBezierQuad {
+                start,
+                end,
+                control,
+                thick,
+                color,
+            } => LineBezierQuad {
+                start,
+                end,
+                control,
+                thick,
+                color,
+            },
+            LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                thick,
+                color,
+            } => LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                thick,
+                color,
+            },
+            LineStrip { points, color } => LineStrip {
+                points: own(points),
+                color,
+            },
+            Circle {
+                center,
+                radius,
+                color,
+            } => Circle {
+                center,
+                radius,
+                color,
+            },
+            CircleSector {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => CircleSector {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            },
+            CircleSectorLines {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => CircleSectorLines {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            },
+            CircleGradient {
+                center,
+                radius,
+                color1,
+                color2,
+            } => CircleGradient {
+                center,
+                radius,
+                color1,
+                color2,
+            },
+            CircleLines {
+                center,
+                radius,
+                color,
+            } => CircleLines {
+                center,
+                radius,
+                color,
+            },
+            Ellipse {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => Ellipse {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            },
+            EllipseLines {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => EllipseLines {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            },
+            Ring {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => Ring {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            },
+            RingLines {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => RingLines {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            },
+            Rectangle { rectangle, color } => Rectangle { rectangle, color },
+            RectanglePro {
+                rectangle,
+                origin,
+                rotation,
+                color,
+            } => RectanglePro {
+                rectangle,
+                origin,
+                rotation,
+                color,
+            },
+            RectangleGradientV {
+                rectangle,
+                color1,
+                color2,
+            } => RectangleGradientV {
+                rectangle,
+                color1,
+                color2,
+            },
+            RectangleGradientH {
+                rectangle,
+                color1,
+                color2,
+            } => RectangleGradientH {
+                rectangle,
+                color1,
+                color2,
+            },
+            RectangleGradientEx {
+                rectangle,
+                color1,
+                color2,
+                color3,
+                color4,
+            } => RectangleGradientEx {
+                rectangle,
+                color1,
+                color2,
+                color3,
+                color4,
+            },
+            RectangleLines { rectangle, color } => RectangleLines { rectangle, color },
+            RectangleLinesEx {
+                rectangle,
+                line_thick,
+                color,
+            } => RectangleLinesEx {
+                rectangle,
+                line_thick,
+                color,
+            },
+            RectangleRounded {
+                rectangle,
+                roundness,
+                segments,
+                color,
+            } => RectangleRounded {
+                rectangle,
+                roundness,
+                segments,
+                color,
+            },
+            RectangleRoundedLines {
+                rectangle,
+                roundness,
+                segments,
+                line_thick,
+                color,
+            } => RectangleRoundedLines {
+                rectangle,
+                roundness,
+                segments,
+                line_thick,
+                color,
+            },
+            Triangle { v1, v2, v3, color } => Triangle { v1, v2, v3, color },
+            TriangleLines { v1, v2, v3, color } => TriangleLines { v1, v2, v3, color },
+            TriangleFan { points, color } => TriangleFan {
+                points: own(points),
+                color,
+            },
+            TriangleStrip { points, color } => TriangleStrip {
+                points: own(points),
+                color,
+            },
+            Poly {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => Poly {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            },
+            PolyLines {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => PolyLines {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            },
+            PolyLinesEx {
+                center,
+                sides,
+                radius,
+                rotation,
+                line_thick,
+                color,
+            } => PolyLinesEx {
+                center,
+                sides,
+                radius,
+                rotation,
+                line_thick,
+                color,
+            },
+            Fps { position } => Fps { position },
+            Text {
+                text,
+                position,
+                font_size,
+                color,
+            } => Text {
+                text: own(text),
+                position,
+                font_size,
+                color,
+            },
+            TextEx {
+                font,
+                text,
+                position,
+                font_size,
+                spacing,
+                tint,
+            } => TextEx {
+                font: own(font),
+                text: own(text),
+                position,
+                font_size,
+                spacing,
+                tint,
+            },
+            TextPro {
+                font,
+                text,
+                position,
+                origin,
+                rotation,
+                font_size,
+                spacing,
+                tint,
+            } => TextPro {
+                font: own(font),
+                text: own(text),
+                position,
+                origin,
+                rotation,
+                font_size,
+                spacing,
+                tint,
+            },
+            Char {
+                font,
+                c,
+                position,
+                font_size,
+                tint,
+            } => Char {
+                font: own(font),
+                c,
+                position,
+                font_size,
+                tint,
+            },
+            Texture {
+                texture,
+                position,
+                tint,
+            } => Texture {
+                texture: own(texture),
+                position,
+                tint,
+            },
+            TextureEx {
+                texture,
+                position,
+                rotation,
+                scale,
+                tint,
+            } => TextureEx {
+                texture: own(texture),
+                position,
+                rotation,
+                scale,
+                tint,
+            },
+            TextureRec {
+                texture,
+                source,
+                position,
+                tint,
+            } => TextureRec {
+                texture: own(texture),
+                source,
+                position,
+                tint,
+            },
+            TextureQuad {
+                texture,
+                tiling,
+                offset,
+                quad,
+                tint,
+            } => TextureQuad {
+                texture: own(texture),
+                tiling,
+                offset,
+                quad,
+                tint,
+            },
+            TextureTiled {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                scale,
+                tint,
+            } => TextureTiled {
+                texture: own(texture),
+                source,
+                dest,
+                origin,
+                rotation,
+                scale,
+                tint,
+            },
+            TexturePro {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => TexturePro {
+                texture: own(texture),
+                source,
+                dest,
+                origin,
+                rotation,
+                tint,
+            },
+            TextureNpatch {
+                texture,
+                info,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => TextureNpatch {
+                texture: own(texture),
+                info,
+                dest,
+                origin,
+                rotation,
+                tint,
+            },
+            TexturePoly {
+                texture,
+                center,
+                points,
+                texcoord,
+                tint,
+            } => TexturePoly {
+                texture: own(texture),
+                center,
+                points: own(points),
+                texcoord: own(texcoord),
+                tint,
+            },
+            Sprites { texture, instances } => Sprites {
+                texture: own(texture),
+                instances,
+            },
+        }
+    }
+}
+
+/// Drawing mode that applies to the commands drawn while it is active.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DrawMode {
+    Camera(Camera),
+    Blend(BlendMode),
+    CustomBlend(BlendFactors),
+    Shader(Shader),
+    Scissor(Rectangle),
+}
+
+impl DrawMode {
+    /// Begin this mode immediately.
+    pub(crate) fn begin(&self) {
+        match self {
+            Self::Camera(camera) => unsafe { raylib4_sys::BeginMode2D((*camera).into()) },
+            Self::Blend(mode) => unsafe {
+                raylib4_sys::BeginBlendMode(mode.to_raw_value() as c_int)
+            },
+            Self::CustomBlend(factors) => {
+                // raylib does not re-apply the factors if the custom mode is already active.
+                unsafe { raylib4_sys::EndBlendMode() };
+                factors.apply();
+                unsafe { raylib4_sys::BeginBlendMode(BlendMode::Custom.to_raw_value() as c_int) };
+            }
+            Self::Shader(shader) => unsafe { raylib4_sys::BeginShaderMode(shader.to_raw()) },
+            Self::Scissor(rectangle) => unsafe {
+                raylib4_sys::BeginScissorMode(
+                    rectangle.position.x as c_int,
+                    rectangle.position.y as c_int,
+                    rectangle.size.width as c_int,
+                    rectangle.size.height as c_int,
+                )
+            },
+        }
+    }
+
+    /// End this mode immediately.
+    pub(crate) fn end(&self) {
+        match self {
+            Self::Camera(_) => unsafe { raylib4_sys::EndMode2D() },
+            Self::Blend(_) | Self::CustomBlend(_) => unsafe { raylib4_sys::EndBlendMode() },
+            Self::Shader(_) => unsafe { raylib4_sys::EndShaderMode() },
+            Self::Scissor(_) => unsafe { raylib4_sys::EndScissorMode() },
+        }
+    }
+
+    /// Move the scissor area by `offset` (other modes are unchanged).
+    pub fn translate(&mut self, offset: Position) {
+        if let Self::Scissor(rectangle) = self {
+            rectangle.position.x += offset.x;
+            rectangle.position.y += offset.y;
+        }
+    }
+}
+
+fn to_vectors(points: &[Position]) -> Vec<raylib4_sys::Vector2> {
+    points
+        .iter()
+        .copied()
+        .map(raylib4_sys::Vector2::from)
+        .collect()
+}
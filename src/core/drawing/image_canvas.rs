@@ -0,0 +1,1551 @@
+//! Software rasterizer that implements [`Draw`] for [`Image`].
+//!
+//! ```no_run
+//! use rfraylib::texture::Image;
+//! use rfraylib::{Color, Draw, Position, Rectangle};
+//!
+//! let mut image = Image::generate_color((256, 256).into(), Color::RAYWHITE);
+//! {
+//!     let mut canvas = image.canvas().expect("uncompressed image");
+//!     canvas.draw_line_ex(Position { x: 16, y: 16 }, Position { x: 240, y: 200 }, 4.0, Color::RED);
+//!     canvas.draw_rectangle_rounded(Rectangle::new(32, 64, 128, 64), 0.5, 8, Color::BLUE);
+//! }
+//! image.export("thumbnail.png");
+//! ```
+use super::sealed::Token;
+use super::{BlendEquation, BlendFactor, BlendFactors, BlendMode, Camera, Color, Draw};
+use super::{DrawCommand, DrawMode};
+use crate::structs::Rectangle;
+use crate::texture::{Image, NpatchInfo, NpatchLayout, PixelFormat, Texture};
+use crate::{Position, Size};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::ffi::{CStr, CString};
+use std::rc::Rc;
+
+type Point = (f32, f32);
+
+const LINE_DIVISIONS: usize = 24;
+
+/// Canvas that draws into an [`Image`] on the CPU.
+///
+/// All shapes are anti-aliased by default (4x4 supersampling).
+/// Cameras, blend modes (including custom blend factors) and scissor areas are supported, shaders are ignored.
+///
+/// Textures are read back from the GPU the first time they are drawn and cached for the lifetime of the canvas
+/// (use [`ImageCanvas::set_texture_image`] or [`ImageCanvas::draw_image`] to draw without a GPU,
+/// and [`ImageCanvas::invalidate_texture`] after redrawing a render texture).
+/// Text rendering always needs the font textures on the GPU.
+///
+/// NOTE: The image is converted to [`PixelFormat::UncompressedR8g8b8a8`] when the canvas is made.
+#[derive(Debug)]
+pub struct ImageCanvas<'a> {
+    image: &'a mut Image,
+    modes: Vec<DrawMode>,
+    anti_aliasing: bool,
+    // Keyed by the texture owner (the cached handle keeps the GPU texture and its id alive).
+    textures: HashMap<usize, (Texture, Option<Rc<Pixels>>)>,
+}
+
+impl<'a> ImageCanvas<'a> {
+    /// Make a canvas for `image` (`None` if the image cannot be converted to RGBA8, e.g., compressed images).
+    pub fn new(image: &'a mut Image) -> Option<Self> {
+        image.set_image_format(PixelFormat::UncompressedR8g8b8a8);
+        if image.0.format != PixelFormat::UncompressedR8g8b8a8 as i32 {
+            // Compressed formats can't be converted.
+            return None;
+        }
+        Some(Self {
+            image,
+            modes: Vec::new(),
+            anti_aliasing: true,
+            textures: HashMap::new(),
+        })
+    }
+
+    pub fn is_anti_aliasing(&self) -> bool {
+        self.anti_aliasing
+    }
+
+    /// Enable or disable anti-aliasing (disabled: each pixel is sampled once at its center).
+    pub fn set_anti_aliasing(&mut self, enabled: bool) {
+        self.anti_aliasing = enabled;
+    }
+
+    /// Use the pixels of `image` when drawing `texture` (instead of reading it back from the GPU).
+    pub fn set_texture_image(&mut self, texture: &Texture, image: &Image) {
+        self.textures.insert(
+            texture.key(),
+            (texture.clone(), Some(Rc::new(Pixels::from_image(image)))),
+        );
+    }
+
+    /// Read `texture` back from the GPU again the next time it is drawn (e.g., after redrawing a render texture).
+    pub fn invalidate_texture(&mut self, texture: &Texture) {
+        self.textures.remove(&texture.key());
+    }
+
+    /// Draw a part of an image defined by a rectangle with 'pro' parameters (see [`Draw::draw_texture_pro`]).
+    pub fn draw_image(
+        &mut self,
+        image: &Image,
+        source: Rectangle,
+        dest: Rectangle,
+        origin: Position,
+        rotation: f32,
+        tint: Color,
+    ) {
+        let pixels = Pixels::from_image(image);
+        self.blit(&pixels, source, rect(dest), point(origin), rotation, tint);
+    }
+
+    fn texture_pixels(&mut self, texture: &Texture) -> Option<Rc<Pixels>> {
+        let (_, pixels) = self.textures.entry(texture.key()).or_insert_with(|| {
+            let pixels = Image::load_from_texture(texture).map(|i| Rc::new(Pixels::from_image(&i)));
+            (texture.clone(), pixels)
+        });
+        pixels.clone()
+    }
+
+    fn data(&mut self) -> &mut [u8] {
+        match data_len(self.image.size()) {
+            Some(len) if !self.image.0.data.is_null() => unsafe {
+                std::slice::from_raw_parts_mut(self.image.0.data as *mut u8, len)
+            },
+            _ => &mut [],
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        self.modes
+            .iter()
+            .rev()
+            .find_map(|mode| match mode {
+                DrawMode::Camera(camera) => Some(Transform::from_camera(camera)),
+                _ => None,
+            })
+            .unwrap_or(Transform::IDENTITY)
+    }
+
+    fn blending(&self) -> Option<BlendFactors> {
+        self.modes.iter().rev().find_map(|mode| match mode {
+            DrawMode::Blend(BlendMode::Alpha) => Some(None),
+            DrawMode::Blend(mode) => Some(Some(blend_mode_factors(*mode))),
+            DrawMode::CustomBlend(factors) => Some(Some(*factors)),
+            _ => None,
+        })?
+    }
+
+    // Drawable area as `(x0, y0, x1, y1)` (exclusive).
+    fn clip(&self) -> (i32, i32, i32, i32) {
+        let size = self.image.size();
+        let mut clip = (0, 0, size.width, size.height);
+        for mode in &self.modes {
+            if let DrawMode::Scissor(area) = mode {
+                clip.0 = clip.0.max(area.position.x);
+                clip.1 = clip.1.max(area.position.y);
+                clip.2 = clip.2.min(area.position.x + area.size.width);
+                clip.3 = clip.3.min(area.position.y + area.size.height);
+            }
+        }
+        clip
+    }
+
+    fn clear(&mut self, color: Color) {
+        let width = self.image.size().width;
+        let (x0, y0, x1, y1) = self.clip();
+        let data = self.data();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let i = ((y * width + x) * 4) as usize;
+                data[i..i + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+            }
+        }
+    }
+
+    /// Fill the area enclosed by `contours` (in world coordinates, non-zero winding rule).
+    ///
+    /// `paint` is called with the world coordinates of each covered pixel center.
+    fn fill(&mut self, contours: &[Vec<Point>], paint: &dyn Fn(Point) -> Color) {
+        let transform = self.transform();
+        let edges = contours
+            .iter()
+            .flat_map(|contour| {
+                let points = contour
+                    .iter()
+                    .map(|&p| transform.apply(p))
+                    .collect::<Vec<_>>();
+                (0..points.len()).map(move |i| (points[i], points[(i + 1) % points.len()]))
+            })
+            .filter(|(a, b)| a.1 != b.1)
+            .collect::<Vec<_>>();
+        if edges.is_empty() {
+            return;
+        }
+
+        let (min_x, min_y, max_x, max_y) = edges.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(x0, y0, x1, y1), (a, b)| {
+                (
+                    x0.min(a.0).min(b.0),
+                    y0.min(a.1).min(b.1),
+                    x1.max(a.0).max(b.0),
+                    y1.max(a.1).max(b.1),
+                )
+            },
+        );
+        let (cx0, cy0, cx1, cy1) = self.clip();
+        let x0 = (min_x.floor() as i32).max(cx0);
+        let y0 = (min_y.floor() as i32).max(cy0);
+        let x1 = (max_x.ceil() as i32).min(cx1);
+        let y1 = (max_y.ceil() as i32).min(cy1);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let n = if self.anti_aliasing { 4 } else { 1 };
+        let blending = self.blending();
+        let width = self.image.size().width;
+        let mut row = vec![0u16; (x1 - x0) as usize];
+        let mut crossings = Vec::new();
+        for py in y0..y1 {
+            row.iter_mut().for_each(|c| *c = 0);
+            for sy in 0..n {
+                let y = py as f32 + (sy as f32 + 0.5) / n as f32;
+                crossings.clear();
+                for &(a, b) in &edges {
+                    let (top, bottom, dir) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+                    if y < top.1 || y >= bottom.1 {
+                        continue;
+                    }
+                    let x = top.0 + (y - top.1) * (bottom.0 - top.0) / (bottom.1 - top.1);
+                    crossings.push((x, dir));
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                let mut start = 0.0;
+                for &(x, dir) in &crossings {
+                    let prev = winding;
+                    winding += dir;
+                    if prev == 0 {
+                        start = x;
+                    } else if winding == 0 {
+                        // Samples `k` at `(k + 0.5) / n` within `[start, x)`.
+                        let k0 = ((start * n as f32 - 0.5).ceil() as i32).max(x0 * n);
+                        let k1 = ((x * n as f32 - 0.5).ceil() as i32).min(x1 * n);
+                        for k in k0..k1 {
+                            row[(k / n - x0) as usize] += 1;
+                        }
+                    }
+                }
+            }
+
+            for (i, &count) in row.iter().enumerate() {
+                if count == 0 {
+                    continue;
+                }
+                let px = x0 + i as i32;
+                let coverage = count as f32 / (n * n) as f32;
+                let world = transform.invert((px as f32 + 0.5, py as f32 + 0.5));
+                let color = paint(world);
+                let offset = ((py * width + px) * 4) as usize;
+                let data = self.data();
+                blend_pixel(&mut data[offset..offset + 4], color, coverage, blending);
+            }
+        }
+    }
+
+    fn fill_color(&mut self, contours: &[Vec<Point>], color: Color) {
+        self.fill(contours, &|_| color);
+    }
+
+    fn stroke(&mut self, points: &[Point], thick: f32, closed: bool, color: Color) {
+        self.fill_color(&stroke(points, thick, closed), color);
+    }
+
+    /// Fill a rectangle placed as in [`Draw::draw_rectangle_pro`].
+    ///
+    /// `paint` is called with the coordinates relative to the top-left corner of the (unrotated) rectangle.
+    fn fill_rect_pro(
+        &mut self,
+        dest: (f32, f32, f32, f32),
+        origin: Point,
+        rotation: f32,
+        paint: &dyn Fn(Point) -> Color,
+    ) {
+        let (x, y, w, h) = dest;
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let to_world = |(lx, ly): Point| {
+            let (lx, ly) = (lx - origin.0, ly - origin.1);
+            (x + lx * cos - ly * sin, y + lx * sin + ly * cos)
+        };
+        let to_local = |(wx, wy): Point| {
+            let (dx, dy) = (wx - x, wy - y);
+            (
+                dx * cos + dy * sin + origin.0,
+                -dx * sin + dy * cos + origin.1,
+            )
+        };
+        let corners = [(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)].map(to_world);
+        self.fill(&[oriented(corners.to_vec(), true)], &|p| paint(to_local(p)));
+    }
+
+    fn blit(
+        &mut self,
+        pixels: &Pixels,
+        source: Rectangle,
+        dest: (f32, f32, f32, f32),
+        origin: Point,
+        rotation: f32,
+        tint: Color,
+    ) {
+        let (_, _, w, h) = dest;
+        let (sx, sy) = (source.position.x as f32, source.position.y as f32);
+        let (sw, sh) = (source.size.width as f32, source.size.height as f32);
+        self.fill_rect_pro(dest, origin, rotation, &|(lx, ly)| {
+            // Negative source sizes flip the texture.
+            let u = lx / w;
+            let v = ly / h;
+            let tx = if sw < 0.0 {
+                sx + (1.0 - u) * -sw
+            } else {
+                sx + u * sw
+            };
+            let ty = if sh < 0.0 {
+                sy + (1.0 - v) * -sh
+            } else {
+                sy + v * sh
+            };
+            modulate(pixels.sample(tx, ty), tint)
+        });
+    }
+
+    /// Draw an image made by [`text_image`] (placed as in [`Draw::draw_text_pro`]).
+    fn draw_text_image(
+        &mut self,
+        image: Image,
+        position: Position,
+        origin: Position,
+        rotation: f32,
+    ) {
+        let size = image.size();
+        if image.0.data.is_null() || size.width <= 0 || size.height <= 0 {
+            return;
+        }
+        let pixels = Pixels::from_image(&image);
+        let source = Rectangle {
+            position: Position { x: 0, y: 0 },
+            size,
+        };
+        let dest = (
+            position.x as f32,
+            position.y as f32,
+            size.width as f32,
+            size.height as f32,
+        );
+        self.blit(&pixels, source, dest, point(origin), rotation, Color::WHITE);
+    }
+
+    fn execute(&mut self, command: &DrawCommand<'_>) {
+        match command {
+            DrawCommand::ClearBackground { color } => self.clear(*color),
+            DrawCommand::Pixel { position, color } => {
+                let (x, y) = point(*position);
+                self.fill_color(&[rectangle_points(x, y, 1.0, 1.0)], *color);
+            }
+            DrawCommand::Line { start, end, color } => {
+                // Through the pixel centers as the GPU does.
+                let start = (start.x as f32 + 0.5, start.y as f32 + 0.5);
+                let end = (end.x as f32 + 0.5, end.y as f32 + 0.5);
+                self.stroke(&[start, end], 1.0, false, *color);
+            }
+            DrawCommand::LineEx {
+                start,
+                end,
+                thick,
+                color,
+            } => self.stroke(&[point(*start), point(*end)], *thick, false, *color),
+            DrawCommand::LineBezier {
+                start,
+                end,
+                thick,
+                color,
+            } => {
+                let (start, end) = (point(*start), point(*end));
+                let points = (0..=LINE_DIVISIONS)
+                    .map(|i| {
+                        let t = i as f32 / LINE_DIVISIONS as f32;
+                        let eased = if t < 0.5 {
+                            4.0 * t * t * t
+                        } else {
+                            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                        };
+                        (
+                            start.0 + (end.0 - start.0) * t,
+                            start.1 + (end.1 - start.1) * eased,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.stroke(&points, *thick, false, *color);
+            }
+            DrawCommand::LineBezierQuad {
+                start,
+                end,
+                control,
+                thick,
+                color,
+            } => {
+                let (p0, p1, p2) = (point(*start), point(*control), point(*end));
+                let points = (0..=LINE_DIVISIONS)
+                    .map(|i| {
+                        let t = i as f32 / LINE_DIVISIONS as f32;
+                        let (a, b, c) = ((1.0 - t) * (1.0 - t), 2.0 * (1.0 - t) * t, t * t);
+                        (
+                            a * p0.0 + b * p1.0 + c * p2.0,
+                            a * p0.1 + b * p1.1 + c * p2.1,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.stroke(&points, *thick, false, *color);
+            }
+            DrawCommand::LineBezierCubic {
+                start,
+                end,
+                start_control,
+                end_control,
+                thick,
+                color,
+            } => {
+                let (p0, p1) = (point(*start), point(*start_control));
+                let (p2, p3) = (point(*end_control), point(*end));
+                let points = (0..=LINE_DIVISIONS)
+                    .map(|i| {
+                        let t = i as f32 / LINE_DIVISIONS as f32;
+                        let u = 1.0 - t;
+                        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                        (
+                            a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                            a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                self.stroke(&points, *thick, false, *color);
+            }
+            DrawCommand::LineStrip { points, color } => {
+                let points = points.iter().map(|&p| point(p)).collect::<Vec<_>>();
+                self.stroke(&points, 1.0, false, *color);
+            }
+            DrawCommand::Circle {
+                center,
+                radius,
+                color,
+            } => self.fill_color(&[ellipse(point(*center), *radius, *radius)], *color),
+            DrawCommand::CircleSector {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => {
+                let center = point(*center);
+                let mut points = arc(center, *radius, *start_angle, *end_angle, *segments);
+                points.push(center);
+                self.fill_color(&[oriented(points, true)], *color);
+            }
+            DrawCommand::CircleSectorLines {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => {
+                let center = point(*center);
+                let mut points = arc(center, *radius, *start_angle, *end_angle, *segments);
+                points.push(center);
+                self.stroke(&points, 1.0, true, *color);
+            }
+            DrawCommand::CircleGradient {
+                center,
+                radius,
+                color1,
+                color2,
+            } => {
+                let (center, radius) = (point(*center), *radius);
+                self.fill(&[ellipse(center, radius, radius)], &|(x, y)| {
+                    let d = ((x - center.0).powi(2) + (y - center.1).powi(2)).sqrt();
+                    lerp_color(*color1, *color2, d / radius)
+                });
+            }
+            DrawCommand::CircleLines {
+                center,
+                radius,
+                color,
+            } => self.stroke(
+                &ellipse(point(*center), *radius, *radius),
+                1.0,
+                true,
+                *color,
+            ),
+            DrawCommand::Ellipse {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => self.fill_color(&[ellipse(point(*center), *radius_h, *radius_v)], *color),
+            DrawCommand::EllipseLines {
+                center,
+                radius_h,
+                radius_v,
+                color,
+            } => self.stroke(
+                &ellipse(point(*center), *radius_h, *radius_v),
+                1.0,
+                true,
+                *color,
+            ),
+            DrawCommand::Ring {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => {
+                let points = ring(
+                    point(*center),
+                    *inner_radius,
+                    *outer_radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments,
+                );
+                self.fill_color(&[oriented(points, true)], *color);
+            }
+            DrawCommand::RingLines {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => {
+                let points = ring(
+                    point(*center),
+                    *inner_radius,
+                    *outer_radius,
+                    *start_angle,
+                    *end_angle,
+                    *segments,
+                );
+                self.stroke(&points, 1.0, true, *color);
+            }
+            DrawCommand::Rectangle { rectangle, color } => {
+                let (x, y, w, h) = rect(*rectangle);
+                self.fill_color(&[rectangle_points(x, y, w, h)], *color);
+            }
+            DrawCommand::RectanglePro {
+                rectangle,
+                origin,
+                rotation,
+                color,
+            } => self.fill_rect_pro(rect(*rectangle), point(*origin), *rotation, &|_| *color),
+            DrawCommand::RectangleGradientV {
+                rectangle,
+                color1,
+                color2,
+            } => {
+                let (_, _, _, h) = rect(*rectangle);
+                self.fill_rect_pro(rect(*rectangle), (0.0, 0.0), 0.0, &|(_, y)| {
+                    lerp_color(*color1, *color2, y / h)
+                });
+            }
+            DrawCommand::RectangleGradientH {
+                rectangle,
+                color1,
+                color2,
+            } => {
+                let (_, _, w, _) = rect(*rectangle);
+                self.fill_rect_pro(rect(*rectangle), (0.0, 0.0), 0.0, &|(x, _)| {
+                    lerp_color(*color1, *color2, x / w)
+                });
+            }
+            DrawCommand::RectangleGradientEx {
+                rectangle,
+                color1,
+                color2,
+                color3,
+                color4,
+            } => {
+                // Top-left, bottom-left, bottom-right and top-right.
+                let (_, _, w, h) = rect(*rectangle);
+                self.fill_rect_pro(rect(*rectangle), (0.0, 0.0), 0.0, &|(x, y)| {
+                    let top = lerp_color(*color1, *color4, x / w);
+                    let bottom = lerp_color(*color2, *color3, x / w);
+                    lerp_color(top, bottom, y / h)
+                });
+            }
+            DrawCommand::RectangleLines { rectangle, color } => {
+                let (x, y, w, h) = rect(*rectangle);
+                let points = rectangle_points(x + 0.5, y + 0.5, w - 1.0, h - 1.0);
+                self.stroke(&points, 1.0, true, *color);
+            }
+            DrawCommand::RectangleLinesEx {
+                rectangle,
+                line_thick,
+                color,
+            } => {
+                let (x, y, w, h) = rect(*rectangle);
+                let t = line_thick.min(w / 2.0).min(h / 2.0);
+                let outer = rectangle_points(x, y, w, h);
+                let inner = rectangle_points(x + t, y + t, w - t * 2.0, h - t * 2.0);
+                self.fill_color(&[outer, oriented(inner, false)], *color);
+            }
+            DrawCommand::RectangleRounded {
+                rectangle,
+                roundness,
+                segments,
+                color,
+            } => {
+                let (x, y, w, h) = rect(*rectangle);
+                let radius = w.min(h) * roundness.clamp(0.0, 1.0) / 2.0;
+                let points = rounded_rectangle(x, y, w, h, radius, *segments);
+                self.fill_color(&[points], *color);
+            }
+            DrawCommand::RectangleRoundedLines {
+                rectangle,
+                roundness,
+                segments,
+                line_thick,
+                color,
+            } => {
+                // The lines are drawn outside the rectangle.
+                let (x, y, w, h) = rect(*rectangle);
+                let t = *line_thick;
+                let radius = w.min(h) * roundness.clamp(0.0, 1.0) / 2.0;
+                let inner = rounded_rectangle(x, y, w, h, radius, *segments);
+                let outer = rounded_rectangle(
+                    x - t,
+                    y - t,
+                    w + t * 2.0,
+                    h + t * 2.0,
+                    if radius > 0.0 { radius + t } else { 0.0 },
+                    *segments,
+                );
+                self.fill_color(&[outer, oriented(inner, false)], *color);
+            }
+            DrawCommand::Triangle { v1, v2, v3, color } => {
+                let points = vec![point(*v1), point(*v2), point(*v3)];
+                self.fill_color(&[oriented(points, true)], *color);
+            }
+            DrawCommand::TriangleLines { v1, v2, v3, color } => {
+                let points = [point(*v1), point(*v2), point(*v3)];
+                self.stroke(&points, 1.0, true, *color);
+            }
+            DrawCommand::TriangleFan { points, color } => {
+                let triangles = (1..points.len().saturating_sub(1))
+                    .map(|i| {
+                        let t = vec![point(points[0]), point(points[i]), point(points[i + 1])];
+                        oriented(t, true)
+                    })
+                    .collect::<Vec<_>>();
+                self.fill_color(&triangles, *color);
+            }
+            DrawCommand::TriangleStrip { points, color } => {
+                let triangles = points
+                    .windows(3)
+                    .map(|t| oriented(t.iter().map(|&p| point(p)).collect(), true))
+                    .collect::<Vec<_>>();
+                self.fill_color(&triangles, *color);
+            }
+            DrawCommand::Poly {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => {
+                let points = polygon(point(*center), *sides, *radius, *rotation);
+                self.fill_color(&[points], *color);
+            }
+            DrawCommand::PolyLines {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => {
+                let points = polygon(point(*center), *sides, *radius, *rotation);
+                self.stroke(&points, 1.0, true, *color);
+            }
+            DrawCommand::PolyLinesEx {
+                center,
+                sides,
+                radius,
+                rotation,
+                line_thick,
+                color,
+            } => {
+                let sides = (*sides).max(3);
+                let exterior_angle = 360.0 / sides as f32;
+                let inner_radius = radius - line_thick * (exterior_angle / 2.0).to_radians().cos();
+                let outer = polygon(point(*center), sides, *radius, *rotation);
+                let inner = polygon(point(*center), sides, inner_radius.max(0.0), *rotation);
+                self.fill_color(&[outer, oriented(inner, false)], *color);
+            }
+            DrawCommand::Fps { position } => {
+                let fps = unsafe { raylib4_sys::GetFPS() };
+                let color = if fps < 15 {
+                    Color::RED
+                } else if fps < 30 {
+                    Color::ORANGE
+                } else {
+                    Color::LIME
+                };
+                let text = CString::new(format!("{} FPS", fps)).expect("unreachable");
+                self.draw_default_font_text(&text, *position, 20, color);
+            }
+            DrawCommand::Text {
+                text,
+                position,
+                font_size,
+                color,
+            } => self.draw_default_font_text(text, *position, *font_size, *color),
+            DrawCommand::TextEx {
+                font,
+                text,
+                position,
+                font_size,
+                spacing,
+                tint,
+            } => {
+                let image = text_image(font.to_raw(), text, *font_size, *spacing, *tint);
+                self.draw_text_image(image, *position, Position { x: 0, y: 0 }, 0.0);
+            }
+            DrawCommand::TextPro {
+                font,
+                text,
+                position,
+                origin,
+                rotation,
+                font_size,
+                spacing,
+                tint,
+            } => {
+                let image = text_image(font.to_raw(), text, *font_size, *spacing, *tint);
+                self.draw_text_image(image, *position, *origin, *rotation);
+            }
+            DrawCommand::Char {
+                font,
+                c,
+                position,
+                font_size,
+                tint,
+            } => {
+                let text = CString::new(c.to_string()).unwrap_or_default();
+                let image = text_image(font.to_raw(), &text, *font_size, 0.0, *tint);
+                self.draw_text_image(image, *position, Position { x: 0, y: 0 }, 0.0);
+            }
+            DrawCommand::Texture {
+                texture,
+                position,
+                tint,
+            } => {
+                let size = texture.size();
+                let source = Rectangle {
+                    position: Position { x: 0, y: 0 },
+                    size,
+                };
+                let dest = Rectangle {
+                    position: *position,
+                    size,
+                };
+                self.blit_texture(texture, source, rect(dest), (0.0, 0.0), 0.0, *tint);
+            }
+            DrawCommand::TextureEx {
+                texture,
+                position,
+                rotation,
+                scale,
+                tint,
+            } => {
+                let size = texture.size();
+                let source = Rectangle {
+                    position: Position { x: 0, y: 0 },
+                    size,
+                };
+                let dest = (
+                    position.x as f32,
+                    position.y as f32,
+                    size.width as f32 * scale,
+                    size.height as f32 * scale,
+                );
+                self.blit_texture(texture, source, dest, (0.0, 0.0), *rotation, *tint);
+            }
+            DrawCommand::TextureRec {
+                texture,
+                source,
+                position,
+                tint,
+            } => {
+                let dest = (
+                    position.x as f32,
+                    position.y as f32,
+                    source.size.width.abs() as f32,
+                    source.size.height.abs() as f32,
+                );
+                self.blit_texture(texture, *source, dest, (0.0, 0.0), 0.0, *tint);
+            }
+            DrawCommand::TextureQuad {
+                texture,
+                tiling,
+                offset,
+                quad,
+                tint,
+            } => {
+                if let Some(pixels) = self.texture_pixels(texture) {
+                    let (_, _, w, h) = rect(*quad);
+                    let (tw, th) = (pixels.width as f32, pixels.height as f32);
+                    self.fill_rect_pro(rect(*quad), (0.0, 0.0), 0.0, &|(x, y)| {
+                        let u = (offset.x as f32 + x / w * tiling.width as f32).rem_euclid(1.0);
+                        let v = (offset.y as f32 + y / h * tiling.height as f32).rem_euclid(1.0);
+                        modulate(pixels.sample(u * tw, v * th), *tint)
+                    });
+                }
+            }
+            DrawCommand::TextureTiled {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                scale,
+                tint,
+            } => {
+                if let Some(pixels) = self.texture_pixels(texture) {
+                    let (sx, sy) = (source.position.x as f32, source.position.y as f32);
+                    let sw = (source.size.width.abs() as f32).max(1.0);
+                    let sh = (source.size.height.abs() as f32).max(1.0);
+                    let scale = *scale;
+                    self.fill_rect_pro(rect(*dest), point(*origin), *rotation, &|(x, y)| {
+                        let tx = sx + (x / scale).rem_euclid(sw);
+                        let ty = sy + (y / scale).rem_euclid(sh);
+                        modulate(pixels.sample(tx, ty), *tint)
+                    });
+                }
+            }
+            DrawCommand::TexturePro {
+                texture,
+                source,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => self.blit_texture(
+                texture,
+                *source,
+                rect(*dest),
+                point(*origin),
+                *rotation,
+                *tint,
+            ),
+            DrawCommand::TextureNpatch {
+                texture,
+                info,
+                dest,
+                origin,
+                rotation,
+                tint,
+            } => {
+                if let Some(pixels) = self.texture_pixels(texture) {
+                    let (_, _, w, h) = rect(*dest);
+                    let info = info.clone();
+                    self.fill_rect_pro(rect(*dest), point(*origin), *rotation, &|(x, y)| {
+                        let (tx, ty) = npatch_texel(&info, (w, h), (x, y));
+                        modulate(pixels.sample(tx, ty), *tint)
+                    });
+                }
+            }
+            DrawCommand::TexturePoly {
+                texture,
+                center,
+                points,
+                texcoord,
+                tint,
+            } => {
+                if let Some(pixels) = self.texture_pixels(texture) {
+                    let center = point(*center);
+                    let (tw, th) = (pixels.width as f32, pixels.height as f32);
+                    for i in 0..points.len().min(texcoord.len()).saturating_sub(1) {
+                        let vertices = [
+                            center,
+                            (center.0 + points[i].x as f32, center.1 + points[i].y as f32),
+                            (
+                                center.0 + points[i + 1].x as f32,
+                                center.1 + points[i + 1].y as f32,
+                            ),
+                        ];
+                        let uvs = [(0.5, 0.5), point(texcoord[i]), point(texcoord[i + 1])];
+                        let triangle = oriented(vertices.to_vec(), true);
+                        self.fill(&[triangle], &|p| {
+                            let (a, b, c) = barycentric(vertices, p);
+                            let u = a * uvs[0].0 + b * uvs[1].0 + c * uvs[2].0;
+                            let v = a * uvs[0].1 + b * uvs[1].1 + c * uvs[2].1;
+                            modulate(pixels.sample(u * tw, v * th), *tint)
+                        });
+                    }
+                }
+            }
+            DrawCommand::Sprites { texture, instances } => {
+                if let Some(pixels) = self.texture_pixels(texture) {
+                    for sprite in instances.iter() {
+                        let mut source = sprite.source;
+                        if sprite.flip_x {
+                            source.size.width = -source.size.width;
+                        }
+                        if sprite.flip_y {
+                            source.size.height = -source.size.height;
+                        }
+                        let (sx, sy) = (sprite.scale.x(), sprite.scale.y());
+                        let dest = (
+                            sprite.position.x as f32,
+                            sprite.position.y as f32,
+                            source.size.width.abs() as f32 * sx,
+                            source.size.height.abs() as f32 * sy,
+                        );
+                        let origin = (sprite.origin.x as f32 * sx, sprite.origin.y as f32 * sy);
+                        self.blit(&pixels, source, dest, origin, sprite.rotation, sprite.tint);
+                    }
+                }
+            }
+        }
+    }
+
+    fn blit_texture(
+        &mut self,
+        texture: &Texture,
+        source: Rectangle,
+        dest: (f32, f32, f32, f32),
+        origin: Point,
+        rotation: f32,
+        tint: Color,
+    ) {
+        if let Some(pixels) = self.texture_pixels(texture) {
+            self.blit(&pixels, source, dest, origin, rotation, tint);
+        }
+    }
+
+    fn draw_default_font_text(
+        &mut self,
+        text: &CStr,
+        position: Position,
+        font_size: usize,
+        color: Color,
+    ) {
+        // Same as `DrawText()`.
+        let font = unsafe { raylib4_sys::GetFontDefault() };
+        let font_size = font_size.max(10);
+        let spacing = (font_size / 10) as f32;
+        let image = text_image(font, text, font_size as f32, spacing, color);
+        self.draw_text_image(image, position, Position { x: 0, y: 0 }, 0.0);
+    }
+}
+
+impl<'a> super::sealed::Sealed for ImageCanvas<'a> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.execute(&command);
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        self.modes.push(mode.clone());
+    }
+
+    fn end_mode(&mut self, _: Token, _mode: &DrawMode) {
+        self.modes.pop();
+    }
+}
+
+impl<'a> Draw for ImageCanvas<'a> {}
+
+#[derive(Debug, Clone)]
+struct Pixels {
+    width: i32,
+    height: i32,
+    colors: Vec<Color>,
+}
+
+impl Pixels {
+    fn from_image(image: &Image) -> Self {
+        let size = image.size();
+        Self {
+            width: size.width,
+            height: size.height,
+            colors: image.load_colors(),
+        }
+    }
+
+    /// Get the nearest texel (clamped to the edges).
+    fn sample(&self, x: f32, y: f32) -> Color {
+        if self.colors.is_empty() {
+            return Color::BLANK;
+        }
+        let x = (x.floor() as i32).clamp(0, self.width - 1);
+        let y = (y.floor() as i32).clamp(0, self.height - 1);
+        self.colors[(y * self.width + x) as usize]
+    }
+}
+
+/// 2D camera transformation (world to screen).
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    target: Point,
+    offset: Point,
+    sin: f32,
+    cos: f32,
+    zoom: f32,
+}
+
+impl Transform {
+    const IDENTITY: Self = Self {
+        target: (0.0, 0.0),
+        offset: (0.0, 0.0),
+        sin: 0.0,
+        cos: 1.0,
+        zoom: 1.0,
+    };
+
+    fn from_camera(camera: &Camera) -> Self {
+        let (sin, cos) = camera.rotation.to_radians().sin_cos();
+        Self {
+            target: point(camera.target),
+            offset: point(camera.offset),
+            sin,
+            cos,
+            zoom: camera.zoom,
+        }
+    }
+
+    fn apply(&self, (x, y): Point) -> Point {
+        let (x, y) = (x - self.target.0, y - self.target.1);
+        (
+            (x * self.cos - y * self.sin) * self.zoom + self.offset.0,
+            (x * self.sin + y * self.cos) * self.zoom + self.offset.1,
+        )
+    }
+
+    fn invert(&self, (x, y): Point) -> Point {
+        let (x, y) = (
+            (x - self.offset.0) / self.zoom,
+            (y - self.offset.1) / self.zoom,
+        );
+        (
+            x * self.cos + y * self.sin + self.target.0,
+            -x * self.sin + y * self.cos + self.target.1,
+        )
+    }
+}
+
+fn point(p: Position) -> Point {
+    (p.x as f32, p.y as f32)
+}
+
+fn rect(r: Rectangle) -> (f32, f32, f32, f32) {
+    (
+        r.position.x as f32,
+        r.position.y as f32,
+        r.size.width as f32,
+        r.size.height as f32,
+    )
+}
+
+fn signed_area(points: &[Point]) -> f32 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Make the orientation of the contour positive (filled area) or negative (hole).
+fn oriented(mut points: Vec<Point>, positive: bool) -> Vec<Point> {
+    if (signed_area(&points) >= 0.0) != positive {
+        points.reverse();
+    }
+    points
+}
+
+fn rectangle_points(x: f32, y: f32, w: f32, h: f32) -> Vec<Point> {
+    oriented(vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)], true)
+}
+
+fn segments_for(radius: f32) -> usize {
+    ((2.0 * PI * radius / 3.0) as usize).clamp(12, 720)
+}
+
+fn ellipse(center: Point, rx: f32, ry: f32) -> Vec<Point> {
+    let n = segments_for(rx.max(ry));
+    let points = (0..n)
+        .map(|i| {
+            let a = 2.0 * PI * i as f32 / n as f32;
+            (center.0 + a.cos() * rx, center.1 + a.sin() * ry)
+        })
+        .collect();
+    oriented(points, true)
+}
+
+/// Points on an arc (angles in degrees, measured as raylib's circle sectors do).
+fn arc(
+    center: Point,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    segments: usize,
+) -> Vec<Point> {
+    let n = if segments < 4 {
+        // Same minimum error as raylib's automatic segment count.
+        ((end_angle - start_angle).abs() / 360.0 * segments_for(radius) as f32).ceil() as usize
+    } else {
+        segments
+    }
+    .max(1);
+    (0..=n)
+        .map(|i| {
+            let a = (start_angle + (end_angle - start_angle) * i as f32 / n as f32).to_radians();
+            (center.0 + a.sin() * radius, center.1 + a.cos() * radius)
+        })
+        .collect()
+}
+
+fn ring(
+    center: Point,
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    segments: usize,
+) -> Vec<Point> {
+    let mut points = arc(center, outer_radius, start_angle, end_angle, segments);
+    let mut inner = arc(center, inner_radius, start_angle, end_angle, segments);
+    inner.reverse();
+    points.extend(inner);
+    points
+}
+
+fn rounded_rectangle(x: f32, y: f32, w: f32, h: f32, radius: f32, segments: usize) -> Vec<Point> {
+    if radius <= 0.0 {
+        return rectangle_points(x, y, w, h);
+    }
+    let r = radius.min(w / 2.0).min(h / 2.0);
+    let n = if segments < 4 {
+        segments_for(r) / 4
+    } else {
+        segments
+    }
+    .max(1);
+    let corners = [
+        ((x + w - r, y + r), -90.0),
+        ((x + w - r, y + h - r), 0.0),
+        ((x + r, y + h - r), 90.0),
+        ((x + r, y + r), 180.0),
+    ];
+    let points = corners
+        .iter()
+        .flat_map(|&((cx, cy), start): &(Point, f32)| {
+            (0..=n).map(move |i| {
+                let a = (start + 90.0 * i as f32 / n as f32).to_radians();
+                (cx + a.cos() * r, cy + a.sin() * r)
+            })
+        })
+        .collect();
+    oriented(points, true)
+}
+
+/// Regular polygon (angles measured as raylib's polygons do).
+fn polygon(center: Point, sides: usize, radius: f32, rotation: f32) -> Vec<Point> {
+    let sides = sides.max(3);
+    let points = (0..sides)
+        .map(|i| {
+            let a = (rotation + 360.0 * i as f32 / sides as f32).to_radians();
+            (center.0 + a.sin() * radius, center.1 + a.cos() * radius)
+        })
+        .collect();
+    oriented(points, true)
+}
+
+/// Contours covering a polyline of the given thickness (butt caps and round joins).
+fn stroke(points: &[Point], thick: f32, closed: bool) -> Vec<Vec<Point>> {
+    let half = thick / 2.0;
+    let n = points.len();
+    let segments = if closed { n } else { n.saturating_sub(1) };
+    let mut contours = Vec::new();
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            continue;
+        }
+        let (nx, ny) = (-dy / len * half, dx / len * half);
+        let quad = vec![
+            (a.0 + nx, a.1 + ny),
+            (b.0 + nx, b.1 + ny),
+            (b.0 - nx, b.1 - ny),
+            (a.0 - nx, a.1 - ny),
+        ];
+        contours.push(oriented(quad, true));
+    }
+    if thick > 2.0 {
+        let joins = if closed { 0..n } else { 1..n.saturating_sub(1) };
+        for i in joins {
+            contours.push(ellipse(points[i], half, half));
+        }
+    }
+    contours
+}
+
+fn barycentric(v: [Point; 3], p: Point) -> (f32, f32, f32) {
+    let d = (v[1].1 - v[2].1) * (v[0].0 - v[2].0) + (v[2].0 - v[1].0) * (v[0].1 - v[2].1);
+    if d == 0.0 {
+        return (1.0, 0.0, 0.0);
+    }
+    let a = ((v[1].1 - v[2].1) * (p.0 - v[2].0) + (v[2].0 - v[1].0) * (p.1 - v[2].1)) / d;
+    let b = ((v[2].1 - v[0].1) * (p.0 - v[2].0) + (v[0].0 - v[2].0) * (p.1 - v[2].1)) / d;
+    (a, b, 1.0 - a - b)
+}
+
+/// Map a position in the destination of an n-patch to the texel position.
+fn npatch_texel(info: &NpatchInfo, (w, h): Point, (x, y): Point) -> Point {
+    let source = info.source;
+    let axis = |pos: f32, dest_len: f32, src_len: f32, start: f32, end: f32, stretch: bool| {
+        if !stretch || dest_len <= start + end {
+            return pos * src_len / dest_len;
+        }
+        if pos < start {
+            pos
+        } else if pos > dest_len - end {
+            src_len - (dest_len - pos)
+        } else {
+            start + (pos - start) * (src_len - start - end) / (dest_len - start - end)
+        }
+    };
+    let (horizontal, vertical) = match info.layout {
+        NpatchLayout::NinePatch => (true, true),
+        NpatchLayout::ThreePatchHorizontal => (true, false),
+        NpatchLayout::ThreePatchVertical => (false, true),
+    };
+    let tx = axis(
+        x,
+        w,
+        source.size.width as f32,
+        info.left as f32,
+        info.right as f32,
+        horizontal,
+    );
+    let ty = axis(
+        y,
+        h,
+        source.size.height as f32,
+        info.top as f32,
+        info.bottom as f32,
+        vertical,
+    );
+    (source.position.x as f32 + tx, source.position.y as f32 + ty)
+}
+
+fn text_image(
+    font: raylib4_sys::Font,
+    text: &CStr,
+    font_size: f32,
+    spacing: f32,
+    tint: Color,
+) -> Image {
+    Image(unsafe { raylib4_sys::ImageTextEx(font, text.as_ptr(), font_size, spacing, tint.into()) })
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color::rgba(
+        lerp(a.r, b.r),
+        lerp(a.g, b.g),
+        lerp(a.b, b.b),
+        lerp(a.a, b.a),
+    )
+}
+
+fn modulate(c: Color, tint: Color) -> Color {
+    let m = |a: u8, b: u8| ((a as u16 * b as u16 + 127) / 255) as u8;
+    Color::rgba(
+        m(c.r, tint.r),
+        m(c.g, tint.g),
+        m(c.b, tint.b),
+        m(c.a, tint.a),
+    )
+}
+
+fn blend_mode_factors(mode: BlendMode) -> BlendFactors {
+    use BlendFactor::*;
+    match mode {
+        BlendMode::Alpha | BlendMode::Custom => {
+            BlendFactors::new(SrcAlpha, OneMinusSrcAlpha, BlendEquation::Add)
+        }
+        BlendMode::Additive => BlendFactors::new(SrcAlpha, One, BlendEquation::Add),
+        BlendMode::Multiplied => BlendFactors::new(DstColor, OneMinusSrcAlpha, BlendEquation::Add),
+        BlendMode::AddColors => BlendFactors::new(One, One, BlendEquation::Add),
+        BlendMode::SubtractColors => BlendFactors::new(One, One, BlendEquation::Subtract),
+    }
+}
+
+/// Blend `src` (whose alpha is multiplied by `coverage`) into the RGBA8 pixel `dst`.
+///
+/// `None` means the default alpha blending ("over" operator).
+fn blend_pixel(dst: &mut [u8], src: Color, coverage: f32, blending: Option<BlendFactors>) {
+    let s = [
+        src.r as f32 / 255.0,
+        src.g as f32 / 255.0,
+        src.b as f32 / 255.0,
+        src.a as f32 / 255.0 * coverage,
+    ];
+    let d = [
+        dst[0] as f32 / 255.0,
+        dst[1] as f32 / 255.0,
+        dst[2] as f32 / 255.0,
+        dst[3] as f32 / 255.0,
+    ];
+    let out = match blending {
+        None => {
+            let a = s[3] + d[3] * (1.0 - s[3]);
+            if a <= 0.0 {
+                [0.0; 4]
+            } else {
+                let c = |i: usize| (s[i] * s[3] + d[i] * d[3] * (1.0 - s[3])) / a;
+                [c(0), c(1), c(2), a]
+            }
+        }
+        Some(f) => {
+            let factor = |factor: BlendFactor, i: usize| match factor {
+                BlendFactor::Zero => 0.0,
+                BlendFactor::One => 1.0,
+                BlendFactor::SrcColor => s[i],
+                BlendFactor::OneMinusSrcColor => 1.0 - s[i],
+                BlendFactor::SrcAlpha => s[3],
+                BlendFactor::OneMinusSrcAlpha => 1.0 - s[3],
+                BlendFactor::DstAlpha => d[3],
+                BlendFactor::OneMinusDstAlpha => 1.0 - d[3],
+                BlendFactor::DstColor => d[i],
+                BlendFactor::OneMinusDstColor => 1.0 - d[i],
+                BlendFactor::SrcAlphaSaturate if i == 3 => 1.0,
+                BlendFactor::SrcAlphaSaturate => s[3].min(1.0 - d[3]),
+            };
+            let channel = |i: usize| {
+                let (src_factor, dst_factor, equation) = if i == 3 {
+                    (f.src_alpha, f.dst_alpha, f.equation_alpha)
+                } else {
+                    (f.src_rgb, f.dst_rgb, f.equation_rgb)
+                };
+                let a = s[i] * factor(src_factor, i);
+                let b = d[i] * factor(dst_factor, i);
+                match equation {
+                    BlendEquation::Add => a + b,
+                    BlendEquation::Subtract => a - b,
+                    BlendEquation::ReverseSubtract => b - a,
+                    BlendEquation::Min => s[i].min(d[i]),
+                    BlendEquation::Max => s[i].max(d[i]),
+                }
+            };
+            [channel(0), channel(1), channel(2), channel(3)]
+        }
+    };
+    for (d, v) in dst.iter_mut().zip(out) {
+        *d = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+}
+
+/// Get the length of RGBA8 pixel data of `size` (`None` if it does not fit in memory).
+fn data_len(size: Size) -> Option<usize> {
+    let width = usize::try_from(size.width).ok()?;
+    let height = usize::try_from(size.height).ok()?;
+    width.checked_mul(height)?.checked_mul(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Size;
+
+    fn blank(width: i32, height: i32) -> Image {
+        Image::generate_color(Size { width, height }, Color::BLANK)
+    }
+
+    /// Get the alpha of each pixel (rows from top to bottom).
+    fn alpha(image: &Image) -> Vec<Vec<u8>> {
+        let width = image.size().width as usize;
+        let colors = image.load_colors();
+        colors
+            .chunks(width)
+            .map(|row| row.iter().map(|c| c.a).collect())
+            .collect()
+    }
+
+    #[test]
+    fn data_len_ignores_negative_sizes() {
+        let size = |width, height| Size { width, height };
+        assert_eq!(data_len(size(3, 2)), Some(24));
+        assert_eq!(data_len(size(-3, 2)), None);
+    }
+
+    #[test]
+    fn aligned_rectangles_cover_whole_pixels() {
+        let mut image = blank(6, 4);
+        image
+            .canvas()
+            .unwrap()
+            .draw_rectangle(Rectangle::new(1, 1, 3, 2), Color::RED);
+        assert_eq!(
+            alpha(&image),
+            [
+                [0, 0, 0, 0, 0, 0],
+                [0, 255, 255, 255, 0, 0],
+                [0, 255, 255, 255, 0, 0],
+                [0, 0, 0, 0, 0, 0],
+            ]
+        );
+        assert_eq!(image.load_colors()[7], Color::RED);
+    }
+
+    #[test]
+    fn diagonal_edges_are_anti_aliased() {
+        let mut image = blank(4, 4);
+        let (a, b, c) = (
+            Position { x: 0, y: 0 },
+            Position { x: 0, y: 4 },
+            Position { x: 4, y: 4 },
+        );
+        image.canvas().unwrap().draw_triangle(a, b, c, Color::WHITE);
+        let coverage = alpha(&image);
+        for y in 0..4 {
+            for x in 0..4 {
+                let expected = match x.cmp(&y) {
+                    std::cmp::Ordering::Less => 255,
+                    std::cmp::Ordering::Equal => 128,
+                    std::cmp::Ordering::Greater => 0,
+                };
+                assert!(coverage[y][x].abs_diff(expected) <= 32, "{:?}", coverage);
+            }
+        }
+
+        let mut image = blank(4, 4);
+        let mut canvas = image.canvas().unwrap();
+        canvas.set_anti_aliasing(false);
+        canvas.draw_triangle(a, b, c, Color::WHITE);
+        drop(canvas);
+        assert!(alpha(&image).iter().flatten().all(|&a| a == 0 || a == 255));
+    }
+
+    #[test]
+    fn nested_scissor_areas_intersect() {
+        let mut image = blank(6, 6);
+        {
+            let mut canvas = image.canvas().unwrap();
+            let mut outer = canvas.begin_scissor_mode(
+                Position { x: 1, y: 1 },
+                Size {
+                    width: 3,
+                    height: 3,
+                },
+            );
+            let mut inner = outer.begin_scissor_mode(
+                Position { x: 2, y: 0 },
+                Size {
+                    width: 4,
+                    height: 6,
+                },
+            );
+            inner.clear_background(Color::WHITE);
+        }
+        assert_eq!(
+            alpha(&image),
+            [
+                [0, 0, 0, 0, 0, 0],
+                [0, 0, 255, 255, 0, 0],
+                [0, 0, 255, 255, 0, 0],
+                [0, 0, 255, 255, 0, 0],
+                [0, 0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn blend_pixel_over_and_custom_factors() {
+        let mut dst = [0, 0, 255, 255];
+        blend_pixel(&mut dst, Color::rgba(255, 0, 0, 255), 0.5, None);
+        assert_eq!(dst, [128, 0, 128, 255]);
+
+        let mut dst = [0, 0, 0, 0];
+        blend_pixel(&mut dst, Color::rgba(255, 0, 0, 128), 1.0, None);
+        assert_eq!(dst, [255, 0, 0, 128]);
+
+        let mut dst = [100, 100, 100, 255];
+        let additive = blend_mode_factors(BlendMode::AddColors);
+        blend_pixel(&mut dst, Color::rgba(200, 50, 0, 255), 1.0, Some(additive));
+        assert_eq!(dst, [255, 150, 100, 255]);
+
+        let mut dst = [100, 100, 100, 255];
+        let subtract = blend_mode_factors(BlendMode::SubtractColors);
+        blend_pixel(&mut dst, Color::rgba(200, 50, 0, 255), 1.0, Some(subtract));
+        assert_eq!(dst, [100, 0, 0, 0]);
+    }
+
+    #[test]
+    fn contour_helpers() {
+        let square = rectangle_points(0.0, 0.0, 2.0, 3.0);
+        assert_eq!(signed_area(&square), 6.0);
+        assert_eq!(signed_area(&oriented(square, false)), -6.0);
+        assert!((signed_area(&polygon((0.0, 0.0), 4, 1.0, 0.0)) - 2.0).abs() < 1e-5);
+
+        // A horizontal segment becomes a rectangle around it.
+        let contours = stroke(&[(0.0, 0.0), (4.0, 0.0)], 2.0, false);
+        assert_eq!(contours.len(), 1);
+        assert_eq!(signed_area(&contours[0]), 8.0);
+
+        let (a, b, c) = barycentric([(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)], (1.0, 2.0));
+        assert_eq!((a, b, c), (0.25, 0.25, 0.5));
+    }
+
+    #[test]
+    fn transform_inverts() {
+        let transform = Transform {
+            target: (10.0, 20.0),
+            offset: (100.0, 50.0),
+            sin: 0.6,
+            cos: 0.8,
+            zoom: 2.0,
+        };
+        let p = transform.invert(transform.apply((3.0, -7.0)));
+        assert!((p.0 - 3.0).abs() < 1e-4 && (p.1 + 7.0).abs() < 1e-4);
+        assert_eq!(Transform::IDENTITY.apply((3.0, -7.0)), (3.0, -7.0));
+    }
+
+    #[test]
+    fn npatch_keeps_borders() {
+        let info = NpatchInfo {
+            source: Rectangle::new(10, 10, 12, 12),
+            left: 4,
+            top: 4,
+            right: 4,
+            bottom: 4,
+            layout: NpatchLayout::NinePatch,
+        };
+        let dest = (40.0, 20.0);
+        assert_eq!(npatch_texel(&info, dest, (2.0, 2.0)), (12.0, 12.0));
+        assert_eq!(npatch_texel(&info, dest, (38.0, 18.0)), (20.0, 20.0));
+        assert_eq!(npatch_texel(&info, dest, (20.0, 10.0)), (16.0, 16.0));
+    }
+}
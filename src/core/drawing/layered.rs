@@ -0,0 +1,185 @@
+//! Layered rendering with z-order sorting.
+//!
+//! ```no_run
+//! use rfraylib::core::drawing::{LayeredCanvas, SortMode};
+//! use rfraylib::{Color, Draw, Position, Rectangle};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! let player = rfraylib::texture::Texture::load("player.png").ok_or("failed to load texture")?;
+//!
+//! while !system.window().should_close() {
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!
+//!     let mut layered = LayeredCanvas::new(&mut canvas);
+//!     layered.set_sort_mode(SortMode::YSort);
+//!     layered
+//!         .at(1, 0.0)
+//!         .draw_texture(&player, Position { x: 100, y: 80 }, Color::WHITE);
+//!     layered
+//!         .at(0, 0.0)
+//!         .draw_rectangle(Rectangle::new(0, 0, 800, 450), Color::DARKGREEN);
+//!     // The buffered calls are flushed when `layered` is dropped.
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::list::replay;
+use super::sealed::Token;
+use super::{Draw, DrawCommand, DrawMode};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Order of the calls in a layer of [`LayeredCanvas`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortMode {
+    /// Sort by z value.
+    #[default]
+    Z,
+
+    /// Sort by the bottom y-coordinate of the drawn area (see [`DrawCommand::bottom`]), then by z value.
+    ///
+    /// Useful for top-down games where objects lower on the screen should be drawn in front.
+    YSort,
+}
+
+#[derive(Debug)]
+struct LayeredItem {
+    layer: i32,
+    y: i32,
+    z: f32,
+    texture_id: u32,
+    modes: Arc<Vec<DrawMode>>,
+    command: DrawCommand<'static>,
+}
+
+impl LayeredItem {
+    fn cmp_key(&self, other: &Self) -> Ordering {
+        self.layer
+            .cmp(&other.layer)
+            .then(self.y.cmp(&other.y))
+            .then(self.z.total_cmp(&other.z))
+            .then(self.texture_id.cmp(&other.texture_id))
+    }
+}
+
+/// Canvas that buffers the calls and draws them sorted by layer and z value.
+///
+/// Calls with the same layer and z value are grouped by texture to minimize state changes
+/// (otherwise the call order is kept).
+/// The buffered calls are drawn onto the parent canvas by [`LayeredCanvas::flush`] or on drop.
+#[derive(Debug)]
+pub struct LayeredCanvas<'a, T: Draw> {
+    parent: &'a mut T,
+    items: Vec<LayeredItem>,
+    modes: Arc<Vec<DrawMode>>,
+    layer: i32,
+    z: f32,
+    sort_mode: SortMode,
+}
+
+impl<'a, T: Draw> LayeredCanvas<'a, T> {
+    pub fn new(parent: &'a mut T) -> Self {
+        Self {
+            parent,
+            items: Vec::new(),
+            modes: Arc::default(),
+            layer: 0,
+            z: 0.0,
+            sort_mode: SortMode::default(),
+        }
+    }
+
+    /// Set the layer and z value of the following calls.
+    pub fn at(&mut self, layer: i32, z: f32) -> &mut Self {
+        self.layer = layer;
+        self.z = z;
+        self
+    }
+
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// Set the layer of the following calls (lower layers are drawn first, default: 0).
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    pub fn z(&self) -> f32 {
+        self.z
+    }
+
+    /// Set the z value of the following calls (lower values are drawn first, default: 0.0).
+    pub fn set_z(&mut self, z: f32) {
+        self.z = z;
+    }
+
+    pub fn sort_mode(&self) -> SortMode {
+        self.sort_mode
+    }
+
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.sort_mode = mode;
+    }
+
+    /// Get the number of the buffered calls.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Draw the buffered calls onto the parent canvas.
+    pub fn flush(&mut self) {
+        let mut items = std::mem::take(&mut self.items);
+        if self.sort_mode == SortMode::YSort {
+            for item in &mut items {
+                item.y = item.command.bottom();
+            }
+        }
+        items.sort_by(LayeredItem::cmp_key);
+        replay(
+            self.parent,
+            items.into_iter().map(|item| (item.modes, item.command)),
+        );
+    }
+}
+
+impl<'a, T: Draw> super::sealed::Sealed for LayeredCanvas<'a, T> {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        let texture_id = match &command {
+            DrawCommand::TextEx { font, .. }
+            | DrawCommand::TextPro { font, .. }
+            | DrawCommand::Char { font, .. } => font.to_raw().texture.id,
+            _ => command.texture().map_or(0, |t| t.to_raw().id),
+        };
+        self.items.push(LayeredItem {
+            layer: self.layer,
+            y: 0,
+            z: self.z,
+            texture_id,
+            modes: Arc::clone(&self.modes),
+            command: command.into_owned(),
+        });
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        Arc::make_mut(&mut self.modes).push(mode.clone());
+    }
+
+    fn end_mode(&mut self, _: Token, _mode: &DrawMode) {
+        Arc::make_mut(&mut self.modes).pop();
+    }
+}
+
+impl<'a, T: Draw> Draw for LayeredCanvas<'a, T> {}
+
+impl<'a, T: Draw> Drop for LayeredCanvas<'a, T> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
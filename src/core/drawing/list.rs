@@ -0,0 +1,176 @@
+//! Recorded draw commands that can be replayed later.
+//!
+//! ```no_run
+//! use rfraylib::core::drawing::DrawList;
+//! use rfraylib::{Color, Draw, Position, Rectangle};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//!
+//! // Record the commands (e.g., in a worker thread).
+//! let mut list = DrawList::new();
+//! list.set_z(1);
+//! list.draw_circle(Position { x: 100, y: 100 }, 20.0, Color::RED);
+//! list.set_z(0);
+//! list.draw_rectangle(Rectangle::new(80, 80, 40, 40), Color::BLUE);
+//! list.sort_by_z();
+//!
+//! while !system.window().should_close() {
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!     list.replay(&mut canvas);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::sealed::Token;
+use super::{Draw, DrawCommand, DrawMode};
+use crate::Position;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+struct DrawItem {
+    z: i32,
+    modes: Arc<Vec<DrawMode>>,
+    command: DrawCommand<'static>,
+}
+
+/// List of [`DrawCommand`]s recorded through the [`Draw`] trait.
+///
+/// Modes (camera, blend, shader and scissor) begun on a `DrawList` are recorded together with the commands.
+/// Textures, fonts and shaders are reference counted so the recorded resources live as long as the list.
+#[derive(Debug, Clone, Default)]
+pub struct DrawList {
+    items: Vec<DrawItem>,
+    modes: Arc<Vec<DrawMode>>,
+    z: i32,
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the z-order assigned to the commands recorded from now on.
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    /// Set the z-order assigned to the commands recorded from now on (default: 0).
+    pub fn set_z(&mut self, z: i32) {
+        self.z = z;
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Remove all recorded commands (the current z-order and modes are kept).
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Get the recorded commands.
+    pub fn commands(&self) -> impl '_ + Iterator<Item = &DrawCommand<'static>> {
+        self.items.iter().map(|item| &item.command)
+    }
+
+    /// Append the commands of `other` (modes begun on `self` are not applied to them).
+    pub fn extend(&mut self, other: &DrawList) {
+        self.items.extend(other.items.iter().cloned());
+    }
+
+    /// Sort the commands by z-order (commands with the same z-order keep the recording order).
+    pub fn sort_by_z(&mut self) {
+        self.items.sort_by_key(|item| item.z);
+    }
+
+    /// Move all commands and scissor areas by `offset`.
+    pub fn translate(&mut self, offset: Position) {
+        let mut translated: Vec<(*const Vec<DrawMode>, Arc<Vec<DrawMode>>)> = Vec::new();
+        for item in &mut self.items {
+            item.command.translate(offset);
+
+            // Items recorded under the same modes share them, so translate each mode stack only once.
+            let key = Arc::as_ptr(&item.modes);
+            if let Some((_, modes)) = translated.iter().find(|(k, _)| *k == key) {
+                item.modes = Arc::clone(modes);
+            } else {
+                let mut modes = (*item.modes).clone();
+                modes.iter_mut().for_each(|mode| mode.translate(offset));
+                let modes = Arc::new(modes);
+                translated.push((key, Arc::clone(&modes)));
+                item.modes = modes;
+            }
+        }
+    }
+
+    /// Draw the recorded commands onto `canvas`.
+    ///
+    /// The recorded modes are begun and ended around the commands as needed.
+    pub fn replay<T: Draw>(&self, canvas: &mut T) {
+        replay(
+            canvas,
+            self.items
+                .iter()
+                .map(|item| (Arc::clone(&item.modes), item.command.clone())),
+        );
+    }
+}
+
+/// Draw `commands` onto `canvas`, beginning and ending the given modes as needed.
+pub(crate) fn replay<T, I>(canvas: &mut T, commands: I)
+where
+    T: Draw,
+    I: Iterator<Item = (Arc<Vec<DrawMode>>, DrawCommand<'static>)>,
+{
+    let mut current: Arc<Vec<DrawMode>> = Arc::default();
+    for (modes, command) in commands {
+        let common = current
+            .iter()
+            .zip(modes.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for mode in current[common..].iter().rev() {
+            canvas.end_mode(Token, mode);
+        }
+        for mode in &modes[common..] {
+            canvas.begin_mode(Token, mode);
+        }
+        current = modes;
+        canvas.draw_command(Token, command);
+    }
+    for mode in current.iter().rev() {
+        canvas.end_mode(Token, mode);
+    }
+}
+
+impl super::sealed::Sealed for DrawList {
+    fn draw_command(&mut self, _: Token, command: DrawCommand<'_>) {
+        self.items.push(DrawItem {
+            z: self.z,
+            modes: Arc::clone(&self.modes),
+            command: command.into_owned(),
+        });
+    }
+
+    fn begin_mode(&mut self, _: Token, mode: &DrawMode) {
+        Arc::make_mut(&mut self.modes).push(mode.clone());
+    }
+
+    fn end_mode(&mut self, _: Token, _mode: &DrawMode) {
+        Arc::make_mut(&mut self.modes).pop();
+    }
+}
+
+impl Draw for DrawList {}
+
+#[allow(dead_code)]
+fn assert_send() {
+    fn f<T: Send>() {}
+    f::<DrawList>();
+}
@@ -0,0 +1,152 @@
+//! Active drawing modes of the window and texture canvases.
+//!
+//! raylib's modes cannot be nested (e.g., `EndScissorMode()` disables clipping entirely),
+//! so the active modes are tracked here and the enclosing mode of the same kind is restored when a mode ends.
+use super::DrawMode;
+use std::cell::RefCell;
+use std::mem::discriminant;
+
+thread_local! {
+    // Modes of each render target (the last one is the current target).
+    static TARGETS: RefCell<Vec<Vec<DrawMode>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Start drawing to a new render target, suspending the modes of the current one.
+pub(crate) fn push_target() {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        if let Some(modes) = targets.last() {
+            for mode in modes.iter().rev() {
+                mode.end();
+            }
+        }
+        targets.push(Vec::new());
+    });
+}
+
+/// Finish drawing to the current render target, resuming the modes of the previous one.
+pub(crate) fn pop_target() {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        if let Some(modes) = targets.pop() {
+            for mode in modes.iter().rev() {
+                mode.end();
+            }
+        }
+        if let Some(modes) = targets.last() {
+            for (i, mode) in modes.iter().enumerate() {
+                // Only the innermost mode of each kind is effective.
+                if !modes[i + 1..].iter().any(|m| same_kind(m, mode)) {
+                    mode.begin();
+                }
+            }
+        }
+    });
+}
+
+/// Begin `mode` on the current render target.
+///
+/// Scissor areas are intersected with the enclosing scissor area.
+pub(crate) fn begin_mode(mode: &DrawMode) {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        if targets.is_empty() {
+            targets.push(Vec::new());
+        }
+        let modes = targets.last_mut().expect("unreachable");
+        let mode = nested(modes, mode);
+        mode.begin();
+        modes.push(mode);
+    });
+}
+
+/// Get `mode` as it takes effect inside `modes` (scissor areas are clipped by the enclosing scissor area).
+fn nested(modes: &[DrawMode], mode: &DrawMode) -> DrawMode {
+    let mut mode = mode.clone();
+    if let DrawMode::Scissor(area) = &mut mode {
+        let outer = modes.iter().rev().find_map(|m| match m {
+            DrawMode::Scissor(outer) => Some(*outer),
+            _ => None,
+        });
+        if let Some(outer) = outer {
+            *area = area.get_collision_rec(outer);
+        }
+    }
+    mode
+}
+
+/// End the innermost mode of the current render target, restoring the enclosing mode of the same kind.
+pub(crate) fn end_mode() {
+    TARGETS.with(|targets| {
+        let mut targets = targets.borrow_mut();
+        let modes = match targets.last_mut() {
+            Some(modes) => modes,
+            None => return,
+        };
+        if let Some(mode) = modes.pop() {
+            match modes.iter().rev().find(|m| same_kind(m, &mode)) {
+                Some(outer) => outer.begin(),
+                None => mode.end(),
+            }
+        }
+    });
+}
+
+fn same_kind(a: &DrawMode, b: &DrawMode) -> bool {
+    match (a, b) {
+        (
+            DrawMode::Blend(_) | DrawMode::CustomBlend(_),
+            DrawMode::Blend(_) | DrawMode::CustomBlend(_),
+        ) => true,
+        _ => discriminant(a) == discriminant(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::drawing::BlendMode;
+    use crate::Rectangle;
+
+    fn scissor(x: i32, y: i32, width: i32, height: i32) -> DrawMode {
+        DrawMode::Scissor(Rectangle::new(x, y, width, height))
+    }
+
+    #[test]
+    fn scissor_areas_intersect_with_innermost_area() {
+        let modes = [
+            scissor(0, 0, 100, 100),
+            DrawMode::Blend(BlendMode::Additive),
+        ];
+        assert_eq!(
+            nested(&[], &scissor(10, 10, 20, 20)),
+            scissor(10, 10, 20, 20)
+        );
+        assert_eq!(
+            nested(&modes, &scissor(90, -10, 20, 20)),
+            scissor(90, 0, 10, 10)
+        );
+
+        let modes = [scissor(0, 0, 100, 100), scissor(50, 50, 10, 10)];
+        assert_eq!(
+            nested(&modes, &scissor(0, 0, 55, 55)),
+            scissor(50, 50, 5, 5)
+        );
+        assert_eq!(nested(&modes, &scissor(0, 0, 10, 10)), scissor(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn other_modes_are_unchanged() {
+        let modes = [scissor(0, 0, 10, 10)];
+        let blend = DrawMode::Blend(BlendMode::Multiplied);
+        assert_eq!(nested(&modes, &blend), blend);
+    }
+
+    #[test]
+    fn blend_modes_are_the_same_kind() {
+        let blend = DrawMode::Blend(BlendMode::Additive);
+        assert!(same_kind(&blend, &DrawMode::Blend(BlendMode::Alpha)));
+        assert!(same_kind(&scissor(0, 0, 1, 1), &scissor(1, 1, 2, 2)));
+        assert!(!same_kind(&blend, &scissor(0, 0, 1, 1)));
+    }
+}
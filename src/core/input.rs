@@ -1,6 +1,9 @@
+pub mod action;
 pub mod gamepad;
+pub mod inject;
 pub mod keyboard;
 pub mod mouse;
+pub(crate) mod record;
 pub mod touch;
 
 pub use self::keyboard::{Key, Keyboard};
@@ -0,0 +1,374 @@
+//! Action-based input mapping.
+//!
+//! ```no_run
+//! use rfraylib::core::input::action::{Binding, InputMap};
+//! use rfraylib::core::input::gamepad::{GamepadAxis, GamepadButton};
+//! use rfraylib::Key;
+//!
+//! let mut actions = InputMap::new();
+//! actions
+//!     .bind_button("jump", Binding::key(Key::Space))
+//!     .bind_button("jump", Binding::gamepad_button(GamepadButton::RightFaceDown))
+//!     .bind_axis2d_x("move", Binding::key(Key::Right))
+//!     .bind_axis2d_x("move", Binding::key(Key::Left).inverted())
+//!     .bind_axis2d_x("move", Binding::gamepad_axis(GamepadAxis::LeftX).dead_zone(0.2));
+//!
+//! // Every frame:
+//! fn update(actions: &mut InputMap, system: &rfraylib::System) {
+//!     actions.update(system);
+//!     if actions.pressed("jump") {
+//!         // ...
+//!     }
+//!     let (x, y) = actions.axis2d("move");
+//! }
+//! ```
+use super::gamepad::{GamepadAxis, GamepadButton};
+use super::keyboard::Key;
+use super::mouse::MouseButton;
+use crate::System;
+use std::collections::BTreeMap;
+
+/// Physical input source of a [`Binding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Input {
+    Key(Key),
+    MouseButton(MouseButton),
+    GamepadButton(GamepadButton),
+    GamepadAxis(GamepadAxis),
+    MouseWheel,
+}
+
+/// Modifier keys that have to be held for a binding to be active (either left or right key).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+    fn is_held(self, system: &System) -> bool {
+        let keyboard = system.keyboard();
+        let held = |l, r| keyboard.is_key_down(l) || keyboard.is_key_down(r);
+        (!self.shift || held(Key::LeftShift, Key::RightShift))
+            && (!self.ctrl || held(Key::LeftControl, Key::RightControl))
+            && (!self.alt || held(Key::LeftAlt, Key::RightAlt))
+    }
+}
+
+/// Binding of an [`Input`] to an action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Binding {
+    pub input: Input,
+    pub modifiers: Modifiers,
+
+    /// Analog values whose magnitude is below this are treated as `0.0`
+    /// (the rest of the range is rescaled to `0.0..=1.0`).
+    pub dead_zone: f32,
+
+    /// Negate the value (e.g., to bind `Key::Left` to the negative side of an axis).
+    pub invert: bool,
+}
+
+impl Binding {
+    pub fn new(input: Input) -> Self {
+        Self {
+            input,
+            modifiers: Modifiers::default(),
+            dead_zone: 0.0,
+            invert: false,
+        }
+    }
+
+    pub fn key(key: Key) -> Self {
+        Self::new(Input::Key(key))
+    }
+
+    pub fn mouse_button(button: MouseButton) -> Self {
+        Self::new(Input::MouseButton(button))
+    }
+
+    pub fn gamepad_button(button: GamepadButton) -> Self {
+        Self::new(Input::GamepadButton(button))
+    }
+
+    pub fn gamepad_axis(axis: GamepadAxis) -> Self {
+        Self::new(Input::GamepadAxis(axis))
+    }
+
+    pub fn mouse_wheel() -> Self {
+        Self::new(Input::MouseWheel)
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    pub fn dead_zone(mut self, dead_zone: f32) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    pub fn inverted(mut self) -> Self {
+        self.invert = !self.invert;
+        self
+    }
+
+    /// Get the current value of this binding (`0.0` or `1.0` for buttons).
+    pub fn value(&self, system: &System, gamepad: Option<u32>) -> f32 {
+        if !self.modifiers.is_held(system) {
+            return 0.0;
+        }
+        let mut gamepads = system
+            .gamepads()
+            .filter(|g| gamepad.is_none() || gamepad == Some(g.index));
+        let value = match self.input {
+            Input::Key(key) => to_f32(system.keyboard().is_key_down(key)),
+            Input::MouseButton(button) => to_f32(system.mouse().is_button_down(button)),
+            Input::GamepadButton(button) => to_f32(gamepads.any(|g| g.is_button_down(button))),
+            Input::GamepadAxis(axis) => gamepads
+                .map(|g| g.get_axis_movement(axis))
+                .fold(0.0_f32, |acc, v| if v.abs() > acc.abs() { v } else { acc }),
+            Input::MouseWheel => system.mouse().get_wheel_move(),
+        };
+        let value = apply_dead_zone(value, self.dead_zone);
+        if self.invert {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+/// Action that bindings are grouped under.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    /// Digital action (active while any binding has a positive value).
+    Button(Vec<Binding>),
+
+    /// One-dimensional analog action (sum of the binding values clamped to `-1.0..=1.0`).
+    Axis(Vec<Binding>),
+
+    /// Two-dimensional analog action (the length of the vector is clamped to `1.0`).
+    Axis2d { x: Vec<Binding>, y: Vec<Binding> },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct ActionState {
+    down: bool,
+    pressed: bool,
+    released: bool,
+    value: (f32, f32),
+}
+
+/// Named actions bound to keyboard, mouse and gamepad inputs.
+///
+/// [`InputMap::update`] must be called once per frame to refresh the action states.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputMap {
+    gamepad: Option<u32>,
+    actions: BTreeMap<String, Action>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    states: BTreeMap<String, ActionState>,
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict gamepad bindings to the gamepad with the given index (`None` means any gamepad).
+    pub fn set_gamepad(&mut self, gamepad: Option<u32>) {
+        self.gamepad = gamepad;
+    }
+
+    pub fn gamepad(&self) -> Option<u32> {
+        self.gamepad
+    }
+
+    /// Bind `binding` to a button action, creating the action if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is a 2D axis action.
+    pub fn bind_button(&mut self, name: &str, binding: Binding) -> &mut Self {
+        match self.entry(name, || Action::Button(Vec::new())) {
+            Action::Button(bindings) | Action::Axis(bindings) => bindings.push(binding),
+            Action::Axis2d { .. } => panic!("action {:?} is not a button", name),
+        }
+        self
+    }
+
+    /// Bind `binding` to an axis action, creating the action if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is a 2D axis action.
+    pub fn bind_axis(&mut self, name: &str, binding: Binding) -> &mut Self {
+        match self.entry(name, || Action::Axis(Vec::new())) {
+            Action::Button(bindings) | Action::Axis(bindings) => bindings.push(binding),
+            Action::Axis2d { .. } => panic!("action {:?} is not an axis", name),
+        }
+        self
+    }
+
+    /// Bind `binding` to the x axis of a 2D axis action, creating the action if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is a button or axis action.
+    pub fn bind_axis2d_x(&mut self, name: &str, binding: Binding) -> &mut Self {
+        match self.entry(name, Self::empty_axis2d) {
+            Action::Axis2d { x, .. } => x.push(binding),
+            _ => panic!("action {:?} is not a 2D axis", name),
+        }
+        self
+    }
+
+    /// Bind `binding` to the y axis of a 2D axis action, creating the action if needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is a button or axis action.
+    pub fn bind_axis2d_y(&mut self, name: &str, binding: Binding) -> &mut Self {
+        match self.entry(name, Self::empty_axis2d) {
+            Action::Axis2d { y, .. } => y.push(binding),
+            _ => panic!("action {:?} is not a 2D axis", name),
+        }
+        self
+    }
+
+    /// Add or replace an action (e.g., after the player rebinds controls).
+    pub fn insert_action(&mut self, name: &str, action: Action) {
+        self.actions.insert(name.to_owned(), action);
+    }
+
+    pub fn remove_action(&mut self, name: &str) -> Option<Action> {
+        self.states.remove(name);
+        self.actions.remove(name)
+    }
+
+    pub fn action(&self, name: &str) -> Option<&Action> {
+        self.actions.get(name)
+    }
+
+    pub fn action_mut(&mut self, name: &str) -> Option<&mut Action> {
+        self.actions.get_mut(name)
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = (&str, &Action)> {
+        self.actions.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Refresh the action states from the current input state.
+    pub fn update(&mut self, system: &System) {
+        let gamepad = self.gamepad;
+        let sum = |bindings: &[Binding]| -> f32 {
+            bindings
+                .iter()
+                .map(|b| b.value(system, gamepad))
+                .sum::<f32>()
+                .clamp(-1.0, 1.0)
+        };
+        for (name, action) in &self.actions {
+            let (value, down) = match action {
+                Action::Button(bindings) => {
+                    let down = bindings.iter().any(|b| b.value(system, gamepad) > 0.0);
+                    ((to_f32(down), 0.0), down)
+                }
+                Action::Axis(bindings) => {
+                    let v = sum(bindings);
+                    ((v, 0.0), v != 0.0)
+                }
+                Action::Axis2d { x, y } => {
+                    let (x, y) = (sum(x), sum(y));
+                    let len = (x * x + y * y).sqrt();
+                    let value = if len > 1.0 {
+                        (x / len, y / len)
+                    } else {
+                        (x, y)
+                    };
+                    (value, len > 0.0)
+                }
+            };
+            let state = self.states.entry(name.clone()).or_default();
+            state.pressed = down && !state.down;
+            state.released = !down && state.down;
+            state.down = down;
+            state.value = value;
+        }
+    }
+
+    /// Check if an action has become active in this frame.
+    pub fn pressed(&self, name: &str) -> bool {
+        self.state(name).pressed
+    }
+
+    /// Check if an action is active.
+    pub fn down(&self, name: &str) -> bool {
+        self.state(name).down
+    }
+
+    /// Check if an action has become inactive in this frame.
+    pub fn released(&self, name: &str) -> bool {
+        self.state(name).released
+    }
+
+    /// Get the value of an action (the X value for 2D axes).
+    pub fn axis(&self, name: &str) -> f32 {
+        self.state(name).value.0
+    }
+
+    pub fn axis2d(&self, name: &str) -> (f32, f32) {
+        self.state(name).value
+    }
+
+    fn state(&self, name: &str) -> ActionState {
+        self.states.get(name).copied().unwrap_or_default()
+    }
+
+    fn entry(&mut self, name: &str, f: impl FnOnce() -> Action) -> &mut Action {
+        self.actions.entry(name.to_owned()).or_insert_with(f)
+    }
+
+    fn empty_axis2d() -> Action {
+        Action::Axis2d {
+            x: Vec::new(),
+            y: Vec::new(),
+        }
+    }
+}
+
+fn to_f32(b: bool) -> f32 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if dead_zone <= 0.0 {
+        value
+    } else if value.abs() < dead_zone || dead_zone >= 1.0 {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
@@ -1,52 +1,174 @@
+use crate::structs::Vector2;
 use std::collections::BTreeSet;
 use std::os::raw::c_int;
 
 #[derive(Debug)]
 pub struct Gamepad {
     pub(crate) index: u32,
+    pub(crate) state: Option<GamepadState>,
+    sticks: [StickSettings; 2],
+    trigger_threshold: f32,
+    triggers: [TriggerState; 2],
 }
 
 impl Gamepad {
+    pub(crate) fn new(index: u32) -> Self {
+        Self {
+            index,
+            state: None,
+            sticks: [StickSettings::default(); 2],
+            trigger_threshold: Self::DEFAULT_TRIGGER_THRESHOLD,
+            triggers: [TriggerState::default(); 2],
+        }
+    }
+
+    pub const DEFAULT_TRIGGER_THRESHOLD: f32 = 0.5;
+
     pub(crate) fn is_available(&self) -> bool {
-        unsafe { raylib4_sys::IsGamepadAvailable(self.index as c_int) }
+        match &self.state {
+            Some(state) => state.is_available,
+            None => unsafe { raylib4_sys::IsGamepadAvailable(self.index as c_int) },
+        }
+    }
+
+    /// Get the index of this gamepad.
+    pub fn index(&self) -> u32 {
+        self.index
     }
 
     /// Get gamepad internal name id.
     pub fn get_name(&self) -> &str {
-        let n =
-            unsafe { std::ffi::CStr::from_ptr(raylib4_sys::GetGamepadName(self.index as c_int)) };
-        n.to_str().expect("unreachable")
+        let p = unsafe { raylib4_sys::GetGamepadName(self.index as c_int) };
+        if p.is_null() {
+            return "";
+        }
+        let n = unsafe { std::ffi::CStr::from_ptr(p) };
+        n.to_str().unwrap_or("")
     }
 
     /// Check if a gamepad button has been pressed once.
     pub fn is_button_pressed(&self, button: GamepadButton) -> bool {
-        unsafe { raylib4_sys::IsGamepadButtonPressed(self.index as c_int, button as c_int) }
+        match &self.state {
+            Some(state) => state.pressed.contains(&button),
+            None => unsafe {
+                raylib4_sys::IsGamepadButtonPressed(self.index as c_int, button as c_int)
+            },
+        }
     }
 
     /// Check if a gamepad button is being pressed.
     pub fn is_button_down(&self, button: GamepadButton) -> bool {
-        unsafe { raylib4_sys::IsGamepadButtonDown(self.index as c_int, button as c_int) }
+        match &self.state {
+            Some(state) => state.down.contains(&button),
+            None => unsafe {
+                raylib4_sys::IsGamepadButtonDown(self.index as c_int, button as c_int)
+            },
+        }
     }
 
     /// Check if a gamepad button has been released once.
     pub fn is_button_released(&self, button: GamepadButton) -> bool {
-        unsafe { raylib4_sys::IsGamepadButtonReleased(self.index as c_int, button as c_int) }
+        match &self.state {
+            Some(state) => state.released.contains(&button),
+            None => unsafe {
+                raylib4_sys::IsGamepadButtonReleased(self.index as c_int, button as c_int)
+            },
+        }
     }
 
     /// Check if a gamepad button is NOT being pressed.
     pub fn is_button_up(&self, button: GamepadButton) -> bool {
-        unsafe { raylib4_sys::IsGamepadButtonUp(self.index as c_int, button as c_int) }
+        match &self.state {
+            Some(state) => !state.down.contains(&button),
+            None => unsafe { raylib4_sys::IsGamepadButtonUp(self.index as c_int, button as c_int) },
+        }
     }
 
     /// Get gamepad axis count for a gamepad.
     pub fn get_axises(&self) -> BTreeSet<GamepadAxis> {
-        let n = unsafe { raylib4_sys::GetGamepadAxisCount(self.index as c_int) as u32 };
-        (0..n).filter_map(GamepadAxis::from_u32).collect()
+        (0..self.axis_count())
+            .filter_map(GamepadAxis::from_u32)
+            .collect()
+    }
+
+    fn axis_count(&self) -> u32 {
+        match &self.state {
+            Some(state) => state.axes.len() as u32,
+            None => unsafe { raylib4_sys::GetGamepadAxisCount(self.index as c_int) as u32 },
+        }
     }
 
     /// Get axis movement value for a gamepad axis.
     pub fn get_axis_movement(&self, axis: GamepadAxis) -> f32 {
-        unsafe { raylib4_sys::GetGamepadAxisMovement(self.index as c_int, axis as c_int) }
+        match &self.state {
+            Some(state) => state.axes.get(axis as usize).copied().unwrap_or(0.0),
+            None => unsafe {
+                raylib4_sys::GetGamepadAxisMovement(self.index as c_int, axis as c_int)
+            },
+        }
+    }
+
+    /// Get the position of a stick processed by its [`StickSettings`].
+    pub fn stick(&self, stick: Stick) -> Vector2 {
+        let (x_axis, y_axis) = stick.axes();
+        let x = self.get_axis_movement(x_axis);
+        let y = self.get_axis_movement(y_axis);
+        let (x, y) = self.sticks[stick as usize].apply(x, y);
+        Vector2::new(x, y)
+    }
+
+    pub fn stick_settings(&self, stick: Stick) -> &StickSettings {
+        &self.sticks[stick as usize]
+    }
+
+    pub fn set_stick_settings(&mut self, stick: Stick, settings: StickSettings) {
+        self.sticks[stick as usize] = settings;
+    }
+
+    /// Get the pressure level of a trigger [0..1] (`0.0` if the gamepad has no such trigger).
+    pub fn trigger(&self, trigger: Trigger) -> f32 {
+        let axis = trigger.axis();
+        // Missing axes read as 0.0, which would be a half-pressed trigger.
+        let v = if (axis as u32) < self.axis_count() {
+            self.get_axis_movement(axis)
+        } else {
+            -1.0
+        };
+        ((v + 1.0) / 2.0).clamp(0.0, 1.0)
+    }
+
+    pub fn trigger_threshold(&self) -> f32 {
+        self.trigger_threshold
+    }
+
+    /// Set the pressure level at which triggers are reported as down.
+    pub fn set_trigger_threshold(&mut self, threshold: f32) {
+        self.trigger_threshold = threshold;
+    }
+
+    /// Check if a trigger has crossed the threshold in the last frame.
+    pub fn is_trigger_pressed(&self, trigger: Trigger) -> bool {
+        self.triggers[trigger as usize].pressed
+    }
+
+    /// Check if a trigger is beyond the threshold.
+    pub fn is_trigger_down(&self, trigger: Trigger) -> bool {
+        self.trigger(trigger) > self.trigger_threshold
+    }
+
+    /// Check if a trigger has fallen below the threshold in the last frame.
+    pub fn is_trigger_released(&self, trigger: Trigger) -> bool {
+        self.triggers[trigger as usize].released
+    }
+
+    pub(crate) fn update_triggers(&mut self) {
+        for trigger in [Trigger::Left, Trigger::Right] {
+            let down = self.is_available() && self.is_trigger_down(trigger);
+            let state = &mut self.triggers[trigger as usize];
+            state.pressed = down && !state.down;
+            state.released = !down && state.down;
+            state.down = down;
+        }
     }
 
     pub(crate) fn get_button_pressed() -> Option<GamepadButton> {
@@ -54,7 +176,276 @@ impl Gamepad {
         if n == -1 {
             return None;
         }
-        let b = match n {
+        Some(GamepadButton::from_u32(n as u32))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left = 0,
+    Right = 1,
+}
+
+impl Stick {
+    fn axes(self) -> (GamepadAxis, GamepadAxis) {
+        match self {
+            Stick::Left => (GamepadAxis::LeftX, GamepadAxis::LeftY),
+            Stick::Right => (GamepadAxis::RightX, GamepadAxis::RightY),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    Left = 0,
+    Right = 1,
+}
+
+impl Trigger {
+    fn axis(self) -> GamepadAxis {
+        match self {
+            Trigger::Left => GamepadAxis::LeftTrigger,
+            Trigger::Right => GamepadAxis::RightTrigger,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TriggerState {
+    down: bool,
+    pressed: bool,
+    released: bool,
+}
+
+/// Mapping from the (dead-zone adjusted) stick magnitude [0..1] to the output magnitude [0..1].
+#[derive(Debug, Clone, Copy)]
+pub enum ResponseCurve {
+    Linear,
+    Quadratic,
+    Custom(fn(f32) -> f32),
+}
+
+impl ResponseCurve {
+    fn apply(self, v: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => v,
+            ResponseCurve::Quadratic => v * v,
+            ResponseCurve::Custom(f) => f(v).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Processing applied by [`Gamepad::stick`].
+#[derive(Debug, Clone, Copy)]
+pub struct StickSettings {
+    /// Stick magnitudes below this are treated as zero (handles drifting sticks).
+    pub radial_dead_zone: f32,
+
+    /// Per-axis values below this are treated as zero (makes it easier to move along an axis).
+    pub axial_dead_zone: f32,
+
+    /// Stick magnitudes above `1.0 - outer_dead_zone` are treated as `1.0`.
+    pub outer_dead_zone: f32,
+
+    pub curve: ResponseCurve,
+}
+
+impl StickSettings {
+    fn apply(&self, mut x: f32, mut y: f32) -> (f32, f32) {
+        if x.abs() < self.axial_dead_zone {
+            x = 0.0;
+        }
+        if y.abs() < self.axial_dead_zone {
+            y = 0.0;
+        }
+
+        let magnitude = (x * x + y * y).sqrt();
+        if magnitude <= self.radial_dead_zone || magnitude == 0.0 {
+            return (0.0, 0.0);
+        }
+        let range = 1.0 - self.radial_dead_zone - self.outer_dead_zone;
+        let scaled = if range <= 0.0 {
+            1.0
+        } else {
+            ((magnitude - self.radial_dead_zone) / range).clamp(0.0, 1.0)
+        };
+        let m = self.curve.apply(scaled) / magnitude;
+        (x * m, y * m)
+    }
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        Self {
+            radial_dead_zone: 0.1,
+            axial_dead_zone: 0.0,
+            outer_dead_zone: 0.05,
+            curve: ResponseCurve::Linear,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GamepadEvent {
+    /// A gamepad has been connected (index and name).
+    Connected(u32, String),
+
+    /// A gamepad has been disconnected (index and name).
+    Disconnected(u32, String),
+}
+
+#[derive(Debug, Clone)]
+struct PlayerSlot {
+    index: u32,
+    name: String,
+    connected: bool,
+}
+
+/// Tracks gamepad connections and assigns them to player slots.
+///
+/// A disconnected gamepad keeps its slot, so that it gets the same slot when reconnected.
+#[derive(Debug)]
+pub(crate) struct GamepadTracker {
+    connected: Vec<Option<String>>,
+    slots: Vec<Option<PlayerSlot>>,
+    events: Vec<GamepadEvent>,
+}
+
+impl GamepadTracker {
+    pub(crate) fn new(max_gamepads: u32) -> Self {
+        Self {
+            connected: vec![None; max_gamepads as usize],
+            slots: vec![None; max_gamepads as usize],
+            events: Vec::new(),
+        }
+    }
+
+    pub(crate) fn events(&self) -> &[GamepadEvent] {
+        &self.events
+    }
+
+    /// Detect connections and disconnections (the events of the previous call are cleared).
+    pub(crate) fn update(&mut self, gamepads: &[Gamepad]) {
+        self.events.clear();
+        for (gamepad, connected) in gamepads.iter().zip(self.connected.iter_mut()) {
+            match (gamepad.is_available(), connected.take()) {
+                (true, None) => {
+                    let name = gamepad.get_name().to_owned();
+                    self.events
+                        .push(GamepadEvent::Connected(gamepad.index, name.clone()));
+                    *connected = Some(name);
+                }
+                (false, Some(name)) => {
+                    self.events
+                        .push(GamepadEvent::Disconnected(gamepad.index, name));
+                }
+                (_, c) => *connected = c,
+            }
+        }
+
+        let events = std::mem::take(&mut self.events);
+        for event in &events {
+            match event {
+                GamepadEvent::Connected(index, name) => self.assign_slot(*index, name),
+                GamepadEvent::Disconnected(index, _) => {
+                    for slot in self.slots.iter_mut().flatten() {
+                        if slot.index == *index && slot.connected {
+                            slot.connected = false;
+                        }
+                    }
+                }
+            }
+        }
+        self.events = events;
+    }
+
+    fn assign_slot(&mut self, index: u32, name: &str) {
+        // Prefer the slot that the same gamepad had before, then an empty slot,
+        // and then the slot of a gamepad that has been disconnected.
+        let slot = self
+            .find_slot(|s| !s.connected && s.index == index && s.name == name)
+            .or_else(|| self.find_slot(|s| !s.connected && s.name == name))
+            .or_else(|| self.slots.iter().position(|s| s.is_none()))
+            .or_else(|| self.find_slot(|s| !s.connected));
+        if let Some(i) = slot {
+            self.slots[i] = Some(PlayerSlot {
+                index,
+                name: name.to_owned(),
+                connected: true,
+            });
+        }
+    }
+
+    fn find_slot(&self, f: impl Fn(&PlayerSlot) -> bool) -> Option<usize> {
+        self.slots.iter().position(|s| s.as_ref().is_some_and(&f))
+    }
+
+    /// Get the index of the gamepad assigned to a player slot (`None` if it is disconnected).
+    pub(crate) fn slot_gamepad(&self, slot: usize) -> Option<u32> {
+        self.slots
+            .get(slot)?
+            .as_ref()
+            .filter(|s| s.connected)
+            .map(|s| s.index)
+    }
+
+    pub(crate) fn gamepad_slot(&self, index: u32) -> Option<usize> {
+        self.find_slot(|s| s.connected && s.index == index)
+    }
+
+    pub(crate) fn release_slot(&mut self, slot: usize) {
+        if let Some(s) = self.slots.get_mut(slot) {
+            *s = None;
+        }
+    }
+}
+
+/// Gamepad state of a frame (used instead of the live state while replaying or injecting input).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct GamepadState {
+    pub is_available: bool,
+    pub down: BTreeSet<GamepadButton>,
+    pub pressed: BTreeSet<GamepadButton>,
+    pub released: BTreeSet<GamepadButton>,
+    pub axes: Vec<f32>,
+}
+
+impl GamepadState {
+    pub(crate) fn capture(index: u32) -> Self {
+        let index = index as c_int;
+        let mut state = Self {
+            is_available: unsafe { raylib4_sys::IsGamepadAvailable(index) },
+            ..Self::default()
+        };
+        if !state.is_available {
+            return state;
+        }
+        for button in GamepadButton::all() {
+            if unsafe { raylib4_sys::IsGamepadButtonDown(index, button as c_int) } {
+                state.down.insert(button);
+            }
+            if unsafe { raylib4_sys::IsGamepadButtonPressed(index, button as c_int) } {
+                state.pressed.insert(button);
+            }
+            if unsafe { raylib4_sys::IsGamepadButtonReleased(index, button as c_int) } {
+                state.released.insert(button);
+            }
+        }
+        let n = unsafe { raylib4_sys::GetGamepadAxisCount(index) };
+        state.axes = (0..n)
+            .map(|axis| unsafe { raylib4_sys::GetGamepadAxisMovement(index, axis) })
+            .collect();
+        state
+    }
+}
+
+impl GamepadButton {
+    pub(crate) fn all() -> impl Iterator<Item = Self> {
+        (1..=Self::RightThumb as u32).map(Self::from_u32)
+    }
+
+    pub(crate) fn from_u32(n: u32) -> Self {
+        match n {
             1 => GamepadButton::LeftFaceUp,
             2 => GamepadButton::LeftFaceRight,
             3 => GamepadButton::LeftFaceDown,
@@ -73,12 +464,12 @@ impl Gamepad {
             16 => GamepadButton::LeftThumb,
             17 => GamepadButton::RightThumb,
             _ => GamepadButton::Unknown,
-        };
-        Some(b)
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum GamepadButton {
     Unknown = 0,
@@ -102,6 +493,7 @@ pub enum GamepadButton {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum GamepadAxis {
     /// Gamepad left stick X axis.
@@ -124,7 +516,7 @@ pub enum GamepadAxis {
 }
 
 impl GamepadAxis {
-    fn from_u32(v: u32) -> Option<Self> {
+    pub(crate) fn from_u32(v: u32) -> Option<Self> {
         match v {
             0 => Some(Self::LeftX),
             1 => Some(Self::LeftY),
@@ -136,3 +528,92 @@ impl GamepadAxis {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gamepad(axes: &[f32]) -> Gamepad {
+        let mut gamepad = Gamepad::new(0);
+        gamepad.state = Some(GamepadState {
+            is_available: true,
+            axes: axes.to_vec(),
+            ..Default::default()
+        });
+        gamepad
+    }
+
+    fn assert_near((x, y): (f32, f32), (ex, ey): (f32, f32)) {
+        assert!(
+            (x - ex).abs() < 1e-5 && (y - ey).abs() < 1e-5,
+            "({}, {}) != ({}, {})",
+            x,
+            y,
+            ex,
+            ey
+        );
+    }
+
+    #[test]
+    fn stick_dead_zones() {
+        let settings = StickSettings {
+            radial_dead_zone: 0.2,
+            axial_dead_zone: 0.1,
+            outer_dead_zone: 0.2,
+            curve: ResponseCurve::Linear,
+        };
+        assert_near(settings.apply(0.0, 0.0), (0.0, 0.0));
+        assert_near(settings.apply(0.15, -0.1), (0.0, 0.0));
+        assert_near(settings.apply(0.05, 0.5), (0.0, 0.5));
+        assert_near(settings.apply(-0.9, 0.0), (-1.0, 0.0));
+        assert_near(settings.apply(0.0, 0.8), (0.0, 1.0));
+        assert_near(settings.apply(0.6, 0.8), (0.6, 0.8));
+    }
+
+    #[test]
+    fn stick_response_curves() {
+        let mut settings = StickSettings {
+            radial_dead_zone: 0.0,
+            axial_dead_zone: 0.0,
+            outer_dead_zone: 0.0,
+            curve: ResponseCurve::Quadratic,
+        };
+        assert_near(settings.apply(0.5, 0.0), (0.25, 0.0));
+        settings.curve = ResponseCurve::Custom(|v| v * 2.0);
+        assert_near(settings.apply(0.0, -0.8), (0.0, -1.0));
+
+        // No range left between the dead zones.
+        settings.radial_dead_zone = 0.6;
+        settings.outer_dead_zone = 0.6;
+        assert_near(settings.apply(0.7, 0.0), (1.0, 0.0));
+    }
+
+    #[test]
+    fn trigger_pressure() {
+        let gamepad = gamepad(&[0.0, 0.0, 0.0, 0.0, -1.0, 0.5]);
+        assert_eq!(gamepad.trigger(Trigger::Left), 0.0);
+        assert_eq!(gamepad.trigger(Trigger::Right), 0.75);
+        assert!(!gamepad.is_trigger_down(Trigger::Left));
+        assert!(gamepad.is_trigger_down(Trigger::Right));
+    }
+
+    #[test]
+    fn missing_triggers_are_released() {
+        let gamepad = gamepad(&[0.0; 4]);
+        for trigger in [Trigger::Left, Trigger::Right] {
+            assert_eq!(gamepad.trigger(trigger), 0.0);
+            assert!(!gamepad.is_trigger_down(trigger));
+        }
+    }
+
+    #[test]
+    fn trigger_threshold_is_exclusive() {
+        let mut gamepad = gamepad(&[0.0, 0.0, 0.0, 0.0, -1.0, 1.0]);
+        assert!(!gamepad.is_trigger_down(Trigger::Left));
+        assert!(gamepad.is_trigger_down(Trigger::Right));
+        gamepad.set_trigger_threshold(1.0);
+        assert!(!gamepad.is_trigger_down(Trigger::Right));
+        gamepad.set_trigger_threshold(0.0);
+        assert!(!gamepad.is_trigger_down(Trigger::Left));
+    }
+}
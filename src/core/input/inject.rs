@@ -0,0 +1,178 @@
+//! Synthetic input injection (e.g., for automated UI tests).
+use super::gamepad::{GamepadAxis, GamepadButton, GamepadState};
+use super::keyboard::Key;
+use super::mouse::MouseButton;
+use super::record::InputFrame;
+use super::touch::TouchPoint;
+use crate::Position;
+
+/// Input events that are merged into the input state of the next frame.
+///
+/// See [`System::inject_input`](crate::System::inject_input).
+#[derive(Debug, Default, Clone)]
+pub struct InputInjection {
+    key_presses: Vec<Key>,
+    key_releases: Vec<Key>,
+    keys_down: Vec<Key>,
+    chars: Vec<char>,
+    mouse_position: Option<Position>,
+    mouse_presses: Vec<MouseButton>,
+    mouse_releases: Vec<MouseButton>,
+    mouse_buttons_down: Vec<MouseButton>,
+    wheel_move: f32,
+    touch_points: Option<Vec<TouchPoint>>,
+    gamepad_presses: Vec<(u32, GamepadButton)>,
+    gamepad_releases: Vec<(u32, GamepadButton)>,
+    gamepad_axes: Vec<(u32, GamepadAxis, f32)>,
+}
+
+impl InputInjection {
+    /// Press a key (it is reported as pressed and down).
+    pub fn press_key(&mut self, key: Key) -> &mut Self {
+        self.key_presses.push(key);
+        self
+    }
+
+    /// Release a key (it is reported as released and up).
+    pub fn release_key(&mut self, key: Key) -> &mut Self {
+        self.key_releases.push(key);
+        self
+    }
+
+    /// Keep a key down (without reporting it as pressed).
+    pub fn hold_key(&mut self, key: Key) -> &mut Self {
+        self.keys_down.push(key);
+        self
+    }
+
+    /// Queue chars to be returned by `Keyboard::take_pressed_chars`.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        self.chars.extend(text.chars());
+        self
+    }
+
+    /// Move the mouse cursor (the delta is calculated from the previous position).
+    pub fn move_mouse(&mut self, position: Position) -> &mut Self {
+        self.mouse_position = Some(position);
+        self
+    }
+
+    pub fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_presses.push(button);
+        self
+    }
+
+    pub fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_releases.push(button);
+        self
+    }
+
+    /// Keep a mouse button down (without reporting it as pressed).
+    pub fn hold_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_buttons_down.push(button);
+        self
+    }
+
+    pub fn move_wheel(&mut self, delta: f32) -> &mut Self {
+        self.wheel_move += delta;
+        self
+    }
+
+    /// Replace the touch points (the first one is also reported as the touch position).
+    pub fn set_touch_points(&mut self, points: impl IntoIterator<Item = TouchPoint>) -> &mut Self {
+        self.touch_points = Some(points.into_iter().collect());
+        self
+    }
+
+    /// Press a gamepad button (the gamepad is reported as available).
+    pub fn press_gamepad_button(&mut self, gamepad: u32, button: GamepadButton) -> &mut Self {
+        self.gamepad_presses.push((gamepad, button));
+        self
+    }
+
+    pub fn release_gamepad_button(&mut self, gamepad: u32, button: GamepadButton) -> &mut Self {
+        self.gamepad_releases.push((gamepad, button));
+        self
+    }
+
+    /// Set a gamepad axis value (the gamepad is reported as available).
+    pub fn set_gamepad_axis(&mut self, gamepad: u32, axis: GamepadAxis, value: f32) -> &mut Self {
+        self.gamepad_axes.push((gamepad, axis, value));
+        self
+    }
+
+    pub(crate) fn apply(self, frame: &mut InputFrame) {
+        let keyboard = &mut frame.keyboard;
+        for key in self.keys_down {
+            keyboard.down.insert(key);
+        }
+        for key in self.key_presses {
+            keyboard.pressed.insert(key);
+            keyboard.down.insert(key);
+            keyboard.keys.push_back(key);
+        }
+        for key in self.key_releases {
+            keyboard.released.insert(key);
+            keyboard.down.remove(&key);
+        }
+        keyboard.chars.extend(self.chars);
+
+        let mouse = &mut frame.mouse;
+        if let Some(position) = self.mouse_position {
+            mouse.delta = Position {
+                x: position.x - mouse.position.x,
+                y: position.y - mouse.position.y,
+            };
+            mouse.position = position;
+        }
+        for button in self.mouse_buttons_down {
+            mouse.down.insert(button);
+        }
+        for button in self.mouse_presses {
+            mouse.pressed.insert(button);
+            mouse.down.insert(button);
+        }
+        for button in self.mouse_releases {
+            mouse.released.insert(button);
+            mouse.down.remove(&button);
+        }
+        mouse.wheel_move += self.wheel_move;
+
+        if let Some(points) = self.touch_points {
+            if let Some(point) = points.first() {
+                frame.touch.position = point.position;
+            }
+            frame.touch.points = points;
+        }
+
+        let gamepads = &mut frame.gamepads;
+        for (index, button) in self.gamepad_presses {
+            let state = gamepad_state(gamepads, index);
+            state.pressed.insert(button);
+            state.down.insert(button);
+        }
+        for (index, button) in self.gamepad_releases {
+            let state = gamepad_state(gamepads, index);
+            state.released.insert(button);
+            state.down.remove(&button);
+        }
+        for (index, axis, value) in self.gamepad_axes {
+            let state = gamepad_state(gamepads, index);
+            let i = axis as usize;
+            if state.axes.len() <= i {
+                state.axes.resize(i + 1, 0.0);
+            }
+            state.axes[i] = value;
+        }
+    }
+}
+
+fn gamepad_state(gamepads: &mut Vec<GamepadState>, index: u32) -> &mut GamepadState {
+    let i = index as usize;
+    if gamepads.len() <= i {
+        gamepads.resize_with(i + 1, GamepadState::default);
+    }
+    let state = &mut gamepads[i];
+    state.is_available = true;
+    state
+}
@@ -1,27 +1,46 @@
+use std::collections::{BTreeSet, VecDeque};
 use std::os::raw::c_int;
 
 #[derive(Debug)]
-pub struct Keyboard(pub(crate) ());
+pub struct Keyboard {
+    pub(crate) state: Option<KeyboardState>,
+}
 
 impl Keyboard {
+    pub(crate) fn new() -> Self {
+        Self { state: None }
+    }
+
     /// Check if a key has been pressed once.
     pub fn is_key_pressed(&self, key: Key) -> bool {
-        unsafe { raylib4_sys::IsKeyPressed(key as c_int) }
+        match &self.state {
+            Some(state) => state.pressed.contains(&key),
+            None => unsafe { raylib4_sys::IsKeyPressed(key as c_int) },
+        }
     }
 
     /// Check if a key is being pressed.
     pub fn is_key_down(&self, key: Key) -> bool {
-        unsafe { raylib4_sys::IsKeyDown(key as c_int) }
+        match &self.state {
+            Some(state) => state.down.contains(&key),
+            None => unsafe { raylib4_sys::IsKeyDown(key as c_int) },
+        }
     }
 
     /// Check if a key has been released once.
     pub fn is_key_released(&self, key: Key) -> bool {
-        unsafe { raylib4_sys::IsKeyReleased(key as c_int) }
+        match &self.state {
+            Some(state) => state.released.contains(&key),
+            None => unsafe { raylib4_sys::IsKeyReleased(key as c_int) },
+        }
     }
 
     /// Check if a key is NOT being pressed.
     pub fn is_key_up(&self, key: Key) -> bool {
-        unsafe { raylib4_sys::IsKeyUp(key as c_int) }
+        match &self.state {
+            Some(state) => !state.down.contains(&key),
+            None => unsafe { raylib4_sys::IsKeyUp(key as c_int) },
+        }
     }
 
     /// Set a custom key to exit program (default is ESC).
@@ -42,23 +61,29 @@ impl Keyboard {
 
 #[derive(Debug)]
 pub struct PressedChars<'a> {
-    #[allow(dead_code)]
-    keyboard: &'a Keyboard,
+    keyboard: &'a mut Keyboard,
 }
 
 impl<'a> Iterator for PressedChars<'a> {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match unsafe { raylib4_sys::GetCharPressed() } {
-                0 => return None,
-                c => {
-                    if let Some(c) = char::from_u32(c as u32) {
-                        return Some(c);
-                    } else {
-                        log::warn!("unknown unicode char (ignored): {}", c);
-                    }
+        if let Some(state) = &mut self.keyboard.state {
+            return state.chars.pop_front();
+        }
+        next_live_char()
+    }
+}
+
+fn next_live_char() -> Option<char> {
+    loop {
+        match unsafe { raylib4_sys::GetCharPressed() } {
+            0 => return None,
+            c => {
+                if let Some(c) = char::from_u32(c as u32) {
+                    return Some(c);
+                } else {
+                    log::warn!("unknown unicode char (ignored): {}", c);
                 }
             }
         }
@@ -67,30 +92,68 @@ impl<'a> Iterator for PressedChars<'a> {
 
 #[derive(Debug)]
 pub struct PressedKeys<'a> {
-    #[allow(dead_code)]
-    keyboard: &'a Keyboard,
+    keyboard: &'a mut Keyboard,
 }
 
 impl<'a> Iterator for PressedKeys<'a> {
     type Item = Key;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match unsafe { raylib4_sys::GetKeyPressed() } {
-                0 => return None,
-                c => {
-                    if let Some(c) = Key::from_u32(c as u32) {
-                        return Some(c);
-                    } else {
-                        log::warn!("unknown unicode key (ignored): {}", c);
-                    }
+        if let Some(state) = &mut self.keyboard.state {
+            return state.keys.pop_front();
+        }
+        next_live_key()
+    }
+}
+
+fn next_live_key() -> Option<Key> {
+    loop {
+        match unsafe { raylib4_sys::GetKeyPressed() } {
+            0 => return None,
+            c => {
+                if let Some(c) = Key::from_u32(c as u32) {
+                    return Some(c);
+                } else {
+                    log::warn!("unknown unicode key (ignored): {}", c);
                 }
             }
         }
     }
 }
 
+/// Keyboard state of a frame (used instead of the live state while replaying or injecting input).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct KeyboardState {
+    pub down: BTreeSet<Key>,
+    pub pressed: BTreeSet<Key>,
+    pub released: BTreeSet<Key>,
+    pub keys: VecDeque<Key>,
+    pub chars: VecDeque<char>,
+}
+
+impl KeyboardState {
+    /// Capture the live state (the key and char queues are drained).
+    pub(crate) fn capture() -> Self {
+        let mut state = Self::default();
+        for key in Key::all() {
+            if unsafe { raylib4_sys::IsKeyDown(key as c_int) } {
+                state.down.insert(key);
+            }
+            if unsafe { raylib4_sys::IsKeyPressed(key as c_int) } {
+                state.pressed.insert(key);
+            }
+            if unsafe { raylib4_sys::IsKeyReleased(key as c_int) } {
+                state.released.insert(key);
+            }
+        }
+        state.keys = std::iter::from_fn(next_live_key).collect();
+        state.chars = std::iter::from_fn(next_live_char).collect();
+        state
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Key {
     Back = 4,
@@ -204,7 +267,11 @@ pub enum Key {
 }
 
 impl Key {
-    fn from_u32(v: u32) -> Option<Self> {
+    pub(crate) fn all() -> impl Iterator<Item = Self> {
+        (0..=Self::KbMenu as u32).filter_map(Self::from_u32)
+    }
+
+    pub(crate) fn from_u32(v: u32) -> Option<Self> {
         use Key::*;
         let k = match v {
             4 => Back,
@@ -1,38 +1,63 @@
 use crate::Position;
+use std::collections::BTreeSet;
 use std::os::raw::c_int;
 
 #[derive(Debug)]
-pub struct Mouse(pub(crate) ());
+pub struct Mouse {
+    pub(crate) state: Option<MouseState>,
+}
 
 impl Mouse {
+    pub(crate) fn new() -> Self {
+        Self { state: None }
+    }
+
     /// Check if a mouse button has been pressed once.
     pub fn is_button_pressed(&self, button: MouseButton) -> bool {
-        unsafe { raylib4_sys::IsMouseButtonPressed(button as c_int) }
+        match &self.state {
+            Some(state) => state.pressed.contains(&button),
+            None => unsafe { raylib4_sys::IsMouseButtonPressed(button as c_int) },
+        }
     }
 
     /// Check if a mouse button is being pressed.
     pub fn is_button_down(&self, button: MouseButton) -> bool {
-        unsafe { raylib4_sys::IsMouseButtonDown(button as c_int) }
+        match &self.state {
+            Some(state) => state.down.contains(&button),
+            None => unsafe { raylib4_sys::IsMouseButtonDown(button as c_int) },
+        }
     }
 
     /// Check if a mouse button has been released once.
     pub fn is_button_released(&self, button: MouseButton) -> bool {
-        unsafe { raylib4_sys::IsMouseButtonReleased(button as c_int) }
+        match &self.state {
+            Some(state) => state.released.contains(&button),
+            None => unsafe { raylib4_sys::IsMouseButtonReleased(button as c_int) },
+        }
     }
 
     /// Check if a mouse button is NOT being pressed.
     pub fn is_button_up(&self, button: MouseButton) -> bool {
-        unsafe { raylib4_sys::IsMouseButtonUp(button as c_int) }
+        match &self.state {
+            Some(state) => !state.down.contains(&button),
+            None => unsafe { raylib4_sys::IsMouseButtonUp(button as c_int) },
+        }
     }
 
     /// Get mouse position XY.
     pub fn get_position(&self) -> Position {
-        unsafe { raylib4_sys::GetMousePosition() }.into()
+        match &self.state {
+            Some(state) => state.position,
+            None => unsafe { raylib4_sys::GetMousePosition() }.into(),
+        }
     }
 
     /// Get mouse delta between frames.
     pub fn get_delta(&self) -> Position {
-        unsafe { raylib4_sys::GetMouseDelta() }.into()
+        match &self.state {
+            Some(state) => state.delta,
+            None => unsafe { raylib4_sys::GetMouseDelta() }.into(),
+        }
     }
 
     /// Set mouse position XY.
@@ -52,7 +77,10 @@ impl Mouse {
 
     /// Get mouse wheel movement Y.
     pub fn get_wheel_move(&self) -> f32 {
-        unsafe { raylib4_sys::GetMouseWheelMove() }
+        match &self.state {
+            Some(state) => state.wheel_move,
+            None => unsafe { raylib4_sys::GetMouseWheelMove() },
+        }
     }
 
     // TODO: move to `Cursor`
@@ -63,6 +91,7 @@ impl Mouse {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseButton {
     Left = 0,
     Right = 1,
@@ -73,6 +102,54 @@ pub enum MouseButton {
     Back = 6,
 }
 
+impl MouseButton {
+    pub(crate) const ALL: [Self; 7] = [
+        Self::Left,
+        Self::Right,
+        Self::Middle,
+        Self::Side,
+        Self::Extra,
+        Self::Forward,
+        Self::Back,
+    ];
+}
+
+/// Mouse state of a frame (used instead of the live state while replaying or injecting input).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MouseState {
+    pub position: Position,
+    pub delta: Position,
+    pub wheel_move: f32,
+    pub down: BTreeSet<MouseButton>,
+    pub pressed: BTreeSet<MouseButton>,
+    pub released: BTreeSet<MouseButton>,
+}
+
+impl MouseState {
+    pub(crate) fn capture() -> Self {
+        let mut state = Self {
+            position: unsafe { raylib4_sys::GetMousePosition() }.into(),
+            delta: unsafe { raylib4_sys::GetMouseDelta() }.into(),
+            wheel_move: unsafe { raylib4_sys::GetMouseWheelMove() },
+            down: BTreeSet::new(),
+            pressed: BTreeSet::new(),
+            released: BTreeSet::new(),
+        };
+        for button in MouseButton::ALL {
+            if unsafe { raylib4_sys::IsMouseButtonDown(button as c_int) } {
+                state.down.insert(button);
+            }
+            if unsafe { raylib4_sys::IsMouseButtonPressed(button as c_int) } {
+                state.pressed.insert(button);
+            }
+            if unsafe { raylib4_sys::IsMouseButtonReleased(button as c_int) } {
+                state.released.insert(button);
+            }
+        }
+        state
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum MouseCursor {
     Default = 0,
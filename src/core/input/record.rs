@@ -0,0 +1,414 @@
+//! Input recording and deterministic replay.
+//!
+//! Recorded files consist of a header followed by one record per frame
+//! (little-endian, see [`InputFrame`] for the contents).
+use super::gamepad::{GamepadButton, GamepadState};
+use super::keyboard::{Key, KeyboardState};
+use super::mouse::{MouseButton, MouseState};
+use super::touch::{Gesture, TouchPoint, TouchState};
+use crate::Position;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+const MAGIC: &[u8; 4] = b"RFRI";
+const VERSION: u8 = 2;
+
+/// Input state of all devices in a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InputFrame {
+    pub frame_time: Duration,
+    pub keyboard: KeyboardState,
+    pub mouse: MouseState,
+    pub touch: TouchState,
+    pub gamepads: Vec<GamepadState>,
+}
+
+impl InputFrame {
+    /// Capture the live input state (the key and char queues are drained).
+    pub(crate) fn capture(frame_time: Duration, gamepads: u32) -> Self {
+        Self {
+            frame_time,
+            keyboard: KeyboardState::capture(),
+            mouse: MouseState::capture(),
+            touch: TouchState::capture(),
+            gamepads: (0..gamepads).map(GamepadState::capture).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct InputRecorder<W: Write> {
+    writer: W,
+}
+
+impl InputRecorder<BufWriter<File>> {
+    pub(crate) fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> InputRecorder<W> {
+    /// Start a recording by writing the header to `writer`.
+    pub(crate) fn new(mut writer: W) -> std::io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Self { writer })
+    }
+
+    /// Write a frame (nothing is written if the frame cannot be recorded).
+    pub(crate) fn write_frame(&mut self, frame: &InputFrame) -> std::io::Result<()> {
+        let mut buf = Vec::new();
+        let w = &mut buf;
+        write_f32(w, frame.frame_time.as_secs_f32())?;
+
+        let keyboard = &frame.keyboard;
+        write_keys(w, keyboard.down.iter().copied())?;
+        write_keys(w, keyboard.pressed.iter().copied())?;
+        write_keys(w, keyboard.released.iter().copied())?;
+        write_keys(w, keyboard.keys.iter().copied())?;
+        write_len_u16(w, keyboard.chars.len())?;
+        for &c in &keyboard.chars {
+            write_u32(w, c as u32)?;
+        }
+
+        let mouse = &frame.mouse;
+        write_position(w, mouse.position)?;
+        write_position(w, mouse.delta)?;
+        write_f32(w, mouse.wheel_move)?;
+        for buttons in [&mouse.down, &mouse.pressed, &mouse.released] {
+            let bits = buttons.iter().fold(0u8, |acc, &b| acc | (1 << b as u8));
+            write_u8(w, bits)?;
+        }
+
+        let touch = &frame.touch;
+        write_position(w, touch.position)?;
+        write_u16(w, touch.gesture as u16)?;
+        write_f32(w, touch.hold_duration.as_secs_f32())?;
+        write_len_u8(w, touch.points.len())?;
+        for point in &touch.points {
+            write_u32(w, point.id)?;
+            write_position(w, point.position)?;
+        }
+
+        write_len_u8(w, frame.gamepads.len())?;
+        for gamepad in &frame.gamepads {
+            write_u8(w, gamepad.is_available as u8)?;
+            for buttons in [&gamepad.down, &gamepad.pressed, &gamepad.released] {
+                let bits = buttons.iter().fold(0u32, |acc, &b| acc | (1 << b as u32));
+                write_u32(w, bits)?;
+            }
+            write_len_u8(w, gamepad.axes.len())?;
+            for &v in &gamepad.axes {
+                write_f32(w, v)?;
+            }
+        }
+        self.writer.write_all(&buf)
+    }
+
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct InputReplay<R: Read> {
+    reader: R,
+}
+
+impl InputReplay<BufReader<File>> {
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> InputReplay<R> {
+    /// Start a replay by checking the header read from `reader`.
+    pub(crate) fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC || header[4] != VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "not an input recording file",
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Read the next frame (`None` at the end of the recording).
+    pub(crate) fn read_frame(&mut self) -> std::io::Result<Option<InputFrame>> {
+        let r = &mut self.reader;
+        let mut first = [0; 4];
+        match r.read(&mut first[..1])? {
+            0 => return Ok(None),
+            _ => r.read_exact(&mut first[1..])?,
+        }
+        let frame_time = Duration::from_secs_f32(f32::from_le_bytes(first).max(0.0));
+
+        let keyboard = KeyboardState {
+            down: read_keys(r)?.collect(),
+            pressed: read_keys(r)?.collect(),
+            released: read_keys(r)?.collect(),
+            keys: read_keys(r)?.collect(),
+            chars: (0..read_u16(r)?)
+                .map(|_| read_u32(r))
+                .collect::<std::io::Result<Vec<_>>>()?
+                .into_iter()
+                .filter_map(char::from_u32)
+                .collect(),
+        };
+
+        let position = read_position(r)?;
+        let delta = read_position(r)?;
+        let wheel_move = read_f32(r)?;
+        let mut mouse_buttons = [BTreeSet::new(), BTreeSet::new(), BTreeSet::new()];
+        for buttons in &mut mouse_buttons {
+            let bits = read_u8(r)?;
+            buttons.extend(
+                MouseButton::ALL
+                    .into_iter()
+                    .filter(|&b| bits & (1 << b as u8) != 0),
+            );
+        }
+        let [down, pressed, released] = mouse_buttons;
+        let mouse = MouseState {
+            position,
+            delta,
+            wheel_move,
+            down,
+            pressed,
+            released,
+        };
+
+        let position = read_position(r)?;
+        let gesture = Gesture::from_u32(read_u16(r)? as u32);
+        let hold_duration = Duration::from_secs_f32(read_f32(r)?.max(0.0));
+        let points = (0..read_u8(r)?)
+            .map(|_| {
+                Ok(TouchPoint {
+                    id: read_u32(r)?,
+                    position: read_position(r)?,
+                })
+            })
+            .collect::<std::io::Result<_>>()?;
+        let touch = TouchState {
+            position,
+            points,
+            gesture,
+            hold_duration,
+        };
+
+        let mut gamepads = Vec::new();
+        for _ in 0..read_u8(r)? {
+            let is_available = read_u8(r)? != 0;
+            let mut gamepad_buttons = [BTreeSet::new(), BTreeSet::new(), BTreeSet::new()];
+            for buttons in &mut gamepad_buttons {
+                let bits = read_u32(r)?;
+                buttons.extend(GamepadButton::all().filter(|&b| bits & (1 << b as u32) != 0));
+            }
+            let [down, pressed, released] = gamepad_buttons;
+            let axes = (0..read_u8(r)?)
+                .map(|_| read_f32(r))
+                .collect::<std::io::Result<_>>()?;
+            gamepads.push(GamepadState {
+                is_available,
+                down,
+                pressed,
+                released,
+                axes,
+            });
+        }
+
+        Ok(Some(InputFrame {
+            frame_time,
+            keyboard,
+            mouse,
+            touch,
+            gamepads,
+        }))
+    }
+}
+
+fn write_u8<W: Write>(w: &mut W, v: u8) -> std::io::Result<()> {
+    w.write_all(&[v])
+}
+
+fn write_u16<W: Write>(w: &mut W, v: u16) -> std::io::Result<()> {
+    w.write_all(&v.to_le_bytes())
+}
+
+fn write_u32<W: Write>(w: &mut W, v: u32) -> std::io::Result<()> {
+    w.write_all(&v.to_le_bytes())
+}
+
+fn write_f32<W: Write>(w: &mut W, v: f32) -> std::io::Result<()> {
+    w.write_all(&v.to_le_bytes())
+}
+
+fn write_len_u8<W: Write>(w: &mut W, len: usize) -> std::io::Result<()> {
+    write_u8(w, u8::try_from(len).map_err(|_| too_many_items(len))?)
+}
+
+fn write_len_u16<W: Write>(w: &mut W, len: usize) -> std::io::Result<()> {
+    write_u16(w, u16::try_from(len).map_err(|_| too_many_items(len))?)
+}
+
+fn too_many_items(len: usize) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("too many items to record in a frame: {}", len),
+    )
+}
+
+fn write_position<W: Write>(w: &mut W, v: Position) -> std::io::Result<()> {
+    w.write_all(&v.x.to_le_bytes())?;
+    w.write_all(&v.y.to_le_bytes())
+}
+
+fn write_keys<W: Write>(
+    w: &mut W,
+    keys: impl ExactSizeIterator<Item = Key>,
+) -> std::io::Result<()> {
+    write_len_u16(w, keys.len())?;
+    for key in keys {
+        write_u16(w, key as u16)?;
+    }
+    Ok(())
+}
+
+fn read_u8<R: Read>(r: &mut R) -> std::io::Result<u8> {
+    let mut buf = [0; 1];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16<R: Read>(r: &mut R) -> std::io::Result<u16> {
+    let mut buf = [0; 2];
+    r.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(r: &mut R) -> std::io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_f32<R: Read>(r: &mut R) -> std::io::Result<f32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(f32::from_le_bytes(buf))
+}
+
+fn read_position<R: Read>(r: &mut R) -> std::io::Result<Position> {
+    let x = read_u32(r)? as i32;
+    let y = read_u32(r)? as i32;
+    Ok(Position { x, y })
+}
+
+fn read_keys<R: Read>(r: &mut R) -> std::io::Result<impl Iterator<Item = Key>> {
+    let keys = (0..read_u16(r)?)
+        .map(|_| read_u16(r))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    Ok(keys.into_iter().filter_map(|k| Key::from_u32(u32::from(k))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, ErrorKind};
+
+    fn frame() -> InputFrame {
+        InputFrame {
+            // Exactly representable as f32 seconds.
+            frame_time: Duration::from_micros(15_625),
+            keyboard: KeyboardState {
+                down: [Key::A, Key::Space].into(),
+                pressed: [Key::Space].into(),
+                keys: [Key::Space].into(),
+                chars: [' ', 'é'].into(),
+                ..Default::default()
+            },
+            mouse: MouseState {
+                position: Position { x: 10, y: -20 },
+                delta: Position { x: 1, y: 2 },
+                wheel_move: -1.5,
+                down: [MouseButton::Left].into(),
+                pressed: BTreeSet::new(),
+                released: [MouseButton::Right].into(),
+            },
+            touch: TouchState {
+                position: Position { x: 3, y: 4 },
+                points: vec![TouchPoint {
+                    id: 7,
+                    position: Position { x: 3, y: 4 },
+                }],
+                gesture: Gesture::Hold,
+                hold_duration: Duration::from_millis(500),
+            },
+            gamepads: vec![GamepadState {
+                is_available: true,
+                down: [GamepadButton::LeftFaceUp, GamepadButton::RightFaceDown].into(),
+                axes: vec![0.5, -1.0],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn record(frames: &[InputFrame]) -> Vec<u8> {
+        let mut recorder = InputRecorder::new(Vec::new()).unwrap();
+        for frame in frames {
+            recorder.write_frame(frame).unwrap();
+        }
+        recorder.writer
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut empty = frame();
+        empty.keyboard = KeyboardState::default();
+        empty.touch.points.clear();
+        empty.gamepads.clear();
+        let frames = [frame(), empty];
+        let mut replay = InputReplay::new(Cursor::new(record(&frames))).unwrap();
+        for frame in &frames {
+            assert_eq!(replay.read_frame().unwrap().as_ref(), Some(frame));
+        }
+        assert_eq!(replay.read_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_bad_header() {
+        for data in [&b"RFR"[..], b"RFRX\x02", b"RFRI\x01"] {
+            assert!(InputReplay::new(data).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_frames() {
+        let data = record(&[frame()]);
+        for len in MAGIC.len() + 2..data.len() {
+            let mut replay = InputReplay::new(&data[..len]).unwrap();
+            let error = replay.read_frame().unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "{}", len);
+        }
+    }
+
+    #[test]
+    fn oversized_frames_are_not_written() {
+        let mut oversized = frame();
+        oversized.touch.points = vec![oversized.touch.points[0].clone(); 256];
+        let mut recorder = InputRecorder::new(Vec::new()).unwrap();
+        let error = recorder.write_frame(&oversized).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(recorder.writer.len(), MAGIC.len() + 1);
+
+        // The recording is still usable.
+        recorder.write_frame(&frame()).unwrap();
+        let mut replay = InputReplay::new(&recorder.writer[..]).unwrap();
+        assert_eq!(replay.read_frame().unwrap(), Some(frame()));
+    }
+}
@@ -3,24 +3,51 @@ use std::os::raw::c_int;
 use std::time::Duration;
 
 #[derive(Debug)]
-pub struct Touch(pub(crate) ());
+pub struct Touch {
+    pub(crate) state: Option<TouchState>,
+    now: Duration,
+    hold_started_at: Option<Duration>,
+}
 
 impl Touch {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: None,
+            now: Duration::ZERO,
+            hold_started_at: None,
+        }
+    }
+
+    pub(crate) fn update(&mut self, now: Duration) {
+        self.now = now;
+        let hold_duration = match &self.state {
+            Some(state) => state.hold_duration,
+            None => live_hold_duration(),
+        };
+        if hold_duration.is_zero() {
+            self.hold_started_at = None;
+        } else {
+            // raylib's hold duration follows the wall clock, so the game clock measures from the first held frame.
+            self.hold_started_at.get_or_insert(now);
+        }
+    }
+
     /// Get touch position for touch point 0 (relative to screen size).
     pub fn get_position(&self) -> Position {
+        if let Some(state) = &self.state {
+            return state.position;
+        }
         let x = unsafe { raylib4_sys::GetTouchX() };
         let y = unsafe { raylib4_sys::GetTouchY() };
         Position { x, y }
     }
 
     pub fn get_touch_points(&self) -> impl Iterator<Item = TouchPoint> {
-        let n = unsafe { raylib4_sys::GetTouchPointCount() };
-        (0..n).map(|i| unsafe {
-            TouchPoint {
-                id: raylib4_sys::GetTouchPointId(i) as u32,
-                position: raylib4_sys::GetTouchPosition(i).into(),
-            }
-        })
+        match &self.state {
+            Some(state) => state.points.clone(),
+            None => live_touch_points(),
+        }
+        .into_iter()
     }
 
     /// Enable a set of gestures using flags.
@@ -30,35 +57,24 @@ impl Touch {
 
     /// Check if a gesture have been detected.
     pub fn is_gesture_detected(&self, gesture: Gesture) -> bool {
-        unsafe { raylib4_sys::IsGestureDetected(gesture as c_int) }
+        match &self.state {
+            Some(state) => state.gesture == gesture,
+            None => unsafe { raylib4_sys::IsGestureDetected(gesture as c_int) },
+        }
     }
 
     /// Get latest detected gesture.
     pub fn get_gesture_detected(&self) -> Gesture {
-        let n = unsafe { raylib4_sys::GetGestureDetected() };
-        match n {
-            0 => Gesture::None,
-            1 => Gesture::Tap,
-            2 => Gesture::Doubletap,
-            4 => Gesture::Hold,
-            8 => Gesture::Drag,
-            16 => Gesture::SwipeRight,
-            32 => Gesture::SwipeLeft,
-            64 => Gesture::SwipeUp,
-            128 => Gesture::SwipeDown,
-            256 => Gesture::PinchIn,
-            512 => Gesture::PinchOut,
-            _ => {
-                log::warn!("unknown gesture (ignored): {}", n);
-                Gesture::None
-            }
+        match &self.state {
+            Some(state) => state.gesture,
+            None => Gesture::from_u32(unsafe { raylib4_sys::GetGestureDetected() } as u32),
         }
     }
 
-    /// Get gesture hold time in milliseconds.
+    /// Get gesture hold time (measured by the game clock).
     pub fn get_gesture_hold_duration(&self) -> Duration {
-        let n = unsafe { raylib4_sys::GetGestureHoldDuration() };
-        Duration::from_secs_f32(n * 1000.0)
+        self.hold_started_at
+            .map_or(Duration::ZERO, |t| self.now.saturating_sub(t))
     }
 
     /// Get drag vector and angle (between initial touch point to current).
@@ -96,7 +112,7 @@ pub struct Distance {
     pub angle: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TouchPoint {
     pub id: u32,
     pub position: Position,
@@ -117,6 +133,69 @@ pub enum Gesture {
     PinchOut = 512,
 }
 
+impl Gesture {
+    pub(crate) fn from_u32(n: u32) -> Self {
+        match n {
+            0 => Gesture::None,
+            1 => Gesture::Tap,
+            2 => Gesture::Doubletap,
+            4 => Gesture::Hold,
+            8 => Gesture::Drag,
+            16 => Gesture::SwipeRight,
+            32 => Gesture::SwipeLeft,
+            64 => Gesture::SwipeUp,
+            128 => Gesture::SwipeDown,
+            256 => Gesture::PinchIn,
+            512 => Gesture::PinchOut,
+            _ => {
+                log::warn!("unknown gesture (ignored): {}", n);
+                Gesture::None
+            }
+        }
+    }
+}
+
+/// Touch state of a frame (used instead of the live state while replaying or injecting input).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TouchState {
+    pub position: Position,
+    pub points: Vec<TouchPoint>,
+    pub gesture: Gesture,
+    pub hold_duration: Duration,
+}
+
+impl TouchState {
+    pub(crate) fn capture() -> Self {
+        Self {
+            position: Position {
+                x: unsafe { raylib4_sys::GetTouchX() },
+                y: unsafe { raylib4_sys::GetTouchY() },
+            },
+            points: live_touch_points(),
+            gesture: Gesture::from_u32(unsafe { raylib4_sys::GetGestureDetected() } as u32),
+            hold_duration: live_hold_duration(),
+        }
+    }
+}
+
+fn live_hold_duration() -> Duration {
+    // In milliseconds.
+    let n = unsafe { raylib4_sys::GetGestureHoldDuration() };
+    Duration::from_secs_f32(n.max(0.0) / 1000.0)
+}
+
+fn live_touch_points() -> Vec<TouchPoint> {
+    let n = unsafe { raylib4_sys::GetTouchPointCount() };
+    (0..n)
+        .map(|i| unsafe {
+            TouchPoint {
+                id: raylib4_sys::GetTouchPointId(i) as u32,
+                position: raylib4_sys::GetTouchPosition(i).into(),
+            }
+        })
+        .collect()
+}
+
 fn gestures_to_flags(xs: impl Iterator<Item = Gesture>) -> u32 {
     let mut flags = 0;
     for x in xs {
@@ -124,3 +203,37 @@ fn gestures_to_flags(xs: impl Iterator<Item = Gesture>) -> u32 {
     }
     flags
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(hold_duration: Duration) -> TouchState {
+        TouchState {
+            position: Position { x: 0, y: 0 },
+            points: Vec::new(),
+            gesture: Gesture::Hold,
+            hold_duration,
+        }
+    }
+
+    #[test]
+    fn hold_duration_follows_the_game_clock() {
+        let ms = Duration::from_millis;
+        let mut touch = Touch::new();
+        touch.state = Some(state(ms(600)));
+        touch.update(ms(1000));
+        assert_eq!(touch.get_gesture_hold_duration(), Duration::ZERO);
+
+        // The recorded (wall-clock) duration is ignored once the hold has started.
+        touch.state = Some(state(ms(5000)));
+        touch.update(ms(1016));
+        assert_eq!(touch.get_gesture_hold_duration(), ms(16));
+        touch.update(ms(1016));
+        assert_eq!(touch.get_gesture_hold_duration(), ms(16));
+
+        touch.state = Some(state(Duration::ZERO));
+        touch.update(ms(1032));
+        assert_eq!(touch.get_gesture_hold_duration(), Duration::ZERO);
+    }
+}
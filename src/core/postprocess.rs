@@ -0,0 +1,344 @@
+//! Full-screen post-processing effects.
+//!
+//! ```no_run
+//! use rfraylib::core::postprocess::{Effect, PostProcessChain};
+//! use rfraylib::{Color, Draw};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! let mut chain = PostProcessChain::new(system.window().get_screen_size())?;
+//! chain.push(Effect::bloom(0.7, 0.8)?).push(Effect::vignette(0.75, 0.45)?);
+//!
+//! while !system.window().should_close() {
+//!     let mut canvas = system.next_frame();
+//!     {
+//!         let mut scene = chain.begin(&mut canvas)?;
+//!         scene.clear_background(Color::BLACK);
+//!         // Draw the scene.
+//!     }
+//!     chain.present(&mut canvas);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::core::drawing::{Draw, TextureCanvas, WindowCanvas};
+use crate::core::shader::{Shader, ShaderError};
+use crate::structs::Vector2;
+use crate::texture::{RenderTexture, Texture};
+use crate::{Color, Position, Rectangle, Size};
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum PostProcessError {
+    #[error("failed to load render texture")]
+    RenderTextureLoadFailed,
+
+    #[error(transparent)]
+    Shader(#[from] ShaderError),
+}
+
+/// Full-screen effect applied by [`PostProcessChain`].
+///
+/// The `resolution` (vec2) uniform of the shader is set to the target size before each pass.
+#[derive(Debug)]
+pub struct Effect {
+    shader: Shader,
+    textures: Vec<(String, Arc<Texture>)>,
+    enabled: bool,
+}
+
+impl Effect {
+    /// Make an effect from a custom fragment shader.
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            textures: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Glow around the pixels brighter than `threshold` (uniforms: `threshold`, `intensity`).
+    pub fn bloom(threshold: f32, intensity: f32) -> Result<Self, ShaderError> {
+        let shader = Shader::load_from_memory(None, Some(BLOOM_FS))?;
+        shader.set_f32("threshold", threshold);
+        shader.set_f32("intensity", intensity);
+        Ok(Self::new(shader))
+    }
+
+    /// CRT monitor look with scanlines and screen curvature (uniforms: `scanline_intensity`, `curvature`).
+    pub fn crt(scanline_intensity: f32, curvature: f32) -> Result<Self, ShaderError> {
+        let shader = Shader::load_from_memory(None, Some(CRT_FS))?;
+        shader.set_f32("scanline_intensity", scanline_intensity);
+        shader.set_f32("curvature", curvature);
+        Ok(Self::new(shader))
+    }
+
+    /// Darken the screen corners (uniforms: `radius`, `softness`).
+    pub fn vignette(radius: f32, softness: f32) -> Result<Self, ShaderError> {
+        let shader = Shader::load_from_memory(None, Some(VIGNETTE_FS))?;
+        shader.set_f32("radius", radius);
+        shader.set_f32("softness", softness);
+        Ok(Self::new(shader))
+    }
+
+    /// Color grading by a lookup table (uniforms: `lut`, `lut_size`, `strength`).
+    ///
+    /// The LUT is a horizontal strip of `lut_size` tiles of `lut_size`x`lut_size` pixels
+    /// (e.g., 256x16 for `lut_size = 16`): red increases along X in each tile, green along Y and blue by tile.
+    pub fn color_grading(lut: Arc<Texture>, strength: f32) -> Result<Self, ShaderError> {
+        let shader = Shader::load_from_memory(None, Some(COLOR_GRADING_FS))?;
+        shader.set_f32("lut_size", lut.size().height as f32);
+        shader.set_f32("strength", strength);
+        let mut effect = Self::new(shader);
+        effect.set_texture("lut", lut);
+        Ok(effect)
+    }
+
+    /// Render the screen with large pixels (uniform: `pixel_size`).
+    pub fn pixelate(pixel_size: f32) -> Result<Self, ShaderError> {
+        let shader = Shader::load_from_memory(None, Some(PIXELATE_FS))?;
+        shader.set_f32("pixel_size", pixel_size);
+        Ok(Self::new(shader))
+    }
+
+    /// Get the shader (e.g., to update uniforms).
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    /// Bind a texture to a sampler uniform before each pass.
+    pub fn set_texture(&mut self, uniform_name: &str, texture: Arc<Texture>) {
+        self.textures.retain(|(name, _)| name != uniform_name);
+        self.textures.push((uniform_name.to_owned(), texture));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    fn draw<T: Draw>(&self, canvas: &mut T, source: &RenderTexture) {
+        let mut canvas = canvas.begin_shader_mode(&self.shader);
+        let size = source.texture().size();
+        self.shader.set_vec2(
+            "resolution",
+            Vector2::new(size.width as f32, size.height as f32),
+        );
+        for (name, texture) in &self.textures {
+            self.shader.set_texture(name, texture);
+        }
+        draw_target(&mut canvas, source);
+    }
+}
+
+/// Ordered list of full-screen effects applied to a scene rendered offscreen.
+#[derive(Debug)]
+pub struct PostProcessChain {
+    targets: [RenderTexture; 2],
+    effects: Vec<Effect>,
+}
+
+impl PostProcessChain {
+    pub fn new(size: Size) -> Result<Self, PostProcessError> {
+        Ok(Self {
+            targets: Self::load_targets(size)?,
+            effects: Vec::new(),
+        })
+    }
+
+    fn load_targets(size: Size) -> Result<[RenderTexture; 2], PostProcessError> {
+        let load = || RenderTexture::load(size).ok_or(PostProcessError::RenderTextureLoadFailed);
+        Ok([load()?, load()?])
+    }
+
+    /// Get the size of the intermediate targets.
+    pub fn size(&self) -> Size {
+        self.targets[0].texture().size()
+    }
+
+    /// Reload the intermediate targets with the given size.
+    pub fn resize(&mut self, size: Size) -> Result<(), PostProcessError> {
+        if size != self.size() {
+            self.targets = Self::load_targets(size)?;
+        }
+        Ok(())
+    }
+
+    /// Append an effect (effects are applied in insertion order).
+    pub fn push(&mut self, effect: Effect) -> &mut Self {
+        self.effects.push(effect);
+        self
+    }
+
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    pub fn effects_mut(&mut self) -> &mut Vec<Effect> {
+        &mut self.effects
+    }
+
+    /// Begin drawing the scene into the offscreen target.
+    ///
+    /// The targets are resized to the screen size first if the window has been resized.
+    pub fn begin<'a, 'w>(
+        &'a mut self,
+        canvas: &'a mut WindowCanvas<'w>,
+    ) -> Result<TextureCanvas<'a, 'a, WindowCanvas<'w>>, PostProcessError> {
+        let window = canvas.system().window();
+        let screen_size = window.get_screen_size();
+        if window.is_resized() || screen_size != self.size() {
+            self.resize(screen_size)?;
+        }
+        Ok(canvas.begin_texture_mode(&mut self.targets[0]))
+    }
+
+    /// Apply the effects to the scene and draw the result to the window.
+    pub fn present(&mut self, canvas: &mut WindowCanvas) {
+        let mut effects = self.effects.iter().filter(|e| e.enabled).peekable();
+        let mut src = 0;
+        while let Some(effect) = effects.next() {
+            if effects.peek().is_none() {
+                effect.draw(canvas, &self.targets[src]);
+                return;
+            }
+            let dst = 1 - src;
+            {
+                let (source, target) = match &mut self.targets {
+                    [a, b] if src == 0 => (&*a, b),
+                    [a, b] => (&*b, a),
+                };
+                let mut target = canvas.begin_texture_mode(target);
+                target.clear_background(Color::BLANK);
+                effect.draw(&mut target, source);
+            }
+            src = dst;
+        }
+        draw_target(canvas, &self.targets[src]);
+    }
+}
+
+fn draw_target<T: Draw>(canvas: &mut T, target: &RenderTexture) {
+    // Render textures are upside down.
+    let size = target.texture().size();
+    canvas.draw_texture_rec(
+        target.texture(),
+        Rectangle::new(0, 0, size.width, -size.height),
+        Position { x: 0, y: 0 },
+        Color::WHITE,
+    );
+}
+
+const BLOOM_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform vec2 resolution;
+uniform float threshold;
+uniform float intensity;
+out vec4 finalColor;
+
+const int RADIUS = 4;
+
+void main() {
+    vec4 source = texture(texture0, fragTexCoord);
+    vec2 texel = 2.0 / resolution;
+    vec3 sum = vec3(0.0);
+    for (int x = -RADIUS; x <= RADIUS; x++) {
+        for (int y = -RADIUS; y <= RADIUS; y++) {
+            vec3 c = texture(texture0, fragTexCoord + vec2(x, y) * texel).rgb;
+            sum += c * step(threshold, max(c.r, max(c.g, c.b)));
+        }
+    }
+    sum /= float((2 * RADIUS + 1) * (2 * RADIUS + 1));
+    finalColor = vec4(source.rgb + sum * intensity, source.a) * colDiffuse * fragColor;
+}
+"#;
+
+const CRT_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform vec2 resolution;
+uniform float scanline_intensity;
+uniform float curvature;
+out vec4 finalColor;
+
+void main() {
+    vec2 uv = fragTexCoord * 2.0 - 1.0;
+    uv += uv * (uv.yx * uv.yx) * curvature;
+    uv = uv * 0.5 + 0.5;
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        finalColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+    vec4 c = texture(texture0, uv);
+    float scanline = sin(uv.y * resolution.y * 3.14159265) * 0.5 + 0.5;
+    c.rgb *= 1.0 - scanline_intensity * (1.0 - scanline);
+    finalColor = c * colDiffuse * fragColor;
+}
+"#;
+
+const VIGNETTE_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform float radius;
+uniform float softness;
+out vec4 finalColor;
+
+void main() {
+    vec4 c = texture(texture0, fragTexCoord);
+    float d = length(fragTexCoord - vec2(0.5));
+    c.rgb *= smoothstep(radius, radius - softness, d);
+    finalColor = c * colDiffuse * fragColor;
+}
+"#;
+
+const COLOR_GRADING_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform sampler2D lut;
+uniform vec4 colDiffuse;
+uniform float lut_size;
+uniform float strength;
+out vec4 finalColor;
+
+vec2 lut_uv(vec3 c, float tile) {
+    float n = lut_size;
+    return vec2((tile * n + c.r * (n - 1.0) + 0.5) / (n * n), (c.g * (n - 1.0) + 0.5) / n);
+}
+
+void main() {
+    vec4 c = texture(texture0, fragTexCoord);
+    float b = c.b * (lut_size - 1.0);
+    float b0 = floor(b);
+    float b1 = min(b0 + 1.0, lut_size - 1.0);
+    vec3 graded = mix(texture(lut, lut_uv(c.rgb, b0)).rgb, texture(lut, lut_uv(c.rgb, b1)).rgb, b - b0);
+    finalColor = vec4(mix(c.rgb, graded, strength), c.a) * colDiffuse * fragColor;
+}
+"#;
+
+const PIXELATE_FS: &str = r#"#version 330
+in vec2 fragTexCoord;
+in vec4 fragColor;
+uniform sampler2D texture0;
+uniform vec4 colDiffuse;
+uniform vec2 resolution;
+uniform float pixel_size;
+out vec4 finalColor;
+
+void main() {
+    vec2 cell = max(pixel_size, 1.0) / resolution;
+    vec2 uv = cell * (floor(fragTexCoord / cell) + 0.5);
+    finalColor = texture(texture0, uv) * colDiffuse * fragColor;
+}
+"#;
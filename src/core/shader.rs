@@ -0,0 +1,255 @@
+use crate::structs::Vector2;
+use crate::system::{GpuOwner, Unload};
+use crate::texture::Texture;
+use std::collections::HashMap;
+use std::ffi::{c_void, CString};
+use std::os::raw::{c_int, c_uint};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+extern "C" {
+    // rlgl.h (not included in the raylib4-sys bindings).
+    fn rlGetShaderIdDefault() -> c_uint;
+}
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ShaderError {
+    #[error("malformed path or shader code")]
+    Malformed {
+        #[from]
+        source: std::ffi::NulError,
+    },
+
+    #[error("failed to open shader file {path:?}")]
+    FileOpenFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to load, compile or link shader: {}", messages.join("; "))]
+    BuildFailed {
+        /// Warning messages logged by raylib while building the shader (e.g., shader info logs).
+        messages: Vec<String>,
+    },
+}
+
+/// Shader program handle.
+///
+/// Shaders are reference counted: clones share the same program,
+/// which is unloaded when the last handle is dropped.
+#[derive(Debug, Clone)]
+pub struct Shader(Arc<ShaderInner>);
+
+#[derive(Debug)]
+struct ShaderInner {
+    raw: GpuOwner<raylib4_sys::Shader>,
+    locations: Mutex<HashMap<String, Option<c_int>>>,
+}
+
+impl Shader {
+    /// Load shader from files and bind default locations.
+    ///
+    /// `None` means the default vertex or fragment shader.
+    /// Passing `None` for both stages fails with [`ShaderError::BuildFailed`],
+    /// since the result would be the default shader (draw outside of a shader mode instead).
+    pub fn load<P: AsRef<Path>>(
+        vs_path: Option<P>,
+        fs_path: Option<P>,
+    ) -> Result<Self, ShaderError> {
+        // raylib silently falls back to the default stage if a file cannot be read.
+        for path in vs_path.iter().chain(fs_path.iter()) {
+            let path = path.as_ref();
+            std::fs::File::open(path).map_err(|source| ShaderError::FileOpenFailed {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        }
+        let vs_path = vs_path.map(path_to_cstring).transpose()?;
+        let fs_path = fs_path.map(path_to_cstring).transpose()?;
+        Self::build(|| unsafe { raylib4_sys::LoadShader(as_ptr(&vs_path), as_ptr(&fs_path)) })
+    }
+
+    /// Load shader from code strings and bind default locations.
+    ///
+    /// `None` means the default vertex or fragment shader.
+    /// Passing `None` for both stages fails with [`ShaderError::BuildFailed`],
+    /// since the result would be the default shader (draw outside of a shader mode instead).
+    pub fn load_from_memory(
+        vs_code: Option<&str>,
+        fs_code: Option<&str>,
+    ) -> Result<Self, ShaderError> {
+        let vs_code = vs_code.map(CString::new).transpose()?;
+        let fs_code = fs_code.map(CString::new).transpose()?;
+        Self::build(|| unsafe {
+            raylib4_sys::LoadShaderFromMemory(as_ptr(&vs_code), as_ptr(&fs_code))
+        })
+    }
+
+    fn build<F>(f: F) -> Result<Self, ShaderError>
+    where
+        F: FnOnce() -> raylib4_sys::Shader,
+    {
+        // raylib falls back to the default shader if a custom shader cannot be built.
+        let (raw, messages) = crate::system::capture_warnings(f);
+        if raw.id == 0 || raw.id == unsafe { rlGetShaderIdDefault() } {
+            return Err(ShaderError::BuildFailed { messages });
+        }
+        Ok(Self(Arc::new(ShaderInner {
+            raw: GpuOwner::new(raw),
+            locations: Mutex::new(HashMap::new()),
+        })))
+    }
+
+    pub(crate) fn to_raw(&self) -> raylib4_sys::Shader {
+        self.0.raw.get()
+    }
+
+    /// Get shader uniform location (cached by name).
+    pub fn get_location(&self, uniform_name: &str) -> Option<i32> {
+        let mut locations = self.0.locations.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&location) = locations.get(uniform_name) {
+            return location;
+        }
+        let location = CString::new(uniform_name).ok().and_then(|name| {
+            let loc = unsafe { raylib4_sys::GetShaderLocation(self.to_raw(), name.as_ptr()) };
+            (loc >= 0).then_some(loc)
+        });
+        locations.insert(uniform_name.to_owned(), location);
+        location
+    }
+
+    /// Set shader uniform value (float).
+    ///
+    /// Unknown uniforms (e.g., optimized out by the shader compiler) are ignored.
+    pub fn set_f32(&self, uniform_name: &str, value: f32) {
+        self.set_value(
+            uniform_name,
+            &value,
+            raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_FLOAT,
+        );
+    }
+
+    /// Set shader uniform value (vec2).
+    pub fn set_vec2(&self, uniform_name: &str, value: Vector2) {
+        let value = [value.x(), value.y()];
+        self.set_value(
+            uniform_name,
+            &value,
+            raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_VEC2,
+        );
+    }
+
+    /// Set shader uniform value (vec3).
+    pub fn set_vec3(&self, uniform_name: &str, value: [f32; 3]) {
+        self.set_value(
+            uniform_name,
+            &value,
+            raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_VEC3,
+        );
+    }
+
+    /// Set shader uniform value (vec4).
+    pub fn set_vec4(&self, uniform_name: &str, value: [f32; 4]) {
+        self.set_value(
+            uniform_name,
+            &value,
+            raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_VEC4,
+        );
+    }
+
+    /// Set shader uniform value (int).
+    pub fn set_i32(&self, uniform_name: &str, value: i32) {
+        self.set_value(
+            uniform_name,
+            &value,
+            raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_INT,
+        );
+    }
+
+    /// Set shader uniform value (float array).
+    pub fn set_f32_array(&self, uniform_name: &str, values: &[f32]) {
+        if let Some(loc) = self.get_location(uniform_name) {
+            unsafe {
+                raylib4_sys::SetShaderValueV(
+                    self.to_raw(),
+                    loc,
+                    values.as_ptr() as *const c_void,
+                    raylib4_sys::ShaderUniformDataType_SHADER_UNIFORM_FLOAT as c_int,
+                    values.len() as c_int,
+                )
+            };
+        }
+    }
+
+    /// Set shader uniform value (4x4 matrix, `m[i]` is raylib's `Matrix.m{i}` i.e., column-major order).
+    pub fn set_matrix(&self, uniform_name: &str, m: [f32; 16]) {
+        if let Some(loc) = self.get_location(uniform_name) {
+            let mat = raylib4_sys::Matrix {
+                m0: m[0],
+                m1: m[1],
+                m2: m[2],
+                m3: m[3],
+                m4: m[4],
+                m5: m[5],
+                m6: m[6],
+                m7: m[7],
+                m8: m[8],
+                m9: m[9],
+                m10: m[10],
+                m11: m[11],
+                m12: m[12],
+                m13: m[13],
+                m14: m[14],
+                m15: m[15],
+            };
+            unsafe { raylib4_sys::SetShaderValueMatrix(self.to_raw(), loc, mat) };
+        }
+    }
+
+    /// Set shader uniform value for texture (sampler2d).
+    pub fn set_texture(&self, uniform_name: &str, texture: &Texture) {
+        if let Some(loc) = self.get_location(uniform_name) {
+            unsafe { raylib4_sys::SetShaderValueTexture(self.to_raw(), loc, texture.to_raw()) };
+        }
+    }
+
+    fn set_value<T>(
+        &self,
+        uniform_name: &str,
+        value: &T,
+        uniform_type: raylib4_sys::ShaderUniformDataType,
+    ) {
+        if let Some(loc) = self.get_location(uniform_name) {
+            unsafe {
+                raylib4_sys::SetShaderValue(
+                    self.to_raw(),
+                    loc,
+                    value as *const T as *const c_void,
+                    uniform_type as c_int,
+                )
+            };
+        }
+    }
+}
+
+impl PartialEq for Shader {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_raw().id == other.to_raw().id
+    }
+}
+
+// SAFETY: The location array pointed by `raylib4_sys::Shader` is never modified after loading.
+unsafe impl Unload for raylib4_sys::Shader {
+    fn unload(self) {
+        unsafe { raylib4_sys::UnloadShader(self) };
+    }
+}
+
+fn path_to_cstring<P: AsRef<Path>>(path: P) -> Result<CString, std::ffi::NulError> {
+    CString::new(path.as_ref().to_string_lossy().as_bytes())
+}
+
+fn as_ptr(s: &Option<CString>) -> *const std::os::raw::c_char {
+    s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr())
+}
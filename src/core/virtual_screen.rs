@@ -0,0 +1,161 @@
+//! Fixed logical resolution scaled to the window.
+use crate::core::drawing::{Draw, TextureCanvas, WindowCanvas};
+use crate::core::input::touch::TouchPoint;
+use crate::texture::{RenderTexture, TextureFilter};
+use crate::{Color, Position, Rectangle, Size, System};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalingMode {
+    /// Scale by the largest integer factor that fits in the window (pixel-perfect).
+    Integer,
+
+    /// Scale by the largest factor that fits in the window keeping the aspect ratio.
+    Fit,
+}
+
+/// Offscreen target with a fixed logical size that is presented letterboxed in the window.
+///
+/// ```no_run
+/// use rfraylib::core::virtual_screen::VirtualScreen;
+/// use rfraylib::{Color, Draw};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut system = rfraylib::SystemBuilder::new().build()?;
+/// let mut screen = VirtualScreen::new((320, 180).into()).expect("failed to load render texture");
+///
+/// while !system.window().should_close() {
+///     let cursor = screen.mouse_position(&system);
+///     let mut canvas = system.next_frame();
+///     {
+///         let mut canvas = screen.begin(&mut canvas);
+///         canvas.clear_background(Color::RAYWHITE);
+///         canvas.draw_pixel(cursor, Color::RED);
+///     }
+///     screen.present(&mut canvas);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct VirtualScreen {
+    target: RenderTexture,
+    scaling_mode: ScalingMode,
+    letterbox_color: Color,
+}
+
+impl VirtualScreen {
+    /// Make a virtual screen (the default scaling mode is [`ScalingMode::Integer`] with nearest filtering).
+    pub fn new(logical_size: Size) -> Option<Self> {
+        let mut target = RenderTexture::load(logical_size)?;
+        target.texture_mut().set_filter(TextureFilter::Point);
+        Some(Self {
+            target,
+            scaling_mode: ScalingMode::Integer,
+            letterbox_color: Color::BLACK,
+        })
+    }
+
+    pub fn logical_size(&self) -> Size {
+        self.target.texture().size()
+    }
+
+    pub fn scaling_mode(&self) -> ScalingMode {
+        self.scaling_mode
+    }
+
+    pub fn set_scaling_mode(&mut self, mode: ScalingMode) {
+        self.scaling_mode = mode;
+    }
+
+    /// Set the filter used when scaling (`TextureFilter::Point` for nearest, `TextureFilter::Bilinear`).
+    pub fn set_filter(&mut self, filter: TextureFilter) {
+        self.target.texture_mut().set_filter(filter);
+    }
+
+    /// Set the color of the bars around the scaled screen.
+    pub fn set_letterbox_color(&mut self, color: Color) {
+        self.letterbox_color = color;
+    }
+
+    /// Get the scale factor from logical to screen coordinates.
+    pub fn scale(&self, screen_size: Size) -> f32 {
+        let logical = self.logical_size();
+        let scale = f32::min(
+            screen_size.width as f32 / logical.width as f32,
+            screen_size.height as f32 / logical.height as f32,
+        );
+        match self.scaling_mode {
+            ScalingMode::Integer if scale >= 1.0 => scale.floor(),
+            _ => scale,
+        }
+    }
+
+    /// Get the area of the window where the logical screen is drawn.
+    pub fn viewport(&self, screen_size: Size) -> Rectangle {
+        let logical = self.logical_size();
+        let scale = self.scale(screen_size);
+        let width = (logical.width as f32 * scale).round() as i32;
+        let height = (logical.height as f32 * scale).round() as i32;
+        Rectangle::new(
+            (screen_size.width - width) / 2,
+            (screen_size.height - height) / 2,
+            width,
+            height,
+        )
+    }
+
+    /// Convert a window position into logical coordinates (it may be outside of the logical screen).
+    pub fn to_logical(&self, screen_size: Size, position: Position) -> Position {
+        let viewport = self.viewport(screen_size);
+        let scale = self.scale(screen_size);
+        Position {
+            x: ((position.x - viewport.position.x) as f32 / scale).floor() as i32,
+            y: ((position.y - viewport.position.y) as f32 / scale).floor() as i32,
+        }
+    }
+
+    /// Get the mouse position in logical coordinates.
+    pub fn mouse_position(&self, system: &System) -> Position {
+        let screen_size = system.window().get_screen_size();
+        self.to_logical(screen_size, system.mouse().get_position())
+    }
+
+    /// Get the touch position in logical coordinates.
+    pub fn touch_position(&self, system: &System) -> Position {
+        let screen_size = system.window().get_screen_size();
+        self.to_logical(screen_size, system.touch().get_position())
+    }
+
+    /// Get the touch points in logical coordinates.
+    pub fn touch_points<'a>(&'a self, system: &'a System) -> impl 'a + Iterator<Item = TouchPoint> {
+        let screen_size = system.window().get_screen_size();
+        system.touch().get_touch_points().map(move |p| TouchPoint {
+            id: p.id,
+            position: self.to_logical(screen_size, p.position),
+        })
+    }
+
+    /// Begin drawing in logical coordinates.
+    pub fn begin<'a, 'w>(
+        &'a mut self,
+        canvas: &'a mut WindowCanvas<'w>,
+    ) -> TextureCanvas<'a, 'a, WindowCanvas<'w>> {
+        canvas.begin_texture_mode(&mut self.target)
+    }
+
+    /// Draw the logical screen scaled to the window (the rest of the window is filled with the letterbox color).
+    pub fn present(&self, canvas: &mut WindowCanvas) {
+        let screen_size = canvas.system().window().get_screen_size();
+        let logical = self.logical_size();
+        canvas.clear_background(self.letterbox_color);
+        canvas.draw_texture_pro(
+            self.target.texture(),
+            // Render textures are upside down.
+            Rectangle::new(0, 0, logical.width, -logical.height),
+            self.viewport(screen_size),
+            Position { x: 0, y: 0 },
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
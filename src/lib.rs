@@ -1,10 +1,15 @@
+pub mod app;
 pub mod audio;
 pub mod core;
 pub mod structs;
 pub mod system;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod text;
 pub mod texture;
+pub mod time;
 
+pub use self::app::App;
 pub use self::core::drawing::{Camera, Color, Draw};
 pub use self::core::input::keyboard::Key;
 pub use self::core::input::mouse::MouseButton;
@@ -1,22 +1,102 @@
+use crate::app::App;
 use crate::audio::AudioDevice;
+use crate::core::capture::{RecordingOptions, ScreenRecorder};
 use crate::core::cursor::Cursor;
 use crate::core::drawing::{TextureCanvas, WindowCanvas};
-use crate::core::input::gamepad::{Gamepad, GamepadButton};
+use crate::core::input::gamepad::{Gamepad, GamepadButton, GamepadEvent, GamepadTracker};
+use crate::core::input::inject::InputInjection;
 use crate::core::input::mouse::Mouse;
+use crate::core::input::record::{InputFrame, InputRecorder, InputReplay};
 use crate::core::input::touch::Touch;
 use crate::core::input::Keyboard;
 use crate::core::monitor::Monitors;
 use crate::core::window::{ConfigFlag, Window};
 use crate::structs::{Rectangle, Size};
 use crate::texture::{RenderTexture, Texture};
+use crate::time::{Clock, FixedTimestep};
+use std::cell::{Cell, RefCell};
 use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
 use std::os::raw::{c_char, c_int};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 static IS_SYSTEM_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+thread_local! {
+    static CAPTURED_WARNINGS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    static IS_SYSTEM_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+// GPU resources dropped on other threads (unloaded at the end of the next frame).
+static DEFERRED_UNLOADS: Mutex<Vec<Box<dyn Send>>> = Mutex::new(Vec::new());
+
+/// Raw GPU resource.
+///
+/// # Safety
+///
+/// The data pointed by the resource (e.g., glyphs) must not be modified until it is unloaded.
+pub(crate) unsafe trait Unload: Copy + std::fmt::Debug + 'static {
+    /// Unload the resource (only called on the thread running the [`System`]).
+    fn unload(self);
+}
+
+/// Owner of a GPU resource that is unloaded when dropped.
+///
+/// If the owner is dropped on another thread (e.g., along with a `DrawList`),
+/// the resource is unloaded at the end of the next frame on the thread running the [`System`].
+#[derive(Debug)]
+pub(crate) struct GpuOwner<T: Unload>(T);
+
+// SAFETY: The resource is read-only (see `Unload`) and only unloaded on the thread running the `System`.
+unsafe impl<T: Unload> Send for GpuOwner<T> {}
+unsafe impl<T: Unload> Sync for GpuOwner<T> {}
+
+impl<T: Unload> GpuOwner<T> {
+    pub(crate) fn new(raw: T) -> Self {
+        Self(raw)
+    }
+
+    pub(crate) fn get(&self) -> T {
+        self.0
+    }
+}
+
+impl<T: Unload> Drop for GpuOwner<T> {
+    fn drop(&mut self) {
+        if IS_SYSTEM_THREAD.with(Cell::get) {
+            self.0.unload();
+        } else {
+            DEFERRED_UNLOADS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(Box::new(Self(self.0)));
+        }
+    }
+}
+
+fn unload_deferred() {
+    let resources =
+        std::mem::take(&mut *DEFERRED_UNLOADS.lock().unwrap_or_else(|e| e.into_inner()));
+    drop(resources);
+}
+
+/// Call `f` and collect the warning (and error) messages that raylib logs meanwhile.
+pub(crate) fn capture_warnings<F, T>(f: F) -> (T, Vec<String>)
+where
+    F: FnOnce() -> T,
+{
+    let prev = CAPTURED_WARNINGS.with(|c| c.replace(Some(Vec::new())));
+    let value = f();
+    let warnings = CAPTURED_WARNINGS
+        .with(|c| c.replace(prev))
+        .unwrap_or_default();
+    (value, warnings)
+}
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum SystemBuildError {
@@ -28,6 +108,9 @@ pub enum SystemBuildError {
         #[from]
         source: std::ffi::NulError,
     },
+
+    #[error("failed to initialize window and OpenGL context")]
+    WindowInitFailed,
 }
 
 #[derive(Debug)]
@@ -36,6 +119,8 @@ pub struct SystemBuilder {
     window_title: String,
     config_flags: BTreeSet<ConfigFlag>,
     target_fps: Option<usize>,
+    headless: bool,
+    max_gamepads: u32,
 }
 
 impl SystemBuilder {
@@ -44,6 +129,7 @@ impl SystemBuilder {
         height: 600,
     };
     pub const DEFAULT_WINDOW_TITLE: &'static str = "";
+    pub const DEFAULT_MAX_GAMEPADS: u32 = 8;
 
     pub fn new() -> Self {
         Self::default()
@@ -69,6 +155,23 @@ impl SystemBuilder {
         self
     }
 
+    /// Build a system without a visible window and sound output (e.g., for rendering tests on CI).
+    ///
+    /// NOTE: Frames are drawn into the framebuffer of a hidden window, and the audio device is a null device.
+    /// The window still needs a display server, so run under a virtual one on machines without a display
+    /// (e.g., `xvfb-run cargo test`). Set `LIBGL_ALWAYS_SOFTWARE=1` in the environment to render with
+    /// Mesa's software rasterizer when there is no GPU.
+    pub fn headless(&mut self) -> &mut Self {
+        self.headless = true;
+        self
+    }
+
+    /// Set the number of gamepads (and player slots) to handle.
+    pub fn max_gamepads(&mut self, n: u32) -> &mut Self {
+        self.max_gamepads = n;
+        self
+    }
+
     pub fn build(&self) -> Result<System, SystemBuildError> {
         if IS_SYSTEM_INITIALIZED.swap(true, Ordering::SeqCst) {
             return Err(SystemBuildError::AlreadyInitialized);
@@ -86,8 +189,12 @@ impl SystemBuilder {
         }
 
         // Initialize window.
+        let mut config_flags = self.config_flags.clone();
+        if self.headless {
+            config_flags.insert(ConfigFlag::WindowHidden);
+        }
         unsafe {
-            let flags = ConfigFlag::flags_to_bits(self.config_flags.iter().copied());
+            let flags = ConfigFlag::flags_to_bits(config_flags.into_iter());
             raylib4_sys::SetConfigFlags(flags);
         }
         unsafe {
@@ -98,23 +205,41 @@ impl SystemBuilder {
                 title.as_ptr(),
             );
         }
+        if !unsafe { raylib4_sys::IsWindowReady() } {
+            IS_SYSTEM_INITIALIZED.store(false, Ordering::SeqCst);
+            return Err(SystemBuildError::WindowInitFailed);
+        }
 
-        const MAX_GAMEPADS: u32 = 8; // TODO: Make configurable.
         let mut system = System {
             window: Window(()),
             monitors: Monitors(()),
             cursor: Cursor(()),
-            keyboard: Keyboard(()),
-            gamepads: (0..MAX_GAMEPADS).map(|index| Gamepad { index }).collect(),
-            mouse: Mouse(()),
-            touch: Touch(()),
-            audio_device: AudioDevice::new(),
+            keyboard: Keyboard::new(),
+            gamepads: (0..self.max_gamepads).map(Gamepad::new).collect(),
+            gamepad_tracker: GamepadTracker::new(self.max_gamepads),
+            mouse: Mouse::new(),
+            touch: Touch::new(),
+            audio_device: if self.headless {
+                AudioDevice::null()
+            } else {
+                AudioDevice::new()
+            },
             shapes_texture: None,
+            headless: self.headless,
+            quit_requested: false,
+            fixed_timestep: None,
+            clock: Clock::new(),
+            input_recorder: None,
+            input_replay: None,
+            input_injection: None,
+            screen_recorder: None,
         };
 
         if let Some(x) = self.target_fps {
             system.set_target_fps(x);
         }
+        system.gamepad_tracker.update(&system.gamepads);
+        IS_SYSTEM_THREAD.with(|x| x.set(true));
 
         Ok(system)
     }
@@ -127,6 +252,8 @@ impl Default for SystemBuilder {
             window_title: Self::DEFAULT_WINDOW_TITLE.to_owned(),
             config_flags: Default::default(),
             target_fps: None,
+            headless: false,
+            max_gamepads: Self::DEFAULT_MAX_GAMEPADS,
         }
     }
 }
@@ -138,13 +265,27 @@ pub struct System {
     cursor: Cursor,
     keyboard: Keyboard,
     gamepads: Vec<Gamepad>,
+    gamepad_tracker: GamepadTracker,
     mouse: Mouse,
     touch: Touch,
     audio_device: AudioDevice,
     shapes_texture: Option<Arc<Texture>>,
+    headless: bool,
+    quit_requested: bool,
+    fixed_timestep: Option<FixedTimestep>,
+    clock: Clock,
+    input_recorder: Option<InputRecorder<BufWriter<File>>>,
+    input_replay: Option<InputReplay<BufReader<File>>>,
+    input_injection: Option<InputInjection>,
+    screen_recorder: Option<ScreenRecorder>,
 }
 
 impl System {
+    /// Check if the system was built with [`SystemBuilder::headless`].
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
     pub fn window(&self) -> &Window {
         &self.window
     }
@@ -185,6 +326,37 @@ impl System {
         self.gamepads.iter_mut().filter(|x| x.is_available())
     }
 
+    /// Get the gamepads connected or disconnected in the last frame.
+    pub fn gamepad_events(&self) -> &[GamepadEvent] {
+        self.gamepad_tracker.events()
+    }
+
+    /// Get the gamepad assigned to a player slot.
+    ///
+    /// Connected gamepads are assigned to the lowest free slot,
+    /// and a gamepad that reconnects gets back the slot it had before.
+    pub fn player_gamepad(&self, slot: usize) -> Option<&Gamepad> {
+        let index = self.gamepad_tracker.slot_gamepad(slot)?;
+        self.gamepads.get(index as usize)
+    }
+
+    pub fn player_gamepad_mut(&mut self, slot: usize) -> Option<&mut Gamepad> {
+        let index = self.gamepad_tracker.slot_gamepad(slot)?;
+        self.gamepads.get_mut(index as usize)
+    }
+
+    /// Get the player slot of a connected gamepad.
+    pub fn player_slot(&self, gamepad: &Gamepad) -> Option<usize> {
+        self.gamepad_tracker.gamepad_slot(gamepad.index)
+    }
+
+    /// Free a player slot (e.g., when a player leaves the game).
+    ///
+    /// If the gamepad is still connected, it is not reassigned until it reconnects.
+    pub fn release_player_slot(&mut self, slot: usize) {
+        self.gamepad_tracker.release_slot(slot);
+    }
+
     pub fn mouse(&self) -> &Mouse {
         &self.mouse
     }
@@ -221,6 +393,68 @@ impl System {
         Ok(())
     }
 
+    /// Run the game loop until the window is closed, [`System::request_quit`] is called or `app` returns an error.
+    ///
+    /// NOTE: `App::update` is not called while the window is unfocused unless `ConfigFlag::WindowAlwaysRun` is set.
+    /// In headless mode, the (hidden) window is always treated as focused.
+    pub fn run<A: App>(&mut self, app: &mut A) -> Result<(), A::Error> {
+        self.quit_requested = false;
+        let mut focused = self.is_focused();
+        loop {
+            if self.window.should_close() && app.on_close_requested(self) {
+                break;
+            }
+            if self.quit_requested {
+                break;
+            }
+
+            if self.window.is_resized() {
+                let size = self.window.get_screen_size();
+                app.on_resize(self, size)?;
+            }
+            if self.is_focused() != focused {
+                focused = !focused;
+                app.on_focus_change(self, focused)?;
+            }
+
+            let paused = !focused && !self.window.is_state(ConfigFlag::WindowAlwaysRun);
+            let mut alpha = 1.0;
+            if !paused {
+                let dt = self.get_frame_time();
+                app.update(self, dt)?;
+
+                if let Some(mut timestep) = self.fixed_timestep.take() {
+                    let result = timestep.advance(dt, |step| app.fixed_update(self, step));
+                    alpha = timestep.alpha();
+                    self.fixed_timestep = Some(timestep);
+                    result?;
+                }
+            }
+
+            let mut canvas = self.next_frame();
+            app.draw(&mut canvas, alpha)?;
+        }
+        Ok(())
+    }
+
+    fn is_focused(&self) -> bool {
+        self.headless || self.window.is_focused()
+    }
+
+    /// Set the fixed timestep used to call `App::fixed_update` in [`System::run`].
+    pub fn set_fixed_timestep(&mut self, timestep: Option<FixedTimestep>) {
+        self.fixed_timestep = timestep;
+    }
+
+    pub fn fixed_timestep(&self) -> Option<&FixedTimestep> {
+        self.fixed_timestep.as_ref()
+    }
+
+    /// Make [`System::run`] return after the current frame.
+    pub fn request_quit(&mut self) {
+        self.quit_requested = true;
+    }
+
     /// Setup canvas (framebuffer) to start drawing.
     ///
     /// On drop: End canvas drawing and swap buffers (double buffering).
@@ -228,8 +462,14 @@ impl System {
         WindowCanvas::new(self)
     }
 
+    /// Begin drawing to render texture outside frames.
+    ///
+    /// The system is mutably borrowed while the texture canvas is alive,
+    /// so neither another texture canvas nor a frame can be begun.
+    ///
+    /// On drop: Ends drawing to render texture.
     pub fn create_texture_canvas<'a, 'b>(
-        &'a self,
+        &'a mut self,
         target: &'b mut RenderTexture,
     ) -> TextureCanvas<'a, 'b, Self> {
         TextureCanvas::new(self, target)
@@ -246,15 +486,173 @@ impl System {
     }
 
     /// Get time in seconds for last frame drawn (delta time).
+    ///
+    /// NOTE: The time is provided by the game clock (see [`System::clock`]).
     pub fn get_frame_time(&self) -> Duration {
-        let seconds = unsafe { raylib4_sys::GetFrameTime() };
-        Duration::from_secs_f32(seconds)
+        self.clock.frame_time()
     }
 
-    /// Get elapsed time in seconds since InitWindow().
+    /// Get elapsed game time since InitWindow().
+    ///
+    /// NOTE: The time is provided by the game clock (see [`System::clock`]).
     pub fn get_time(&self) -> Duration {
-        let seconds = unsafe { raylib4_sys::GetTime() };
-        Duration::from_secs_f64(seconds)
+        self.clock.time()
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+
+    /// Start recording the input state of each frame into a file.
+    pub fn start_input_recording<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        self.input_recorder = Some(InputRecorder::create(path)?);
+        Ok(())
+    }
+
+    pub fn stop_input_recording(&mut self) -> std::io::Result<()> {
+        if let Some(mut recorder) = self.input_recorder.take() {
+            recorder.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_recording_input(&self) -> bool {
+        self.input_recorder.is_some()
+    }
+
+    /// Start recording the screen into an animated GIF or a PNG sequence (see [`RecordingOptions`]).
+    ///
+    /// Frames are encoded on a background thread. If encoding falls behind, frames are dropped
+    /// instead of stalling the game loop.
+    pub fn start_recording<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: RecordingOptions,
+    ) -> std::io::Result<()> {
+        self.stop_recording()?;
+        self.screen_recorder = Some(ScreenRecorder::start(path, options)?);
+        Ok(())
+    }
+
+    /// Stop the screen recording and wait until the captured frames are written.
+    pub fn stop_recording(&mut self) -> std::io::Result<()> {
+        if let Some(recorder) = self.screen_recorder.take() {
+            recorder.finish()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.screen_recorder.is_some()
+    }
+
+    /// Called by `WindowCanvas` just before EndDrawing().
+    pub(crate) fn capture_frame(&mut self) {
+        if let Some(recorder) = &mut self.screen_recorder {
+            if !recorder.capture(&self.window) {
+                if let Some(Err(e)) = self.screen_recorder.take().map(|r| r.finish()) {
+                    log::warn!("failed to write screen recording (stopped): {}", e);
+                }
+            }
+        }
+    }
+
+    /// Start replaying input recorded by [`System::start_input_recording`].
+    ///
+    /// While replaying, the input devices (and the game clock) report the recorded states instead of the live ones.
+    /// The replay stops automatically at the end of the recording.
+    pub fn start_input_replay<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        self.input_replay = Some(InputReplay::open(path)?);
+        Ok(())
+    }
+
+    pub fn stop_input_replay(&mut self) {
+        self.input_replay = None;
+    }
+
+    pub fn is_replaying_input(&self) -> bool {
+        self.input_replay.is_some()
+    }
+
+    /// Inject synthetic input into the next frame.
+    ///
+    /// The injected events are merged into the input state when the current frame ends,
+    /// and the input accessors (e.g., `Keyboard::is_key_pressed`) report them during the next frame.
+    pub fn inject_input(&mut self) -> &mut InputInjection {
+        self.input_injection.get_or_insert_with(Default::default)
+    }
+
+    fn next_input_frame(&mut self, frame_time: Duration) -> Option<InputFrame> {
+        let mut frame = None;
+        if let Some(replay) = &mut self.input_replay {
+            match replay.read_frame() {
+                Ok(Some(f)) => frame = Some(f),
+                Ok(None) => log::info!("input replay finished"),
+                Err(e) => log::warn!("failed to read input replay (stopped): {}", e),
+            }
+            if frame.is_none() {
+                self.input_replay = None;
+            }
+        }
+        if frame.is_none() && (self.input_recorder.is_some() || self.input_injection.is_some()) {
+            frame = Some(InputFrame::capture(frame_time, self.gamepads.len() as u32));
+        }
+        let mut frame = frame?;
+
+        if let Some(injection) = self.input_injection.take() {
+            injection.apply(&mut frame);
+        }
+        if let Some(recorder) = &mut self.input_recorder {
+            if let Err(e) = recorder.write_frame(&frame) {
+                log::warn!("failed to write input recording (stopped): {}", e);
+                self.input_recorder = None;
+            }
+        }
+        Some(frame)
+    }
+
+    fn apply_input_frame(&mut self, frame: Option<InputFrame>) {
+        let frame = match frame {
+            None => {
+                self.keyboard.state = None;
+                self.mouse.state = None;
+                self.touch.state = None;
+                for gamepad in &mut self.gamepads {
+                    gamepad.state = None;
+                }
+                return;
+            }
+            Some(frame) => frame,
+        };
+        self.keyboard.state = Some(frame.keyboard);
+        self.mouse.state = Some(frame.mouse);
+        self.touch.state = Some(frame.touch);
+        let mut states = frame.gamepads.into_iter();
+        for gamepad in &mut self.gamepads {
+            gamepad.state = Some(states.next().unwrap_or_default());
+        }
+    }
+
+    /// Called by `WindowCanvas` just after EndDrawing().
+    pub(crate) fn end_frame(&mut self) {
+        let seconds = unsafe { raylib4_sys::GetFrameTime() };
+        let frame = self.next_input_frame(Duration::from_secs_f32(seconds));
+        let frame_time = frame
+            .as_ref()
+            .map_or(Duration::from_secs_f32(seconds), |f| f.frame_time);
+        self.clock.tick(frame_time);
+        self.apply_input_frame(frame);
+        self.gamepad_tracker.update(&self.gamepads);
+        for gamepad in &mut self.gamepads {
+            gamepad.update_triggers();
+        }
+        self.touch.update(self.clock.time());
+        self.audio_device.set_clock_paused(self.clock.is_paused());
+        unload_deferred();
     }
 
     /// Takes a screenshot of current screen (filename extension defines format).
@@ -281,6 +679,15 @@ impl System {
     }
 }
 
+impl Drop for System {
+    fn drop(&mut self) {
+        // Unload the GPU resources before the window (and OpenGL context) is closed.
+        self.shapes_texture = None;
+        unload_deferred();
+        IS_SYSTEM_THREAD.with(|x| x.set(false));
+    }
+}
+
 extern "C" fn trace_log_callback(
     log_level: c_int,
     text: *const c_char,
@@ -294,7 +701,9 @@ extern "C" fn trace_log_callback(
         log::LevelFilter::Debug => raylib4_sys::TraceLogLevel_LOG_DEBUG,
         log::LevelFilter::Trace => raylib4_sys::TraceLogLevel_LOG_TRACE,
     };
-    if log_level < filter as c_int {
+    let capturing = log_level >= raylib4_sys::TraceLogLevel_LOG_WARNING as c_int
+        && CAPTURED_WARNINGS.with(|c| c.borrow().is_some());
+    if log_level < filter as c_int && !capturing {
         return;
     }
 
@@ -304,6 +713,16 @@ extern "C" fn trace_log_callback(
             log::warn!("`vsprintf()` failed: {}", e);
         }
         Ok(s) => {
+            if capturing {
+                CAPTURED_WARNINGS.with(|c| {
+                    if let Some(warnings) = &mut *c.borrow_mut() {
+                        warnings.push(s.clone());
+                    }
+                });
+                if log_level < filter as c_int {
+                    return;
+                }
+            }
             if log_level >= raylib4_sys::TraceLogLevel_LOG_ERROR as c_int {
                 log::error!("{}", s);
             } else if log_level >= raylib4_sys::TraceLogLevel_LOG_WARNING as c_int {
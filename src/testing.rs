@@ -0,0 +1,178 @@
+//! Golden-image snapshot testing.
+//!
+//! Set the [`BLESS_ENV`] environment variable (e.g., `RFRAYLIB_BLESS=1 cargo test`)
+//! to create or update the reference images instead of comparing against them.
+use crate::core::drawing::TextureCanvas;
+use crate::structs::Size;
+use crate::texture::{Image, RenderTexture};
+use crate::{Color, System};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes [`Snapshot::check`] overwrite the reference images.
+pub const BLESS_ENV: &str = "RFRAYLIB_BLESS";
+
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    #[error("failed to load render texture")]
+    RenderTextureLoadFailed,
+
+    #[error("failed to read back rendered image")]
+    ReadBackFailed,
+
+    #[error("reference image {path:?} not found (run with {BLESS_ENV}=1 to create it)")]
+    ReferenceNotFound { path: PathBuf },
+
+    #[error("failed to write image {path:?}")]
+    ExportFailed { path: PathBuf },
+
+    #[error("size mismatch: expected {expected:?}, actual {actual:?}")]
+    SizeMismatch { expected: Size, actual: Size },
+
+    #[error(
+        "{pixels} pixels differ from the reference image (actual: {actual:?}, diff: {diff:?})"
+    )]
+    Mismatch {
+        pixels: usize,
+        actual: PathBuf,
+        diff: PathBuf,
+    },
+}
+
+/// Snapshot of a rendered frame that is compared with a stored PNG reference.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    reference: PathBuf,
+    size: Size,
+    tolerance: u8,
+}
+
+impl Snapshot {
+    pub fn new<P: AsRef<Path>>(reference: P, size: Size) -> Self {
+        Self {
+            reference: reference.as_ref().to_path_buf(),
+            size,
+            tolerance: 0,
+        }
+    }
+
+    /// Set the maximum allowed difference per color channel.
+    pub fn tolerance(&mut self, tolerance: u8) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Render `f` into an offscreen target and compare the result with the reference image.
+    ///
+    /// On mismatch, `<name>.actual.png` and `<name>.diff.png` are written next to the reference.
+    pub fn check<F>(&self, system: &mut System, f: F) -> Result<(), SnapshotError>
+    where
+        F: FnOnce(&mut TextureCanvas<System>),
+    {
+        let actual = self.render(system, f)?;
+        if is_blessing() {
+            return export(&actual, &self.reference);
+        }
+
+        let reference = match Image::load(&self.reference) {
+            Some(image) => image,
+            None => {
+                export(&actual, &self.sibling_path("actual"))?;
+                return Err(SnapshotError::ReferenceNotFound {
+                    path: self.reference.clone(),
+                });
+            }
+        };
+        if reference.size() != actual.size() {
+            export(&actual, &self.sibling_path("actual"))?;
+            return Err(SnapshotError::SizeMismatch {
+                expected: reference.size(),
+                actual: actual.size(),
+            });
+        }
+
+        let expected_colors = reference.load_colors();
+        let actual_colors = actual.load_colors();
+        let mut pixels = 0;
+        let diff_colors = expected_colors
+            .iter()
+            .zip(actual_colors.iter())
+            .map(|(&e, &a)| {
+                if self.is_same_color(e, a) {
+                    let v = (a.r as u32 * 299 + a.g as u32 * 587 + a.b as u32 * 114) / 1000;
+                    Color::rgba(v as u8, v as u8, v as u8, 64)
+                } else {
+                    pixels += 1;
+                    Color::MAGENTA
+                }
+            })
+            .collect::<Vec<_>>();
+        if pixels == 0 {
+            return Ok(());
+        }
+
+        let actual_path = self.sibling_path("actual");
+        let diff_path = self.sibling_path("diff");
+        let diff = Image::from_colors(actual.size(), &diff_colors).expect("unreachable");
+        export(&actual, &actual_path)?;
+        export(&diff, &diff_path)?;
+        Err(SnapshotError::Mismatch {
+            pixels,
+            actual: actual_path,
+            diff: diff_path,
+        })
+    }
+
+    fn render<F>(&self, system: &mut System, f: F) -> Result<Image, SnapshotError>
+    where
+        F: FnOnce(&mut TextureCanvas<System>),
+    {
+        let mut target =
+            RenderTexture::load(self.size).ok_or(SnapshotError::RenderTextureLoadFailed)?;
+        {
+            let mut canvas = system.create_texture_canvas(&mut target);
+            f(&mut canvas);
+        }
+        let mut image =
+            Image::load_from_texture(target.texture()).ok_or(SnapshotError::ReadBackFailed)?;
+
+        // Render textures are stored upside down.
+        image.flip_vertical();
+        Ok(image)
+    }
+
+    fn is_same_color(&self, a: Color, b: Color) -> bool {
+        let t = self.tolerance;
+        a.r.abs_diff(b.r) <= t
+            && a.g.abs_diff(b.g) <= t
+            && a.b.abs_diff(b.b) <= t
+            && a.a.abs_diff(b.a) <= t
+    }
+
+    fn sibling_path(&self, suffix: &str) -> PathBuf {
+        let stem = self
+            .reference
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.reference
+            .with_file_name(format!("{}.{}.png", stem, suffix))
+    }
+}
+
+fn is_blessing() -> bool {
+    std::env::var_os(BLESS_ENV).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+fn export(image: &Image, path: &Path) -> Result<(), SnapshotError> {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if image.export(path) {
+        Ok(())
+    } else {
+        Err(SnapshotError::ExportFailed {
+            path: path.to_path_buf(),
+        })
+    }
+}
@@ -1,19 +1,33 @@
 use crate::structs::{Rectangle, Size};
+use crate::system::{GpuOwner, Unload};
 use crate::texture::Image;
 use crate::Color;
 use std::ffi::CString;
 use std::os::raw::c_int;
 use std::path::Path;
+use std::sync::Arc;
 
-#[derive(Debug)]
-pub struct Font(pub(crate) raylib4_sys::Font); // TODO
+/// Font handle.
+///
+/// Fonts are reference counted: clones share the same font data,
+/// which is unloaded when the last handle is dropped.
+#[derive(Debug, Clone)]
+pub struct Font(Arc<GpuOwner<raylib4_sys::Font>>);
 
 impl Font {
+    fn new(font: raylib4_sys::Font) -> Self {
+        Self(Arc::new(GpuOwner::new(font)))
+    }
+
+    pub(crate) fn to_raw(&self) -> raylib4_sys::Font {
+        self.0.get()
+    }
+
     /// Load font from file into GPU memory (VRAM).
     pub fn load<P: AsRef<Path>>(path: P) -> Self {
         path_to_cstring(path)
             .map(|path| unsafe { raylib4_sys::LoadFont(path.as_ptr()) })
-            .map(Self)
+            .map(Self::new)
             .unwrap_or_else(Self::default)
     }
 
@@ -33,13 +47,15 @@ impl Font {
                     chars.len() as c_int,
                 )
             })
-            .map(Self)
+            .map(Self::new)
             .unwrap_or_else(Self::default)
     }
 
     /// Load font from Image (XNA style).
     pub fn load_from_image(image: &Image, key: Color, first_char: usize) -> Self {
-        Self(unsafe { raylib4_sys::LoadFontFromImage(image.0, key.into(), first_char as c_int) })
+        Self::new(unsafe {
+            raylib4_sys::LoadFontFromImage(image.0, key.into(), first_char as c_int)
+        })
     }
 
     /// Load font from memory buffer, fileType refers to extension: i.e. '.ttf'
@@ -66,13 +82,13 @@ impl Font {
                     chars.len() as c_int,
                 )
             };
-            Some(Self(font))
+            Some(Self::new(font))
         })()
         .unwrap_or_else(Self::default)
     }
 
     pub fn is_default(&self) -> bool {
-        self.0.texture.id == Self::default().0.texture.id
+        self.to_raw().texture.id == Self::default().to_raw().texture.id
     }
 
     /// Measure string width for default font.
@@ -89,39 +105,43 @@ impl Font {
         spacing: f32,
     ) -> Result<Size, std::ffi::NulError> {
         let text = CString::new(text)?;
-        Ok(unsafe { raylib4_sys::MeasureTextEx(self.0, text.as_ptr(), font_size, spacing).into() })
+        Ok(unsafe {
+            raylib4_sys::MeasureTextEx(self.to_raw(), text.as_ptr(), font_size, spacing).into()
+        })
     }
 
     /// Get glyph index position in font for a codepoint (unicode character), fallback to '?' if not found.
     pub fn get_glyph_index(&self, c: char) -> usize {
         // TODO: error check
-        unsafe { raylib4_sys::GetGlyphIndex(self.0, u32::from(c) as c_int) as usize }
+        unsafe { raylib4_sys::GetGlyphIndex(self.to_raw(), u32::from(c) as c_int) as usize }
     }
 
     /// Get glyph font info data for a codepoint (unicode character), fallback to '?' if not found.
     pub fn get_glyph_info(&self, c: char) -> GlyphInfoRef {
         let i = self.get_glyph_index(c);
+        let font = self.to_raw();
         GlyphInfoRef(
-            &unsafe { &*std::ptr::slice_from_raw_parts(self.0.glyphs, self.0.glyphCount as usize) }
-                [i],
+            &unsafe { &*std::ptr::slice_from_raw_parts(font.glyphs, font.glyphCount as usize) }[i],
         )
     }
 
     /// Get glyph rectangle in font atlas for a codepoint (unicode character), fallback to '?' if not found.
     pub fn get_glyph_atlas_rec(&self, c: char) -> Rectangle {
-        unsafe { raylib4_sys::GetGlyphAtlasRec(self.0, u32::from(c) as c_int).into() }
+        unsafe { raylib4_sys::GetGlyphAtlasRec(self.to_raw(), u32::from(c) as c_int).into() }
     }
 }
 
 impl Default for Font {
     fn default() -> Self {
-        Self(unsafe { raylib4_sys::GetFontDefault() })
+        Self::new(unsafe { raylib4_sys::GetFontDefault() })
     }
 }
 
-impl Drop for Font {
-    fn drop(&mut self) {
-        unsafe { raylib4_sys::UnloadFont(self.0) };
+// SAFETY: The glyph data pointed by `raylib4_sys::Font` is never modified after loading.
+unsafe impl Unload for raylib4_sys::Font {
+    fn unload(self) {
+        // NOTE: `UnloadFont()` does nothing for the default font.
+        unsafe { raylib4_sys::UnloadFont(self) };
     }
 }
 
@@ -1,8 +1,16 @@
+pub mod animated;
+pub mod animation;
+pub mod aseprite;
+pub mod atlas;
+
+pub use self::atlas::{TextureAtlas, TextureAtlasBuilder};
 use crate::structs::Rectangle;
+use crate::system::{GpuOwner, Unload};
 use crate::{Color, Position, Size};
 use std::ffi::CString;
 use std::os::raw::{c_int, c_void};
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct RenderTexture(
@@ -18,7 +26,10 @@ impl RenderTexture {
         if texture.id == 0 {
             None
         } else {
-            let t = Texture(texture.texture); // TODO
+            let t = Texture(
+                texture.texture,
+                Arc::new(GpuOwner::new(TextureOwner::RenderTexture(texture))),
+            );
             Some(Self(texture, t))
         }
     }
@@ -26,25 +37,39 @@ impl RenderTexture {
     pub fn texture(&self) -> &Texture {
         &self.1
     }
-}
 
-impl Drop for RenderTexture {
-    fn drop(&mut self) {
-        self.1 .0.id = 0; // TODO
-        unsafe { raylib4_sys::UnloadRenderTexture(self.0) };
+    pub fn texture_mut(&mut self) -> &mut Texture {
+        &mut self.1
     }
 }
 
-#[derive(Debug)]
+/// Texture handle.
+///
+/// Textures are reference counted: clones share the same GPU texture,
+/// which is unloaded when the last handle (or the owning [`RenderTexture`]) is dropped.
+#[derive(Debug, Clone)]
 pub struct Texture(
     pub(crate) raylib4_sys::Texture, // TODO
+    #[allow(dead_code)] Arc<GpuOwner<TextureOwner>>,
 );
 
 impl Texture {
+    fn new(texture: raylib4_sys::Texture) -> Self {
+        Self(
+            texture,
+            Arc::new(GpuOwner::new(TextureOwner::Texture(texture))),
+        )
+    }
+
     pub(crate) fn to_raw(&self) -> raylib4_sys::Texture {
         self.0.clone()
     }
 
+    /// Identity of the GPU texture shared by all clones (unique while a handle is alive).
+    pub(crate) fn key(&self) -> usize {
+        Arc::as_ptr(&self.1) as usize
+    }
+
     pub fn size(&self) -> Size {
         (self.0.width as i32, self.0.height as i32).into()
     }
@@ -56,7 +81,7 @@ impl Texture {
         if texture.id == 0 {
             None
         } else {
-            Some(Self(texture))
+            Some(Self::new(texture))
         }
     }
 
@@ -66,7 +91,7 @@ impl Texture {
         if texture.id == 0 {
             None
         } else {
-            Some(Self(texture))
+            Some(Self::new(texture))
         }
     }
 
@@ -76,7 +101,7 @@ impl Texture {
         if texture.id == 0 {
             None
         } else {
-            Some(Self(texture))
+            Some(Self::new(texture))
         }
     }
 
@@ -107,9 +132,19 @@ impl Texture {
     }
 }
 
-impl Drop for Texture {
-    fn drop(&mut self) {
-        unsafe { raylib4_sys::UnloadTexture(self.0) };
+#[derive(Debug, Clone, Copy)]
+enum TextureOwner {
+    Texture(raylib4_sys::Texture),
+    RenderTexture(raylib4_sys::RenderTexture),
+}
+
+// SAFETY: Textures hold no pointers.
+unsafe impl Unload for TextureOwner {
+    fn unload(self) {
+        match self {
+            Self::Texture(texture) => unsafe { raylib4_sys::UnloadTexture(texture) },
+            Self::RenderTexture(texture) => unsafe { raylib4_sys::UnloadRenderTexture(texture) },
+        }
     }
 }
 
@@ -354,6 +389,22 @@ impl Image {
         })
     }
 
+    /// Create an image from a Color array (RGBA - 32bit).
+    pub fn from_colors(size: Size, colors: &[Color]) -> Option<Self> {
+        if size.width < 0 || size.height < 0 || colors.len() != (size.width * size.height) as usize
+        {
+            return None;
+        }
+        let image = Self::generate_color(size, Color::BLANK);
+        let pixels = unsafe {
+            std::slice::from_raw_parts_mut(image.0.data as *mut raylib4_sys::Color, colors.len())
+        };
+        for (pixel, color) in pixels.iter_mut().zip(colors.iter().copied()) {
+            *pixel = color.into();
+        }
+        Some(image)
+    }
+
     /// Create an image from another image piece.
     pub fn to_trimed_image(&self, rectangle: Rectangle) -> Self {
         Self(unsafe { raylib4_sys::ImageFromImage(self.0, rectangle.into()) })
@@ -541,6 +592,13 @@ impl Image {
         unsafe { raylib4_sys::ImageClearBackground(&mut self.0, color.into()) };
     }
 
+    /// Make a software canvas that implements [`Draw`](crate::Draw) for this image.
+    ///
+    /// The image is converted to RGBA8 (`None` if it cannot be converted, e.g., compressed images).
+    pub fn canvas(&mut self) -> Option<crate::core::drawing::ImageCanvas<'_>> {
+        crate::core::drawing::ImageCanvas::new(self)
+    }
+
     /// Draw pixel within an image.
     pub fn draw_pixel(&mut self, position: Position, color: Color) {
         unsafe { raylib4_sys::ImageDrawPixelV(&mut self.0, position.into(), color.into()) };
@@ -0,0 +1,479 @@
+//! Animated images (GIF).
+//!
+//! ```no_run
+//! use rfraylib::texture::animated::{AnimatedImage, AnimatedTexture};
+//! use rfraylib::{Color, Draw, Position};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! let image = AnimatedImage::load("scarfy.gif").ok_or("failed to load image")?;
+//! println!("{} frames, loop: {:?}", image.len(), image.loop_count());
+//!
+//! let mut texture = AnimatedTexture::new(image).ok_or("failed to load texture")?;
+//! while !system.window().should_close() {
+//!     texture.update(system.get_frame_time());
+//!
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!     texture.draw(&mut canvas, Position { x: 10, y: 10 }, Color::WHITE);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::{Image, PixelFormat, Texture};
+use crate::{Color, Draw, Position, Rectangle, Size};
+use std::path::Path;
+use std::time::Duration;
+
+// Browsers show frames without a delay for 100 ms.
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// Number of times an animation is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoopCount {
+    Infinite,
+    Times(u32),
+}
+
+/// Frame of an [`AnimatedImage`].
+#[derive(Debug, Clone, Copy)]
+pub struct AnimatedFrame<'a> {
+    pub index: usize,
+
+    /// RGBA (8 bits per channel) pixels.
+    pub pixels: &'a [u8],
+
+    pub delay: Duration,
+}
+
+/// Image sequence with per-frame delays.
+#[derive(Debug, Clone)]
+pub struct AnimatedImage {
+    size: Size,
+    pixels: Vec<u8>,
+    delays: Vec<Duration>,
+    loop_count: LoopCount,
+}
+
+impl AnimatedImage {
+    /// Load an animated GIF (other formats supported by [`Image::load_anim`] give a single frame).
+    pub fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let path = path.as_ref();
+        let (mut image, frames) = Image::load_anim(path)?;
+        // GIFs are already RGBA8, but single-frame fallbacks keep their native format.
+        image.set_image_format(PixelFormat::UncompressedR8g8b8a8);
+        if image.0.format != PixelFormat::UncompressedR8g8b8a8 as i32 {
+            // Compressed formats can't be converted.
+            return None;
+        }
+        let size = image.size();
+        let len = frame_len(size) * frames;
+        let pixels = unsafe { std::slice::from_raw_parts(image.0.data as *const u8, len) }.to_vec();
+
+        let (mut delays, loop_count) = std::fs::read(path)
+            .ok()
+            .and_then(|data| parse_gif_timing(&data))
+            .unwrap_or((Vec::new(), LoopCount::Infinite));
+        delays.resize(frames, DEFAULT_DELAY);
+        Some(Self {
+            size,
+            pixels,
+            delays,
+            loop_count,
+        })
+    }
+
+    /// Make an animation from frames of the same size.
+    pub fn from_frames(frames: &[(Image, Duration)], loop_count: LoopCount) -> Option<Self> {
+        let size = frames.first()?.0.size();
+        let mut pixels = Vec::with_capacity(frame_len(size) * frames.len());
+        for (image, _) in frames {
+            if image.size() != size {
+                return None;
+            }
+            for c in image.load_colors() {
+                pixels.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+            }
+        }
+        Some(Self {
+            size,
+            pixels,
+            delays: frames.iter().map(|(_, delay)| *delay).collect(),
+            loop_count,
+        })
+    }
+
+    /// Get the size of a frame.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the number of frames.
+    pub fn len(&self) -> usize {
+        self.delays.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.delays.is_empty()
+    }
+
+    pub fn loop_count(&self) -> LoopCount {
+        self.loop_count
+    }
+
+    pub fn set_loop_count(&mut self, loop_count: LoopCount) {
+        self.loop_count = loop_count;
+    }
+
+    pub fn delays(&self) -> &[Duration] {
+        &self.delays
+    }
+
+    /// Set the delay of the frame at `index`.
+    ///
+    /// Returns `false` if there is no such frame.
+    pub fn set_delay(&mut self, index: usize, delay: Duration) -> bool {
+        match self.delays.get_mut(index) {
+            Some(d) => {
+                *d = delay;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the duration of one pass through the frames.
+    pub fn total_duration(&self) -> Duration {
+        self.delays.iter().sum()
+    }
+
+    /// Get the frame at `index` (a view into the pixels of all frames).
+    pub fn frame(&self, index: usize) -> Option<AnimatedFrame<'_>> {
+        let len = frame_len(self.size);
+        Some(AnimatedFrame {
+            index,
+            pixels: self.pixels.get(index * len..(index + 1) * len)?,
+            delay: *self.delays.get(index)?,
+        })
+    }
+
+    pub fn frames(&self) -> impl '_ + Iterator<Item = AnimatedFrame<'_>> {
+        (0..self.len()).filter_map(move |i| self.frame(i))
+    }
+
+    /// Copy the frame at `index` to a new image.
+    pub fn frame_image(&self, index: usize) -> Option<Image> {
+        let frame = self.frame(index)?;
+        let image = Image::generate_color(self.size, Color::BLANK);
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(image.0.data as *mut u8, frame.pixels.len()) };
+        data.copy_from_slice(frame.pixels);
+        Some(image)
+    }
+
+    /// Get the image with all frames stacked vertically.
+    pub fn to_sprite_sheet(&self) -> Image {
+        let size = Size {
+            width: self.size.width,
+            height: self.size.height * self.len() as i32,
+        };
+        let image = Image::generate_color(size, Color::BLANK);
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(image.0.data as *mut u8, self.pixels.len()) };
+        data.copy_from_slice(&self.pixels);
+        image
+    }
+}
+
+/// Texture showing the current frame of an [`AnimatedImage`].
+///
+/// The texture is updated in place when the frame changes,
+/// so commands recorded with [`AnimatedTexture::texture`] (e.g., in a [`DrawList`](crate::core::drawing::DrawList))
+/// show the frame that is current when they are executed.
+#[derive(Debug)]
+pub struct AnimatedTexture {
+    image: AnimatedImage,
+    texture: Texture,
+    frame: usize,
+    elapsed: Duration,
+    loops: u32,
+    finished: bool,
+    paused: bool,
+}
+
+impl AnimatedTexture {
+    pub fn new(image: AnimatedImage) -> Option<Self> {
+        let texture = Texture::load_from_image(&image.frame_image(0)?)?;
+        Some(Self {
+            image,
+            texture,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            loops: 0,
+            finished: false,
+            paused: false,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Option<Self> {
+        Self::new(AnimatedImage::load(path)?)
+    }
+
+    pub fn image(&self) -> &AnimatedImage {
+        &self.image
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn frame_index(&self) -> usize {
+        self.frame
+    }
+
+    /// Show the frame at `index`.
+    pub fn set_frame_index(&mut self, index: usize) {
+        if index < self.image.len() && index != self.frame {
+            self.frame = index;
+            self.upload();
+        }
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Returns `true` if the animation has been played the number of times given by its loop count.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Play the animation from the first frame.
+    pub fn restart(&mut self) {
+        self.set_frame_index(0);
+        self.loops = 0;
+        self.finished = false;
+    }
+
+    /// Advance the animation by `dt` (e.g., [`System::get_frame_time`](crate::System::get_frame_time)).
+    pub fn update(&mut self, dt: Duration) {
+        if self.paused || self.finished || self.image.total_duration().is_zero() {
+            return;
+        }
+
+        let mut frame = self.frame;
+        self.elapsed += dt;
+        while self.elapsed >= self.image.delays[frame] {
+            if frame + 1 < self.image.len() {
+                self.elapsed -= self.image.delays[frame];
+                frame += 1;
+                continue;
+            }
+            self.loops = self.loops.saturating_add(1);
+            if let LoopCount::Times(n) = self.image.loop_count {
+                if self.loops >= n {
+                    self.finished = true;
+                    self.elapsed = Duration::ZERO;
+                    break;
+                }
+            }
+            self.elapsed -= self.image.delays[frame];
+            frame = 0;
+        }
+        if frame != self.frame {
+            self.frame = frame;
+            self.upload();
+        }
+    }
+
+    /// Draw the current frame.
+    pub fn draw<T: Draw>(&self, canvas: &mut T, position: Position, tint: Color) {
+        canvas.draw_texture(&self.texture, position, tint);
+    }
+
+    fn upload(&mut self) {
+        if let Some(frame) = self.image.frame(self.frame) {
+            let area = Rectangle {
+                position: Position { x: 0, y: 0 },
+                size: self.image.size,
+            };
+            self.texture.update_rec(area, frame.pixels);
+        }
+    }
+}
+
+fn frame_len(size: Size) -> usize {
+    size.width.max(0) as usize * size.height.max(0) as usize * 4
+}
+
+/// Get the frame delays and the loop count of a GIF file.
+fn parse_gif_timing(data: &[u8]) -> Option<(Vec<Duration>, LoopCount)> {
+    if !data.starts_with(b"GIF") {
+        return None;
+    }
+    let packed = *data.get(10)?;
+    let mut pos = 13;
+    if packed & 0x80 != 0 {
+        pos += 3 << ((packed & 0x07) + 1);
+    }
+
+    let mut delays = Vec::new();
+    // Without the NETSCAPE2.0 extension, the animation is played once.
+    let mut loop_count = LoopCount::Times(1);
+    let mut delay = None;
+    loop {
+        match *data.get(pos)? {
+            // Extension.
+            0x21 => {
+                let label = *data.get(pos + 1)?;
+                pos += 2;
+                if label == 0xF9 {
+                    let centiseconds =
+                        u16::from_le_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]);
+                    delay = Some(centiseconds);
+                } else if label == 0xFF && data.get(pos + 1..pos + 12)? == b"NETSCAPE2.0" {
+                    let sub = pos + 12;
+                    if *data.get(sub)? == 3 && *data.get(sub + 1)? == 1 {
+                        // The count is the number of repetitions after the first play.
+                        let n = u16::from_le_bytes([*data.get(sub + 2)?, *data.get(sub + 3)?]);
+                        loop_count = match n {
+                            0 => LoopCount::Infinite,
+                            n => LoopCount::Times(n as u32 + 1),
+                        };
+                    }
+                }
+                pos = skip_sub_blocks(data, pos)?;
+            }
+            // Image descriptor.
+            0x2C => {
+                let packed = *data.get(pos + 9)?;
+                pos += 10;
+                if packed & 0x80 != 0 {
+                    pos += 3 << ((packed & 0x07) + 1);
+                }
+                pos += 1; // LZW minimum code size.
+                pos = skip_sub_blocks(data, pos)?;
+                delays.push(match delay.take() {
+                    None | Some(0) => DEFAULT_DELAY,
+                    Some(n) => Duration::from_millis(n as u64 * 10),
+                });
+            }
+            // Trailer.
+            0x3B => break,
+            _ => return None,
+        }
+    }
+    Some((delays, loop_count))
+}
+
+/// Skip data sub-blocks starting at `pos`, returning the position after the block terminator.
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *data.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            return Some(pos);
+        }
+        pos += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a 1x1 GIF with a global color table and a frame per delay (`None` for no graphic control extension).
+    fn gif(repetitions: Option<u16>, delays: &[Option<u16>]) -> Vec<u8> {
+        let mut data = b"GIF89a".to_vec();
+        data.extend([1, 0, 1, 0, 0x80, 0, 0]);
+        data.extend([0, 0, 0, 255, 255, 255]);
+        if let Some(n) = repetitions {
+            data.extend([0x21, 0xFF, 11]);
+            data.extend(b"NETSCAPE2.0");
+            data.extend([3, 1]);
+            data.extend(n.to_le_bytes());
+            data.push(0);
+        }
+        for (i, delay) in delays.iter().enumerate() {
+            if let Some(delay) = delay {
+                data.extend([0x21, 0xF9, 4, 0]);
+                data.extend(delay.to_le_bytes());
+                data.extend([0, 0]);
+            }
+            // Every other frame has a local color table.
+            let packed = if i % 2 == 1 { 0x80 } else { 0 };
+            data.extend([0x2C, 0, 0, 0, 0, 1, 0, 1, 0, packed]);
+            if packed != 0 {
+                data.extend([255, 0, 0, 0, 0, 255]);
+            }
+            data.extend([2, 2, 0x4C, 0x01, 0]);
+        }
+        data.push(0x3B);
+        data
+    }
+
+    #[test]
+    fn gif_delays() {
+        let (delays, _) =
+            parse_gif_timing(&gif(None, &[Some(5), Some(0), None, Some(250)])).unwrap();
+        assert_eq!(
+            delays,
+            [
+                Duration::from_millis(50),
+                DEFAULT_DELAY,
+                DEFAULT_DELAY,
+                Duration::from_millis(2500)
+            ]
+        );
+    }
+
+    #[test]
+    fn gif_loop_count() {
+        let loop_count = |repetitions| parse_gif_timing(&gif(repetitions, &[Some(1)])).unwrap().1;
+        assert_eq!(loop_count(None), LoopCount::Times(1));
+        assert_eq!(loop_count(Some(0)), LoopCount::Infinite);
+        assert_eq!(loop_count(Some(2)), LoopCount::Times(3));
+    }
+
+    #[test]
+    fn gif_rejects_bad_data() {
+        let data = gif(Some(0), &[Some(1), Some(2)]);
+        for len in 0..data.len() {
+            assert_eq!(parse_gif_timing(&data[..len]), None, "{}", len);
+        }
+        assert_eq!(parse_gif_timing(b"\x89PNG\r\n\x1a\n"), None);
+        let mut data = data;
+        let len = data.len();
+        data[len - 1] = 0;
+        assert_eq!(parse_gif_timing(&data), None);
+    }
+
+    #[test]
+    fn frame_len_ignores_negative_sizes() {
+        let size = |width, height| Size { width, height };
+        assert_eq!(frame_len(size(3, 2)), 24);
+        assert_eq!(frame_len(size(-3, 2)), 0);
+    }
+
+    #[test]
+    fn set_delay_ignores_missing_frames() {
+        let mut image = AnimatedImage {
+            size: Size {
+                width: 1,
+                height: 1,
+            },
+            pixels: vec![0; 8],
+            delays: vec![Duration::ZERO; 2],
+            loop_count: LoopCount::Infinite,
+        };
+        assert!(image.set_delay(1, Duration::from_millis(20)));
+        assert!(!image.set_delay(2, Duration::from_millis(20)));
+        assert_eq!(image.delays(), [Duration::ZERO, Duration::from_millis(20)]);
+        assert_eq!(image.total_duration(), Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,667 @@
+//! Sprite sheet animation.
+//!
+//! ```no_run
+//! use rfraylib::texture::animation::{AnimatedSprite, PlaybackMode, SpriteAnimation, SpriteGrid};
+//! use rfraylib::{Color, Draw, Position, Rectangle, Size};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//! let texture = rfraylib::texture::Texture::load("hero.png").ok_or("failed to load texture")?;
+//!
+//! // 8x4 frames of 32x32 pixels, the second row is the walk cycle.
+//! let grid = SpriteGrid::new(Size { width: 32, height: 32 }, 8, 4);
+//! let mut walk = SpriteAnimation::from_grid(&grid, 8..16, Duration::from_millis(100));
+//! walk.add_event(3, "footstep").add_event(7, "footstep");
+//! let mut idle = SpriteAnimation::from_grid(&grid, 0..4, Duration::from_millis(200));
+//! idle.set_mode(PlaybackMode::PingPong);
+//!
+//! let mut hero = AnimatedSprite::new(texture);
+//! hero.add_clip("walk", walk);
+//! hero.add_clip("idle", idle);
+//! hero.play("walk");
+//!
+//! while !system.window().should_close() {
+//!     hero.update(system.get_frame_time(), |event| {
+//!         if event.name == "footstep" {
+//!             // Play a sound.
+//!         }
+//!     });
+//!
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!     let dest = Rectangle::new(100, 100, 64, 64);
+//!     hero.draw(&mut canvas, dest, Position { x: 32, y: 32 }, 0.0, Color::WHITE);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::atlas::TextureAtlas;
+use super::Texture;
+use crate::{Color, Draw, Position, Rectangle, Size};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How [`AnimatedSprite`] plays a clip.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaybackMode {
+    /// Restart from the first frame after the last frame.
+    #[default]
+    Loop,
+
+    /// Play forward then backward repeatedly.
+    PingPong,
+
+    /// Stop at the last frame.
+    Once,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationFrame {
+    /// Part of the texture to draw.
+    pub source: Rectangle,
+
+    pub duration: Duration,
+
+    /// Size of the whole frame (larger than the source if the frame was trimmed in an atlas).
+    pub frame_size: Size,
+
+    /// Position of the source in the whole frame.
+    pub offset: Position,
+}
+
+impl AnimationFrame {
+    pub fn new(source: Rectangle, duration: Duration) -> Self {
+        Self {
+            source,
+            duration,
+            frame_size: source.size,
+            offset: Position { x: 0, y: 0 },
+        }
+    }
+}
+
+/// Layout of equally sized frames in a sprite sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpriteGrid {
+    pub frame_size: Size,
+    pub columns: usize,
+    pub rows: usize,
+
+    /// Position of the top-left frame.
+    pub offset: Position,
+
+    /// Gap between adjacent frames.
+    pub spacing: Size,
+}
+
+impl SpriteGrid {
+    pub fn new(frame_size: Size, columns: usize, rows: usize) -> Self {
+        Self {
+            frame_size,
+            columns,
+            rows,
+            offset: Position { x: 0, y: 0 },
+            spacing: Size {
+                width: 0,
+                height: 0,
+            },
+        }
+    }
+
+    /// Get the number of frames.
+    pub fn len(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the area of the `i`-th frame (in row-major order).
+    pub fn frame(&self, i: usize) -> Rectangle {
+        let column = (i % self.columns.max(1)) as i32;
+        let row = (i / self.columns.max(1)) as i32;
+        Rectangle::new(
+            self.offset.x + column * (self.frame_size.width + self.spacing.width),
+            self.offset.y + row * (self.frame_size.height + self.spacing.height),
+            self.frame_size.width,
+            self.frame_size.height,
+        )
+    }
+}
+
+/// Animation clip made of frames.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SpriteAnimation {
+    frames: Vec<AnimationFrame>,
+    mode: PlaybackMode,
+    events: Vec<(usize, String)>,
+}
+
+impl SpriteAnimation {
+    pub fn new(frames: Vec<AnimationFrame>) -> Self {
+        Self {
+            frames,
+            mode: PlaybackMode::default(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Make a clip from the frames of a grid (indices in row-major order).
+    pub fn from_grid<I>(grid: &SpriteGrid, indices: I, frame_duration: Duration) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let frames = indices
+            .into_iter()
+            .map(|i| AnimationFrame::new(grid.frame(i), frame_duration))
+            .collect();
+        Self::new(frames)
+    }
+
+    /// Make a clip from the atlas regions whose names start with `prefix`.
+    ///
+    /// The frames are ordered by the number following the prefix (e.g., "walk_2" comes before "walk_10").
+    pub fn from_atlas(atlas: &TextureAtlas, prefix: &str, frame_duration: Duration) -> Self {
+        let mut regions = atlas
+            .regions()
+            .iter()
+            .filter_map(|(name, region)| {
+                let suffix = name.strip_prefix(prefix)?;
+                let digits = suffix.trim_start_matches(|c: char| !c.is_ascii_digit());
+                let number = digits.parse::<u64>().ok();
+                Some(((number, name), region))
+            })
+            .collect::<Vec<_>>();
+        regions.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let frames = regions
+            .into_iter()
+            .map(|(_, region)| AnimationFrame {
+                source: region.rectangle,
+                duration: frame_duration,
+                frame_size: region.source_size,
+                offset: region.offset,
+            })
+            .collect();
+        Self::new(frames)
+    }
+
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    pub fn frames_mut(&mut self) -> &mut [AnimationFrame] {
+        &mut self.frames
+    }
+
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        self.mode = mode;
+    }
+
+    /// Set the duration of every frame.
+    pub fn set_frame_duration(&mut self, duration: Duration) {
+        for frame in &mut self.frames {
+            frame.duration = duration;
+        }
+    }
+
+    /// Fire an event named `name` each time the frame `frame` is shown.
+    pub fn add_event(&mut self, frame: usize, name: &str) -> &mut Self {
+        self.events.push((frame, name.to_owned()));
+        self
+    }
+
+    pub fn events(&self) -> impl '_ + Iterator<Item = (usize, &str)> {
+        self.events
+            .iter()
+            .map(|(frame, name)| (*frame, name.as_str()))
+    }
+
+    /// Get the duration of one pass through the frames.
+    pub fn total_duration(&self) -> Duration {
+        self.frames.iter().map(|f| f.duration).sum()
+    }
+
+    /// Get the duration after which a looping clip shows the same frame in the same direction again.
+    fn cycle_duration(&self) -> Option<Duration> {
+        let total = self.total_duration();
+        match self.mode {
+            PlaybackMode::Once => None,
+            PlaybackMode::Loop => Some(total),
+            PlaybackMode::PingPong => match self.frames.as_slice() {
+                [first, .., last] => Some(
+                    total
+                        .saturating_mul(2)
+                        .saturating_sub(first.duration + last.duration),
+                ),
+                _ => Some(total),
+            },
+        }
+    }
+
+    fn next_frame(&self, frame: usize, forward: bool) -> Option<(usize, bool)> {
+        let last = self.frames.len().checked_sub(1)?;
+        match self.mode {
+            PlaybackMode::Loop => Some((if frame < last { frame + 1 } else { 0 }, true)),
+            PlaybackMode::Once => (frame < last).then(|| (frame + 1, true)),
+            PlaybackMode::PingPong if last == 0 => Some((0, true)),
+            PlaybackMode::PingPong if forward => {
+                if frame < last {
+                    Some((frame + 1, true))
+                } else {
+                    Some((frame - 1, false))
+                }
+            }
+            PlaybackMode::PingPong => {
+                if frame > 0 {
+                    Some((frame - 1, false))
+                } else {
+                    Some((1, true))
+                }
+            }
+        }
+    }
+}
+
+/// Event fired by [`AnimatedSprite::update`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationEvent<'a> {
+    pub clip: &'a str,
+    pub frame: usize,
+    pub name: &'a str,
+}
+
+/// Texture with named clips and their playback state.
+#[derive(Debug, Clone)]
+pub struct AnimatedSprite {
+    texture: Texture,
+    clips: BTreeMap<String, SpriteAnimation>,
+    current: Option<String>,
+    frame: usize,
+    elapsed: Duration,
+    forward: bool,
+    finished: bool,
+    entered: bool,
+    paused: bool,
+    speed: f32,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl AnimatedSprite {
+    /// Maximum playback speed multiplier.
+    pub const MAX_SPEED: f32 = 1000.0;
+
+    pub fn new(texture: Texture) -> Self {
+        Self {
+            texture,
+            clips: BTreeMap::new(),
+            current: None,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            forward: true,
+            finished: false,
+            entered: false,
+            paused: false,
+            speed: 1.0,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    /// Add a clip (a clip added with the same name is replaced).
+    ///
+    /// Replacing the current clip restarts it.
+    pub fn add_clip(&mut self, name: &str, clip: SpriteAnimation) {
+        self.clips.insert(name.to_owned(), clip);
+        if self.current.as_deref() == Some(name) {
+            self.restart();
+        }
+    }
+
+    pub fn clip(&self, name: &str) -> Option<&SpriteAnimation> {
+        self.clips.get(name)
+    }
+
+    /// Play the clip named `name` from the start (does nothing if the clip is already playing).
+    ///
+    /// Returns `false` if there is no such clip.
+    pub fn play(&mut self, name: &str) -> bool {
+        if !self.clips.contains_key(name) {
+            return false;
+        }
+        if self.current.as_deref() != Some(name) || self.finished {
+            self.current = Some(name.to_owned());
+            self.restart();
+        }
+        self.paused = false;
+        true
+    }
+
+    /// Play the current clip from the start.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = Duration::ZERO;
+        self.forward = true;
+        self.finished = false;
+        self.entered = false;
+    }
+
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Get the index of the shown frame in the current clip.
+    pub fn frame_index(&self) -> usize {
+        self.frame
+    }
+
+    pub fn current_frame(&self) -> Option<&AnimationFrame> {
+        self.current_animation()?.frames.get(self.frame)
+    }
+
+    /// Returns `true` if a [`PlaybackMode::Once`] clip reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Set the playback speed multiplier (default: 1.0).
+    ///
+    /// The speed is clamped to `0.0..=MAX_SPEED` (NaN is treated as 0.0).
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = if speed.is_nan() {
+            0.0
+        } else {
+            speed.clamp(0.0, Self::MAX_SPEED)
+        };
+    }
+
+    pub fn flip_x(&self) -> bool {
+        self.flip_x
+    }
+
+    pub fn set_flip_x(&mut self, flip: bool) {
+        self.flip_x = flip;
+    }
+
+    pub fn flip_y(&self) -> bool {
+        self.flip_y
+    }
+
+    pub fn set_flip_y(&mut self, flip: bool) {
+        self.flip_y = flip;
+    }
+
+    /// Advance the playback by `dt` (multiplied by the speed).
+    ///
+    /// `on_event` is called for each event of the frames shown during `dt` (including skipped frames,
+    /// but a `dt` spanning many loops of the clip fires the events of the skipped loops only once).
+    pub fn update<F>(&mut self, dt: Duration, mut on_event: F)
+    where
+        F: FnMut(AnimationEvent),
+    {
+        let (name, clip) = match self
+            .current
+            .as_deref()
+            .and_then(|name| Some((name, self.clips.get(name)?)))
+        {
+            Some(x) => x,
+            None => return,
+        };
+        if clip.frames.is_empty() {
+            return;
+        }
+        let fire = |frame: usize, on_event: &mut F| {
+            for (_, event) in clip.events.iter().filter(|(f, _)| *f == frame) {
+                on_event(AnimationEvent {
+                    clip: name,
+                    frame,
+                    name: event,
+                });
+            }
+        };
+        if !self.entered {
+            self.entered = true;
+            fire(self.frame, &mut on_event);
+        }
+        if self.paused || self.finished || clip.total_duration().is_zero() {
+            return;
+        }
+
+        let dt = Duration::try_from_secs_f64(dt.as_secs_f64() * f64::from(self.speed))
+            .unwrap_or(Duration::MAX);
+        self.elapsed = self.elapsed.saturating_add(dt);
+        if let Some(cycle) = clip.cycle_duration() {
+            // Keep one cycle (so every frame is still shown) and drop the rest.
+            if self.elapsed >= cycle.saturating_mul(2) {
+                let rest = self.elapsed.as_nanos() % cycle.as_nanos();
+                let rest =
+                    Duration::new((rest / 1_000_000_000) as u64, (rest % 1_000_000_000) as u32);
+                self.elapsed = cycle + rest;
+            }
+        }
+        while self.elapsed >= clip.frames[self.frame].duration {
+            match clip.next_frame(self.frame, self.forward) {
+                Some((frame, forward)) => {
+                    self.elapsed -= clip.frames[self.frame].duration;
+                    self.frame = frame;
+                    self.forward = forward;
+                    fire(frame, &mut on_event);
+                }
+                None => {
+                    self.elapsed = Duration::ZERO;
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Draw the current frame (placed as in [`Draw::draw_texture_pro`]).
+    ///
+    /// `dest` is the area of the whole frame, so trimmed frames keep their placement.
+    pub fn draw<T: Draw>(
+        &self,
+        canvas: &mut T,
+        dest: Rectangle,
+        origin: Position,
+        rotation: f32,
+        tint: Color,
+    ) {
+        let frame = match self.current_frame() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let scale_x = dest.size.width as f32 / frame.frame_size.width.max(1) as f32;
+        let scale_y = dest.size.height as f32 / frame.frame_size.height.max(1) as f32;
+
+        let mut source = frame.source;
+        let mut offset = frame.offset;
+        if self.flip_x {
+            source.size.width = -source.size.width;
+            offset.x = frame.frame_size.width - frame.offset.x - frame.source.size.width;
+        }
+        if self.flip_y {
+            source.size.height = -source.size.height;
+            offset.y = frame.frame_size.height - frame.offset.y - frame.source.size.height;
+        }
+
+        let dest = Rectangle {
+            position: dest.position,
+            size: Size {
+                width: (frame.source.size.width as f32 * scale_x) as i32,
+                height: (frame.source.size.height as f32 * scale_y) as i32,
+            },
+        };
+        let origin = Position {
+            x: origin.x - (offset.x as f32 * scale_x) as i32,
+            y: origin.y - (offset.y as f32 * scale_y) as i32,
+        };
+        canvas.draw_texture_pro(&self.texture, source, dest, origin, rotation, tint);
+    }
+
+    fn current_animation(&self) -> Option<&SpriteAnimation> {
+        self.clips.get(self.current.as_deref()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::PixelFormat;
+
+    fn clip(len: usize, mode: PlaybackMode) -> SpriteAnimation {
+        let grid = SpriteGrid::new(
+            Size {
+                width: 16,
+                height: 16,
+            },
+            len,
+            1,
+        );
+        let mut clip = SpriteAnimation::from_grid(&grid, 0..len, Duration::from_millis(100));
+        clip.set_mode(mode);
+        clip
+    }
+
+    /// Get the frames shown after the first one until the clip stops (at most `count`).
+    fn play(clip: &SpriteAnimation, count: usize) -> Vec<usize> {
+        let (mut frame, mut forward) = (0, true);
+        let mut frames = Vec::new();
+        while frames.len() < count {
+            match clip.next_frame(frame, forward) {
+                Some(next) => (frame, forward) = next,
+                None => break,
+            }
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn loop_restarts_from_first_frame() {
+        assert_eq!(play(&clip(3, PlaybackMode::Loop), 7), [1, 2, 0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_both_ends() {
+        assert_eq!(
+            play(&clip(3, PlaybackMode::PingPong), 8),
+            [1, 2, 1, 0, 1, 2, 1, 0]
+        );
+        assert_eq!(play(&clip(1, PlaybackMode::PingPong), 3), [0, 0, 0]);
+    }
+
+    #[test]
+    fn once_stops_at_last_frame() {
+        assert_eq!(play(&clip(3, PlaybackMode::Once), 10), [1, 2]);
+        assert!(play(&clip(0, PlaybackMode::Loop), 10).is_empty());
+    }
+
+    #[test]
+    fn grid_frames_are_row_major() {
+        let mut grid = SpriteGrid::new(
+            Size {
+                width: 8,
+                height: 4,
+            },
+            3,
+            2,
+        );
+        grid.offset = Position { x: 1, y: 2 };
+        grid.spacing = Size {
+            width: 1,
+            height: 1,
+        };
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid.frame(0), Rectangle::new(1, 2, 8, 4));
+        assert_eq!(grid.frame(2), Rectangle::new(19, 2, 8, 4));
+        assert_eq!(grid.frame(4), Rectangle::new(10, 7, 8, 4));
+    }
+
+    #[test]
+    fn total_duration_sums_frames() {
+        let mut clip = clip(4, PlaybackMode::Loop);
+        assert_eq!(clip.total_duration(), Duration::from_millis(400));
+        clip.set_frame_duration(Duration::from_millis(50));
+        assert_eq!(clip.total_duration(), Duration::from_millis(200));
+    }
+
+    fn sprite(clip: SpriteAnimation) -> AnimatedSprite {
+        let texture = Texture::new(raylib4_sys::Texture {
+            id: 0,
+            width: 64,
+            height: 16,
+            mipmaps: 1,
+            format: PixelFormat::UncompressedR8g8b8a8 as i32,
+        });
+        let mut sprite = AnimatedSprite::new(texture);
+        sprite.add_clip("a", clip);
+        sprite.play("a");
+        sprite
+    }
+
+    #[test]
+    fn replacing_current_clip_restarts_it() {
+        let mut sprite = sprite(clip(4, PlaybackMode::Loop));
+        sprite.update(Duration::from_millis(350), |_| {});
+        assert_eq!(sprite.frame_index(), 3);
+        sprite.add_clip("a", clip(2, PlaybackMode::Loop));
+        assert_eq!(sprite.frame_index(), 0);
+        sprite.update(Duration::from_millis(150), |_| {});
+        assert_eq!(sprite.frame_index(), 1);
+    }
+
+    #[test]
+    fn long_updates_skip_whole_cycles() {
+        let hours = Duration::from_secs(3600 * 1000);
+        for (mode, dt, frame) in [
+            (PlaybackMode::Loop, hours + Duration::from_millis(150), 1),
+            // 400 ms per cycle (0, 1, 2, 1).
+            (
+                PlaybackMode::PingPong,
+                hours + Duration::from_millis(350),
+                1,
+            ),
+            (
+                PlaybackMode::PingPong,
+                hours + Duration::from_millis(250),
+                2,
+            ),
+        ] {
+            let mut clip = clip(3, mode);
+            clip.add_event(2, "last");
+            let mut sprite = sprite(clip);
+            let mut events = 0;
+            sprite.update(dt, |_| events += 1);
+            assert_eq!(sprite.frame_index(), frame, "{:?}", mode);
+            assert!((1..=3).contains(&events), "{:?}", mode);
+        }
+    }
+}
@@ -0,0 +1,1123 @@
+//! Aseprite (.ase / .aseprite) file importer.
+//!
+//! ```no_run
+//! use rfraylib::texture::aseprite::AsepriteFile;
+//! use rfraylib::texture::animation::AnimatedSprite;
+//! use rfraylib::texture::Texture;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let _system = rfraylib::SystemBuilder::new().build()?;
+//! let file = AsepriteFile::load("hero.aseprite")?;
+//!
+//! // Single frame.
+//! let icon = Texture::load_from_image(&file.frames()[0].image).ok_or("failed to load texture")?;
+//!
+//! // All frames with the tagged animations.
+//! let (sheet, grid) = file.sprite_sheet()?;
+//! let mut hero = AnimatedSprite::new(Texture::load_from_image(&sheet).ok_or("failed to load texture")?);
+//! for tag in file.tags() {
+//!     hero.add_clip(&tag.name, file.animation(&grid, &tag.name).expect("unreachable"));
+//! }
+//!
+//! // 9-slice panels.
+//! let panel = file.slice("panel").and_then(|s| s.keys[0].to_npatch_info());
+//! # Ok(())
+//! # }
+//! ```
+use super::animation::{AnimationFrame, PlaybackMode, SpriteAnimation, SpriteGrid};
+use super::{Image, NpatchInfo, NpatchLayout};
+use crate::{Color, Position, Rectangle, Size};
+use std::path::Path;
+use std::time::Duration;
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+const CHUNK_SLICE: u16 = 0x2022;
+
+const LAYER_FLAG_VISIBLE: u16 = 1;
+const LAYER_FLAG_BACKGROUND: u16 = 8;
+const LAYER_FLAG_REFERENCE: u16 = 64;
+
+/// Indexed sprites can only address 256 colors.
+const MAX_PALETTE_SIZE: usize = 256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AsepriteError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("malformed aseprite file: {reason}")]
+    Malformed { reason: &'static str },
+
+    #[error("unsupported color depth: {depth}")]
+    UnsupportedColorDepth { depth: u16 },
+
+    #[error("failed to decompress cel data")]
+    DecompressionFailed,
+
+    #[error("failed to make image")]
+    ImageCreationFailed,
+}
+
+/// Layer blend mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsepriteBlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+    Addition,
+    Subtract,
+    Divide,
+}
+
+impl AsepriteBlendMode {
+    fn from_raw_value(v: u16) -> Self {
+        match v {
+            1 => Self::Multiply,
+            2 => Self::Screen,
+            3 => Self::Overlay,
+            4 => Self::Darken,
+            5 => Self::Lighten,
+            6 => Self::ColorDodge,
+            7 => Self::ColorBurn,
+            8 => Self::HardLight,
+            9 => Self::SoftLight,
+            10 => Self::Difference,
+            11 => Self::Exclusion,
+            12 => Self::Hue,
+            13 => Self::Saturation,
+            14 => Self::Color,
+            15 => Self::Luminosity,
+            16 => Self::Addition,
+            17 => Self::Subtract,
+            18 => Self::Divide,
+            _ => Self::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AsepriteLayerKind {
+    Normal,
+    Group,
+    Tilemap,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsepriteLayer {
+    pub name: String,
+    pub kind: AsepriteLayerKind,
+
+    /// Nesting level in the layer groups (0 for top-level layers).
+    pub child_level: usize,
+
+    /// Visibility of the layer itself (layers in hidden groups are not drawn either).
+    pub visible: bool,
+
+    pub background: bool,
+    pub reference: bool,
+    pub opacity: u8,
+    pub blend_mode: AsepriteBlendMode,
+}
+
+#[derive(Debug, Clone)]
+pub struct AsepriteFrame {
+    /// Composition of the visible layers.
+    pub image: Image,
+
+    pub duration: Duration,
+}
+
+/// Playback direction of a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+/// Named range of frames.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsepriteTag {
+    pub name: String,
+
+    /// First frame index.
+    pub from: usize,
+
+    /// Last frame index (inclusive).
+    pub to: usize,
+
+    pub direction: TagDirection,
+
+    /// Number of times to play the tag (0 means infinite).
+    pub repeat: usize,
+}
+
+/// Named area of the sprite.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsepriteSlice {
+    pub name: String,
+
+    /// Keys ordered by frame (each key applies until the frame of the next key).
+    pub keys: Vec<SliceKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SliceKey {
+    /// First frame index this key applies to.
+    pub frame: usize,
+
+    pub bounds: Rectangle,
+
+    /// 9-slice center (relative to the bounds).
+    pub center: Option<Rectangle>,
+
+    /// Pivot (relative to the bounds).
+    pub pivot: Option<Position>,
+}
+
+impl SliceKey {
+    /// Get the n-patch info of a 9-slice (the source is the bounds in the frame image).
+    pub fn to_npatch_info(&self) -> Option<NpatchInfo> {
+        let center = self.center?;
+        Some(NpatchInfo {
+            source: self.bounds,
+            left: center.position.x,
+            top: center.position.y,
+            right: self.bounds.size.width - center.position.x - center.size.width,
+            bottom: self.bounds.size.height - center.position.y - center.size.height,
+            layout: NpatchLayout::NinePatch,
+        })
+    }
+}
+
+/// Parsed Aseprite file.
+///
+/// Group layers only affect the visibility of their children, and tilemap layers are not drawn.
+#[derive(Debug, Clone)]
+pub struct AsepriteFile {
+    size: Size,
+    layers: Vec<AsepriteLayer>,
+    frames: Vec<AsepriteFrame>,
+    tags: Vec<AsepriteTag>,
+    slices: Vec<AsepriteSlice>,
+}
+
+impl AsepriteFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, AsepriteError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, AsepriteError> {
+        Parser::default().parse(data)
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn layers(&self) -> &[AsepriteLayer] {
+        &self.layers
+    }
+
+    pub fn frames(&self) -> &[AsepriteFrame] {
+        &self.frames
+    }
+
+    pub fn tags(&self) -> &[AsepriteTag] {
+        &self.tags
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&AsepriteTag> {
+        self.tags.iter().find(|t| t.name == name)
+    }
+
+    pub fn slices(&self) -> &[AsepriteSlice] {
+        &self.slices
+    }
+
+    pub fn slice(&self, name: &str) -> Option<&AsepriteSlice> {
+        self.slices.iter().find(|s| s.name == name)
+    }
+
+    /// Lay out all frames in a row.
+    pub fn sprite_sheet(&self) -> Result<(Image, SpriteGrid), AsepriteError> {
+        let (w, h) = (self.size.width, self.size.height);
+        let columns = self.frames.len();
+        let (w, h) = (w as usize, h as usize);
+        let sheet_width = w
+            .checked_mul(columns)
+            .and_then(|width| i32::try_from(width).ok())
+            .ok_or(AsepriteError::ImageCreationFailed)?;
+        let len = (sheet_width as usize)
+            .checked_mul(h)
+            .ok_or(AsepriteError::ImageCreationFailed)?;
+        let mut colors = vec![Color::BLANK; len];
+        for (i, frame) in self.frames.iter().enumerate() {
+            let frame_colors = frame.image.load_colors();
+            for y in 0..h {
+                let src = y * w;
+                let dst = y * sheet_width as usize + i * w;
+                colors[dst..dst + w].copy_from_slice(&frame_colors[src..src + w]);
+            }
+        }
+        let size = Size {
+            width: sheet_width,
+            height: h as i32,
+        };
+        let image = Image::from_colors(size, &colors).ok_or(AsepriteError::ImageCreationFailed)?;
+        Ok((image, SpriteGrid::new(self.size, columns, 1)))
+    }
+
+    /// Make a clip from the tag named `tag` (`grid` is the one returned by [`AsepriteFile::sprite_sheet`]).
+    pub fn animation(&self, grid: &SpriteGrid, tag: &str) -> Option<SpriteAnimation> {
+        let tag = self.tag(tag)?;
+        let mut frames = (tag.from..=tag.to.min(self.frames.len().saturating_sub(1)))
+            .map(|i| AnimationFrame::new(grid.frame(i), self.frames[i].duration))
+            .collect::<Vec<_>>();
+        if matches!(
+            tag.direction,
+            TagDirection::Reverse | TagDirection::PingPongReverse
+        ) {
+            frames.reverse();
+        }
+        let mut animation = SpriteAnimation::new(frames);
+        if matches!(
+            tag.direction,
+            TagDirection::PingPong | TagDirection::PingPongReverse
+        ) {
+            animation.set_mode(PlaybackMode::PingPong);
+        }
+        Some(animation)
+    }
+}
+
+#[derive(Debug)]
+enum CelData {
+    Pixels { size: (i32, i32), data: Vec<u8> },
+    Linked { frame: usize },
+}
+
+#[derive(Debug)]
+struct Cel {
+    layer: usize,
+    position: (i32, i32),
+    opacity: u8,
+    z_index: i32,
+    data: CelData,
+}
+
+#[derive(Debug, Default)]
+struct Parser {
+    depth: u16,
+    transparent_index: u8,
+    layer_opacity_valid: bool,
+    palette: Vec<Color>,
+    layers: Vec<AsepriteLayer>,
+    durations: Vec<Duration>,
+    cels: Vec<Vec<Cel>>,
+    tags: Vec<AsepriteTag>,
+    slices: Vec<AsepriteSlice>,
+}
+
+impl Parser {
+    fn parse(mut self, data: &[u8]) -> Result<AsepriteFile, AsepriteError> {
+        let mut header = Reader::new(data);
+        header.skip(4)?;
+        if header.u16()? != FILE_MAGIC {
+            return Err(malformed("bad magic number"));
+        }
+        let frames = header.u16()?;
+        let size = Size {
+            width: header.u16()? as i32,
+            height: header.u16()? as i32,
+        };
+        self.depth = header.u16()?;
+        if !matches!(self.depth, 8 | 16 | 32) {
+            return Err(AsepriteError::UnsupportedColorDepth { depth: self.depth });
+        }
+        self.layer_opacity_valid = header.u32()? & 1 != 0;
+        header.skip(2 + 4 + 4)?;
+        self.transparent_index = header.u8()?;
+
+        let mut reader = Reader::new(data);
+        reader.skip(128)?;
+        for _ in 0..frames {
+            let start = reader.pos;
+            let frame_bytes = reader.u32()? as usize;
+            if reader.u16()? != FRAME_MAGIC {
+                return Err(malformed("bad frame magic number"));
+            }
+            let old_chunks = reader.u16()? as usize;
+            self.durations
+                .push(Duration::from_millis(reader.u16()? as u64));
+            reader.skip(2)?;
+            let chunks = match reader.u32()? as usize {
+                0 => old_chunks,
+                n => n,
+            };
+            self.cels.push(Vec::new());
+            for _ in 0..chunks {
+                let chunk_start = reader.pos;
+                let chunk_size = reader.u32()? as usize;
+                let chunk_type = reader.u16()?;
+                if chunk_size < 6 {
+                    return Err(malformed("bad chunk size"));
+                }
+                let body = reader.bytes(chunk_size - 6)?;
+                self.parse_chunk(chunk_type, Reader::new(body))?;
+                reader.pos = chunk_start + chunk_size;
+            }
+            reader.pos = start + frame_bytes;
+        }
+
+        let images = (0..self.cels.len())
+            .map(|frame| self.compose(frame, size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let frames = images
+            .into_iter()
+            .zip(self.durations)
+            .map(|(image, duration)| AsepriteFrame { image, duration })
+            .collect();
+        Ok(AsepriteFile {
+            size,
+            layers: self.layers,
+            frames,
+            tags: self.tags,
+            slices: self.slices,
+        })
+    }
+
+    fn parse_chunk(&mut self, chunk_type: u16, mut r: Reader) -> Result<(), AsepriteError> {
+        match chunk_type {
+            CHUNK_OLD_PALETTE if self.palette.is_empty() => {
+                let mut index = 0;
+                for _ in 0..r.u16()? {
+                    index += r.u8()? as usize;
+                    let count = match r.u8()? {
+                        0 => 256,
+                        n => n as usize,
+                    };
+                    for _ in 0..count {
+                        let (red, green, blue) = (r.u8()?, r.u8()?, r.u8()?);
+                        self.set_palette(index, Color::rgba(red, green, blue, 255))?;
+                        index += 1;
+                    }
+                }
+            }
+            CHUNK_PALETTE => {
+                let size = r.u32()? as usize;
+                let first = r.u32()? as usize;
+                let last = r.u32()? as usize;
+                r.skip(8)?;
+                if size > MAX_PALETTE_SIZE || last >= MAX_PALETTE_SIZE || first > last {
+                    return Err(malformed("invalid palette range"));
+                }
+                self.palette
+                    .resize(size.max(self.palette.len()), Color::BLANK);
+                for index in first..=last {
+                    let flags = r.u16()?;
+                    let color = Color::rgba(r.u8()?, r.u8()?, r.u8()?, r.u8()?);
+                    if flags & 1 != 0 {
+                        r.string()?;
+                    }
+                    self.set_palette(index, color)?;
+                }
+            }
+            CHUNK_LAYER => {
+                let flags = r.u16()?;
+                let kind = match r.u16()? {
+                    1 => AsepriteLayerKind::Group,
+                    2 => AsepriteLayerKind::Tilemap,
+                    _ => AsepriteLayerKind::Normal,
+                };
+                let child_level = r.u16()? as usize;
+                r.skip(4)?;
+                let blend_mode = AsepriteBlendMode::from_raw_value(r.u16()?);
+                let opacity = r.u8()?;
+                r.skip(3)?;
+                let name = r.string()?;
+                self.layers.push(AsepriteLayer {
+                    name,
+                    kind,
+                    child_level,
+                    visible: flags & LAYER_FLAG_VISIBLE != 0,
+                    background: flags & LAYER_FLAG_BACKGROUND != 0,
+                    reference: flags & LAYER_FLAG_REFERENCE != 0,
+                    opacity: if self.layer_opacity_valid {
+                        opacity
+                    } else {
+                        255
+                    },
+                    blend_mode,
+                });
+            }
+            CHUNK_CEL => {
+                let layer = r.u16()? as usize;
+                let position = (r.i16()? as i32, r.i16()? as i32);
+                let opacity = r.u8()?;
+                let cel_type = r.u16()?;
+                let z_index = r.i16()? as i32;
+                r.skip(5)?;
+                let data = match cel_type {
+                    0 | 2 => {
+                        let size = (r.u16()? as i32, r.u16()? as i32);
+                        let rest = r.bytes(r.remaining())?;
+                        let bytes_per_pixel = (self.depth / 8) as usize;
+                        let len = (size.0 as usize)
+                            .checked_mul(size.1 as usize)
+                            .and_then(|n| n.checked_mul(bytes_per_pixel))
+                            .ok_or_else(|| malformed("cel too large"))?;
+                        let data = if cel_type == 0 {
+                            rest.to_vec()
+                        } else {
+                            inflate(rest, len).ok_or(AsepriteError::DecompressionFailed)?
+                        };
+                        if data.len() < len {
+                            return Err(malformed("truncated cel"));
+                        }
+                        CelData::Pixels { size, data }
+                    }
+                    1 => CelData::Linked {
+                        frame: r.u16()? as usize,
+                    },
+                    // Tilemaps are not supported.
+                    _ => return Ok(()),
+                };
+                let cel = Cel {
+                    layer,
+                    position,
+                    opacity,
+                    z_index,
+                    data,
+                };
+                self.cels.last_mut().expect("unreachable").push(cel);
+            }
+            CHUNK_TAGS => {
+                let count = r.u16()?;
+                r.skip(8)?;
+                for _ in 0..count {
+                    let from = r.u16()? as usize;
+                    let to = r.u16()? as usize;
+                    let direction = match r.u8()? {
+                        1 => TagDirection::Reverse,
+                        2 => TagDirection::PingPong,
+                        3 => TagDirection::PingPongReverse,
+                        _ => TagDirection::Forward,
+                    };
+                    let repeat = r.u16()? as usize;
+                    r.skip(6 + 3 + 1)?;
+                    let name = r.string()?;
+                    self.tags.push(AsepriteTag {
+                        name,
+                        from,
+                        to,
+                        direction,
+                        repeat,
+                    });
+                }
+            }
+            CHUNK_SLICE => {
+                let count = r.u32()?;
+                let flags = r.u32()?;
+                r.skip(4)?;
+                let name = r.string()?;
+                let mut keys = Vec::new();
+                for _ in 0..count {
+                    let frame = r.u32()? as usize;
+                    let bounds = r.rectangle()?;
+                    let center = if flags & 1 != 0 {
+                        Some(r.rectangle()?)
+                    } else {
+                        None
+                    };
+                    let pivot = if flags & 2 != 0 {
+                        Some(Position {
+                            x: r.i32()?,
+                            y: r.i32()?,
+                        })
+                    } else {
+                        None
+                    };
+                    keys.push(SliceKey {
+                        frame,
+                        bounds,
+                        center,
+                        pivot,
+                    });
+                }
+                self.slices.push(AsepriteSlice { name, keys });
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn set_palette(&mut self, index: usize, color: Color) -> Result<(), AsepriteError> {
+        if index >= MAX_PALETTE_SIZE {
+            return Err(malformed("palette index out of range"));
+        }
+        if index >= self.palette.len() {
+            self.palette.resize(index + 1, Color::BLANK);
+        }
+        self.palette[index] = color;
+        Ok(())
+    }
+
+    /// Get whether the layer and all its parent groups are visible.
+    fn is_layer_visible(&self, index: usize) -> bool {
+        let mut level = match self.layers.get(index) {
+            Some(layer) if layer.visible && !layer.reference => layer.child_level,
+            _ => return false,
+        };
+        for layer in self.layers[..index].iter().rev() {
+            if layer.child_level < level {
+                if !layer.visible {
+                    return false;
+                }
+                level = layer.child_level;
+            }
+        }
+        true
+    }
+
+    fn compose(&self, frame: usize, size: Size) -> Result<Image, AsepriteError> {
+        let mut cels = self.cels[frame]
+            .iter()
+            .filter(|cel| {
+                self.is_layer_visible(cel.layer)
+                    && self.layers[cel.layer].kind == AsepriteLayerKind::Normal
+            })
+            .collect::<Vec<_>>();
+        cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index, cel.z_index));
+
+        let len = (size.width as usize)
+            .checked_mul(size.height as usize)
+            .ok_or(AsepriteError::ImageCreationFailed)?;
+        let mut canvas = vec![Color::BLANK; len];
+        for cel in cels {
+            let layer = &self.layers[cel.layer];
+            let (cel_size, data) = match self.resolve(frame, cel) {
+                Some(x) => x,
+                None => continue,
+            };
+            let opacity = cel.opacity as f32 / 255.0 * layer.opacity as f32 / 255.0;
+            for y in 0..cel_size.1 {
+                let cy = cel.position.1 + y;
+                if cy < 0 || cy >= size.height {
+                    continue;
+                }
+                for x in 0..cel_size.0 {
+                    let cx = cel.position.0 + x;
+                    if cx < 0 || cx >= size.width {
+                        continue;
+                    }
+                    let src_index = y as usize * cel_size.0 as usize + x as usize;
+                    let src = self.pixel(data, src_index, layer.background);
+                    let dst = &mut canvas[cy as usize * size.width as usize + cx as usize];
+                    *dst = blend(*dst, src, opacity, layer.blend_mode);
+                }
+            }
+        }
+        Image::from_colors(size, &canvas).ok_or(AsepriteError::ImageCreationFailed)
+    }
+
+    /// Get the pixels of a cel (following links to other frames).
+    fn resolve<'a>(&'a self, frame: usize, cel: &'a Cel) -> Option<((i32, i32), &'a [u8])> {
+        match &cel.data {
+            CelData::Pixels { size, data } => Some((*size, data)),
+            CelData::Linked { frame: linked } if *linked != frame => {
+                let target = self
+                    .cels
+                    .get(*linked)?
+                    .iter()
+                    .find(|c| c.layer == cel.layer)?;
+                match &target.data {
+                    CelData::Pixels { size, data } => Some((*size, data)),
+                    CelData::Linked { .. } => None,
+                }
+            }
+            CelData::Linked { .. } => None,
+        }
+    }
+
+    fn pixel(&self, data: &[u8], i: usize, background: bool) -> Color {
+        match self.depth {
+            32 => Color::rgba(
+                data[i * 4],
+                data[i * 4 + 1],
+                data[i * 4 + 2],
+                data[i * 4 + 3],
+            ),
+            16 => Color::rgba(data[i * 2], data[i * 2], data[i * 2], data[i * 2 + 1]),
+            _ => {
+                let index = data[i];
+                if index == self.transparent_index && !background {
+                    Color::BLANK
+                } else {
+                    self.palette
+                        .get(index as usize)
+                        .copied()
+                        .unwrap_or(Color::BLANK)
+                }
+            }
+        }
+    }
+}
+
+fn malformed(reason: &'static str) -> AsepriteError {
+    AsepriteError::Malformed { reason }
+}
+
+/// Decompress zlib data (the checksum is verified) into at most `max_len` bytes.
+fn inflate(data: &[u8], max_len: usize) -> Option<Vec<u8>> {
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, max_len).ok()
+}
+
+/// Blend `src` over `dst` as Aseprite does.
+fn blend(dst: Color, src: Color, opacity: f32, mode: AsepriteBlendMode) -> Color {
+    let sa = src.a as f32 / 255.0 * opacity;
+    if sa <= 0.0 {
+        return dst;
+    }
+    let ba = dst.a as f32 / 255.0;
+    let b = [dst.r, dst.g, dst.b].map(|c| c as f32 / 255.0);
+    let s = [src.r, src.g, src.b].map(|c| c as f32 / 255.0);
+
+    // Blended color over an opaque backdrop, mixed with the source color by the backdrop alpha.
+    let mixed = blend_rgb(b, s, mode);
+    let s = [0, 1, 2].map(|i| (1.0 - ba) * s[i] + ba * mixed[i]);
+
+    let ra = sa + ba - ba * sa;
+    let out = [0, 1, 2].map(|i| b[i] + (s[i] - b[i]) * sa / ra);
+    let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::rgba(to_u8(out[0]), to_u8(out[1]), to_u8(out[2]), to_u8(ra))
+}
+
+fn blend_rgb(b: [f32; 3], s: [f32; 3], mode: AsepriteBlendMode) -> [f32; 3] {
+    use AsepriteBlendMode::*;
+
+    let separable = |f: fn(f32, f32) -> f32| [f(b[0], s[0]), f(b[1], s[1]), f(b[2], s[2])];
+    match mode {
+        Normal => s,
+        Multiply => separable(|b, s| b * s),
+        Screen => separable(screen),
+        Overlay => separable(|b, s| hard_light(s, b)),
+        Darken => separable(f32::min),
+        Lighten => separable(f32::max),
+        ColorDodge => separable(|b, s| {
+            if b <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (b / (1.0 - s)).min(1.0)
+            }
+        }),
+        ColorBurn => separable(|b, s| {
+            if b >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - b) / s).min(1.0)
+            }
+        }),
+        HardLight => separable(hard_light),
+        SoftLight => separable(|b, s| {
+            if s <= 0.5 {
+                b - (1.0 - 2.0 * s) * b * (1.0 - b)
+            } else {
+                let d = if b <= 0.25 {
+                    ((16.0 * b - 12.0) * b + 4.0) * b
+                } else {
+                    b.sqrt()
+                };
+                b + (2.0 * s - 1.0) * (d - b)
+            }
+        }),
+        Difference => separable(|b, s| (b - s).abs()),
+        Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+        Hue => set_lum(set_sat(s, sat(b)), lum(b)),
+        Saturation => set_lum(set_sat(b, sat(s)), lum(b)),
+        Color => set_lum(s, lum(b)),
+        Luminosity => set_lum(b, lum(s)),
+        Addition => separable(|b, s| (b + s).min(1.0)),
+        Subtract => separable(|b, s| (b - s).max(0.0)),
+        Divide => separable(|b, s| {
+            if b <= 0.0 {
+                0.0
+            } else if b >= s {
+                1.0
+            } else {
+                b / s
+            }
+        }),
+    }
+}
+
+fn screen(b: f32, s: f32) -> f32 {
+    b + s - b * s
+}
+
+fn hard_light(b: f32, s: f32) -> f32 {
+    if s <= 0.5 {
+        b * 2.0 * s
+    } else {
+        screen(b, 2.0 * s - 1.0)
+    }
+}
+
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    let c = c.map(|v| v + d);
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        if n < 0.0 {
+            l + (v - l) * l / (l - n)
+        } else if x > 1.0 {
+            l + (v - l) * (1.0 - l) / (x - l)
+        } else {
+            v
+        }
+    })
+}
+
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    let max = c[0].max(c[1]).max(c[2]);
+    let min = c[0].min(c[1]).min(c[2]);
+    if max <= min {
+        return [0.0; 3];
+    }
+    c.map(|v| {
+        if v == max {
+            s
+        } else if v == min {
+            0.0
+        } else {
+            (v - min) * s / (max - min)
+        }
+    })
+}
+
+#[derive(Debug)]
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], AsepriteError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| malformed("unexpected end of data"))?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, n: usize) -> Result<(), AsepriteError> {
+        self.bytes(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, AsepriteError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, AsepriteError> {
+        Ok(u16::from_le_bytes(
+            self.bytes(2)?.try_into().expect("unreachable"),
+        ))
+    }
+
+    fn i16(&mut self) -> Result<i16, AsepriteError> {
+        Ok(i16::from_le_bytes(
+            self.bytes(2)?.try_into().expect("unreachable"),
+        ))
+    }
+
+    fn u32(&mut self) -> Result<u32, AsepriteError> {
+        Ok(u32::from_le_bytes(
+            self.bytes(4)?.try_into().expect("unreachable"),
+        ))
+    }
+
+    fn i32(&mut self) -> Result<i32, AsepriteError> {
+        Ok(i32::from_le_bytes(
+            self.bytes(4)?.try_into().expect("unreachable"),
+        ))
+    }
+
+    fn string(&mut self) -> Result<String, AsepriteError> {
+        let len = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+
+    fn rectangle(&mut self) -> Result<Rectangle, AsepriteError> {
+        let (x, y) = (self.i32()?, self.i32()?);
+        let (w, h) = (self.u32()? as i32, self.u32()? as i32);
+        Ok(Rectangle::new(x, y, w, h))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(chunk_type: u16, body: &[u8]) -> Vec<u8> {
+        let mut chunk = ((body.len() + 6) as u32).to_le_bytes().to_vec();
+        chunk.extend(chunk_type.to_le_bytes());
+        chunk.extend(body);
+        chunk
+    }
+
+    /// Make a 4x4 RGBA file with one frame made of `chunks`.
+    fn file(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body = chunks.concat();
+        let mut frame = ((body.len() + 16) as u32).to_le_bytes().to_vec();
+        frame.extend(FRAME_MAGIC.to_le_bytes());
+        frame.extend((chunks.len() as u16).to_le_bytes());
+        frame.extend(100u16.to_le_bytes());
+        frame.extend([0; 2]);
+        frame.extend((chunks.len() as u32).to_le_bytes());
+        frame.extend(body);
+
+        let mut header = vec![0; 128];
+        header[0..4].copy_from_slice(&((128 + frame.len()) as u32).to_le_bytes());
+        header[4..6].copy_from_slice(&FILE_MAGIC.to_le_bytes());
+        header[6..8].copy_from_slice(&1u16.to_le_bytes());
+        header[8..10].copy_from_slice(&4u16.to_le_bytes());
+        header[10..12].copy_from_slice(&4u16.to_le_bytes());
+        header[12..14].copy_from_slice(&32u16.to_le_bytes());
+        header.extend(frame);
+        header
+    }
+
+    fn palette(size: u32, first: u32, last: u32) -> Vec<u8> {
+        let mut body = [size, first, last].map(u32::to_le_bytes).concat();
+        body.extend([0; 8]);
+        for _ in first..=last.min(first.saturating_add(3)) {
+            body.extend([0, 0, 1, 2, 3, 255]);
+        }
+        chunk(CHUNK_PALETTE, &body)
+    }
+
+    fn cel(cel_type: u16, size: (u16, u16), data: &[u8]) -> Vec<u8> {
+        // Layer, position, then opacity.
+        let mut body = vec![0; 6];
+        body.push(255);
+        body.extend(cel_type.to_le_bytes());
+        body.extend([0; 7]);
+        body.extend(size.0.to_le_bytes());
+        body.extend(size.1.to_le_bytes());
+        body.extend(data);
+        chunk(CHUNK_CEL, &body)
+    }
+
+    fn layer() -> Vec<u8> {
+        let mut body = LAYER_FLAG_VISIBLE.to_le_bytes().to_vec();
+        body.extend([0; 10]);
+        body.extend([255, 0, 0, 0]);
+        body.extend(1u16.to_le_bytes());
+        body.push(b'a');
+        chunk(CHUNK_LAYER, &body)
+    }
+
+    fn is_malformed(result: Result<AsepriteFile, AsepriteError>) -> bool {
+        matches!(result, Err(AsepriteError::Malformed { .. }))
+    }
+
+    #[test]
+    fn parses_raw_and_compressed_cels() {
+        let pixels = [10, 20, 30, 255].repeat(16);
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&pixels, 6);
+        for chunks in [
+            [layer(), cel(0, (4, 4), &pixels)],
+            [layer(), cel(2, (4, 4), &compressed)],
+        ] {
+            let file = AsepriteFile::from_bytes(&file(&chunks)).unwrap_or_else(|e| panic!("{}", e));
+            assert_eq!(file.layers()[0].name, "a");
+            assert_eq!(file.frames().len(), 1);
+            assert_eq!(file.frames()[0].duration, Duration::from_millis(100));
+            assert_eq!(
+                file.frames()[0].image.load_colors(),
+                [Color::rgb(10, 20, 30); 16]
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_header() {
+        assert!(is_malformed(AsepriteFile::from_bytes(&[])));
+        let mut data = file(&[]);
+        data[4] ^= 1;
+        assert!(is_malformed(AsepriteFile::from_bytes(&data)));
+        let mut data = file(&[]);
+        data[12] = 24;
+        assert!(matches!(
+            AsepriteFile::from_bytes(&data),
+            Err(AsepriteError::UnsupportedColorDepth { depth: 24 })
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let data = file(&[cel(0, (4, 4), &[0; 64])]);
+        for len in [100, 130, 150, data.len() - 1] {
+            assert!(is_malformed(AsepriteFile::from_bytes(&data[..len])));
+        }
+        assert!(is_malformed(AsepriteFile::from_bytes(&file(&[cel(
+            0,
+            (4, 4),
+            &[0; 63]
+        )]))));
+    }
+
+    #[test]
+    fn rejects_bad_palette_ranges() {
+        for (size, first, last) in [(300, 0, 0), (16, 0, 256), (16, 3, 2), (16, u32::MAX, 0)] {
+            let data = file(&[palette(size, first, last)]);
+            assert!(is_malformed(AsepriteFile::from_bytes(&data)));
+        }
+    }
+
+    #[test]
+    fn rejects_old_palette_past_256_colors() {
+        // Two packets of 256 colors.
+        let mut body = 2u16.to_le_bytes().to_vec();
+        for _ in 0..2 {
+            body.extend([0, 0]);
+            body.extend([0; 256 * 3]);
+        }
+        let data = file(&[chunk(CHUNK_OLD_PALETTE, &body)]);
+        assert!(is_malformed(AsepriteFile::from_bytes(&data)));
+    }
+
+    #[test]
+    fn rejects_bad_compressed_cels() {
+        let pixels = [7; 64];
+        let mut compressed = miniz_oxide::deflate::compress_to_vec_zlib(&pixels, 6);
+        let len = compressed.len();
+        compressed[len - 1] ^= 1;
+        for data in [
+            &compressed[..],
+            &compressed[..len / 2],
+            &[0x78, 0x9c, 1, 2, 3],
+        ] {
+            assert!(matches!(
+                AsepriteFile::from_bytes(&file(&[cel(2, (4, 4), data)])),
+                Err(AsepriteError::DecompressionFailed)
+            ));
+        }
+
+        // More pixels than the cel size.
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&[0; 128], 6);
+        assert!(matches!(
+            AsepriteFile::from_bytes(&file(&[cel(2, (4, 4), &compressed)])),
+            Err(AsepriteError::DecompressionFailed)
+        ));
+    }
+
+    #[test]
+    fn inflate_round_trips() {
+        let pixels = (0..=255).collect::<Vec<u8>>();
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&pixels, 6);
+        assert_eq!(inflate(&compressed, pixels.len()), Some(pixels));
+    }
+
+    #[test]
+    fn blend_normal() {
+        let (red, blue) = (Color::rgb(255, 0, 0), Color::rgb(0, 0, 255));
+        assert_eq!(blend(red, blue, 1.0, AsepriteBlendMode::Normal), blue);
+        assert_eq!(blend(red, blue, 0.0, AsepriteBlendMode::Normal), red);
+        assert_eq!(
+            blend(red, blue, 128.0 / 255.0, AsepriteBlendMode::Normal),
+            Color::rgb(127, 0, 128)
+        );
+        assert_eq!(
+            blend(
+                Color::BLANK,
+                Color::rgba(0, 0, 255, 51),
+                1.0,
+                AsepriteBlendMode::Multiply
+            ),
+            Color::rgba(0, 0, 255, 51)
+        );
+    }
+
+    #[test]
+    fn blend_separable_modes() {
+        use AsepriteBlendMode as Mode;
+
+        let gray = Color::rgb(128, 128, 128);
+        let color = Color::rgb(10, 100, 200);
+        let cases = [
+            (Mode::Multiply, Color::WHITE, color, color),
+            (Mode::Multiply, Color::BLACK, color, Color::BLACK),
+            (Mode::Screen, Color::BLACK, color, color),
+            (Mode::Screen, Color::WHITE, color, Color::WHITE),
+            (Mode::Darken, gray, color, Color::rgb(10, 100, 128)),
+            (Mode::Lighten, gray, color, Color::rgb(128, 128, 200)),
+            (Mode::Difference, color, color, Color::BLACK),
+            (Mode::Addition, gray, gray, Color::WHITE),
+            (Mode::Subtract, gray, gray, Color::BLACK),
+        ];
+        for (mode, dst, src, expected) in cases {
+            assert_eq!(blend(dst, src, 1.0, mode), expected, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn blend_non_separable_modes_keep_luminosity() {
+        use AsepriteBlendMode as Mode;
+
+        let (b, s) = ([0.2, 0.5, 0.8], [0.9, 0.1, 0.3]);
+        for mode in [Mode::Hue, Mode::Saturation, Mode::Color] {
+            assert!(
+                (lum(blend_rgb(b, s, mode)) - lum(b)).abs() < 1e-4,
+                "{:?}",
+                mode
+            );
+        }
+        assert!((lum(blend_rgb(b, s, Mode::Luminosity)) - lum(s)).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,838 @@
+//! Texture atlas packing.
+//!
+//! ```no_run
+//! use rfraylib::texture::atlas::{PackMethod, TextureAtlasBuilder};
+//! use rfraylib::texture::Image;
+//! use rfraylib::{Color, Draw, Position};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut system = rfraylib::SystemBuilder::new().build()?;
+//!
+//! let mut builder = TextureAtlasBuilder::new();
+//! for name in ["player", "enemy", "coin"] {
+//!     let image = Image::load(format!("sprites/{}.png", name)).ok_or("failed to load image")?;
+//!     builder.add_image(name, image);
+//! }
+//! let atlas = builder
+//!     .pack_method(PackMethod::MaxRects)
+//!     .padding(2)
+//!     .extrude(1)
+//!     .trim(0.0)
+//!     .power_of_two(true)
+//!     .build()?;
+//!
+//! while !system.window().should_close() {
+//!     let mut canvas = system.next_frame();
+//!     canvas.clear_background(Color::RAYWHITE);
+//!     let coin = atlas.get("coin").ok_or("no such sprite")?;
+//!     canvas.draw_texture_rec(atlas.texture(), coin, Position { x: 10, y: 10 }, Color::WHITE);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use super::{Image, Texture};
+use crate::{Color, Position, Rectangle, Size};
+use std::collections::BTreeMap;
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AtlasError {
+    #[error("images do not fit in an atlas of {}x{} pixels", max_size.width, max_size.height)]
+    TooLarge { max_size: Size },
+
+    #[error("failed to make the atlas image")]
+    ImageCreationFailed,
+
+    #[error("failed to load texture")]
+    TextureLoadFailed,
+
+    #[error("failed to load image {path:?}")]
+    ImageLoadFailed { path: std::path::PathBuf },
+
+    #[error("failed to export image {path:?}")]
+    ImageExportFailed { path: std::path::PathBuf },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "serde")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Rectangle packing algorithm of [`TextureAtlasBuilder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PackMethod {
+    /// Bottom-left skyline packing (fast).
+    Skyline,
+
+    /// Maximal rectangles packing with the best short side fit heuristic (tighter).
+    #[default]
+    MaxRects,
+}
+
+/// Location of an image in a texture atlas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AtlasRegion {
+    /// Area of the (trimmed) image in the atlas.
+    pub rectangle: Rectangle,
+
+    /// Size of the image before trimming.
+    pub source_size: Size,
+
+    /// Position of the trimmed area in the image before trimming.
+    pub offset: Position,
+}
+
+impl AtlasRegion {
+    pub fn is_trimmed(&self) -> bool {
+        self.rectangle.size != self.source_size
+    }
+}
+
+/// Builder of texture atlases.
+///
+/// Images are packed without rotation.
+#[derive(Debug, Clone)]
+pub struct TextureAtlasBuilder {
+    images: BTreeMap<String, Image>,
+    pack_method: PackMethod,
+    padding: usize,
+    extrude: usize,
+    trim_threshold: Option<f32>,
+    power_of_two: bool,
+    max_size: Size,
+}
+
+impl TextureAtlasBuilder {
+    pub const DEFAULT_MAX_SIZE: Size = Size {
+        width: 4096,
+        height: 4096,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an image (an image added with the same name is replaced).
+    pub fn add_image(&mut self, name: &str, image: Image) -> &mut Self {
+        self.images.insert(name.to_owned(), image);
+        self
+    }
+
+    pub fn pack_method(&mut self, method: PackMethod) -> &mut Self {
+        self.pack_method = method;
+        self
+    }
+
+    /// Set the number of transparent pixels between images (default: 0).
+    pub fn padding(&mut self, padding: usize) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the number of pixels by which the edges of each image are repeated (default: 0).
+    ///
+    /// Extrusion prevents bleeding of neighboring images when sampling with filtering or subpixel offsets.
+    pub fn extrude(&mut self, pixels: usize) -> &mut Self {
+        self.extrude = pixels;
+        self
+    }
+
+    /// Trim the transparent borders of the images (see [`Image::get_alpha_border`]).
+    pub fn trim(&mut self, threshold: f32) -> &mut Self {
+        self.trim_threshold = Some(threshold);
+        self
+    }
+
+    /// Make the width and height of the atlas powers of two.
+    pub fn power_of_two(&mut self, enabled: bool) -> &mut Self {
+        self.power_of_two = enabled;
+        self
+    }
+
+    /// Set the maximum size of the atlas (default: [`TextureAtlasBuilder::DEFAULT_MAX_SIZE`]).
+    pub fn max_size(&mut self, size: Size) -> &mut Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Pack the images into an atlas texture.
+    pub fn build(&self) -> Result<TextureAtlas, AtlasError> {
+        TextureAtlas::from_image(&self.build_image()?)
+    }
+
+    /// Pack the images into an atlas image (this does not need a GPU).
+    pub fn build_image(&self) -> Result<AtlasImage, AtlasError> {
+        let border = self.extrude as i32;
+        let sprites = self
+            .images
+            .iter()
+            .map(|(name, image)| {
+                let source_size = image.size();
+                let mut area = Rectangle {
+                    position: Position { x: 0, y: 0 },
+                    size: source_size,
+                };
+                if let Some(threshold) = self.trim_threshold {
+                    area = image.get_alpha_border(threshold);
+                }
+                Sprite {
+                    name,
+                    source_size,
+                    area,
+                    colors: image.load_colors(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Each slot holds an extruded image followed by padding.
+        let spacing = self.padding as i32;
+        let slots = sprites
+            .iter()
+            .map(|s| {
+                if s.area.size.width <= 0 || s.area.size.height <= 0 {
+                    (0, 0)
+                } else {
+                    (
+                        s.area.size.width + border * 2 + spacing,
+                        s.area.size.height + border * 2 + spacing,
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        let (size, positions) = self.pack_slots(&slots)?;
+
+        let mut colors = vec![Color::BLANK; (size.width * size.height) as usize];
+        let mut regions = BTreeMap::new();
+        for (sprite, position) in sprites.iter().zip(positions) {
+            let rectangle = Rectangle {
+                position: Position {
+                    x: position.x + border,
+                    y: position.y + border,
+                },
+                size: sprite.area.size,
+            };
+            sprite.blit(&mut colors, size.width, rectangle, border);
+            regions.insert(
+                sprite.name.clone(),
+                AtlasRegion {
+                    rectangle,
+                    source_size: sprite.source_size,
+                    offset: sprite.area.position,
+                },
+            );
+        }
+
+        let image = Image::from_colors(size, &colors).ok_or(AtlasError::ImageCreationFailed)?;
+        Ok(AtlasImage { image, regions })
+    }
+
+    fn pack_slots(&self, slots: &[(i32, i32)]) -> Result<(Size, Vec<Position>), AtlasError> {
+        let too_large = AtlasError::TooLarge {
+            max_size: self.max_size,
+        };
+        let spacing = self.padding as i32;
+
+        // The trailing padding of the rightmost and bottommost slots may lie outside of the atlas.
+        let max_width = self.max_size.width + spacing;
+        let max_height = self.max_size.height + spacing;
+        let area = slots.iter().map(|&(w, h)| w as i64 * h as i64).sum::<i64>();
+        let widest = slots.iter().map(|&(w, _)| w).max().unwrap_or(0);
+        let tallest = slots.iter().map(|&(_, h)| h).max().unwrap_or(0);
+        if widest > max_width || tallest > max_height {
+            return Err(too_large);
+        }
+
+        let mut best: Option<(Size, Vec<Position>)> = None;
+        if self.power_of_two {
+            let mut candidates = Vec::new();
+            for w in powers_of_two(self.max_size.width) {
+                for h in powers_of_two(self.max_size.height) {
+                    candidates.push((w, h));
+                }
+            }
+            candidates.sort_by_key(|&(w, h)| (w as i64 * h as i64, w.max(h)));
+            for (w, h) in candidates {
+                if let Some(positions) = pack(self.pack_method, (w + spacing, h + spacing), slots) {
+                    best = Some((
+                        Size {
+                            width: w,
+                            height: h,
+                        },
+                        positions,
+                    ));
+                    break;
+                }
+            }
+        } else {
+            // Try a few bin widths and keep the one that gives the smallest atlas.
+            let mut width = ((area as f64).sqrt() as i32).max(widest).max(1);
+            loop {
+                let width_limit = width.min(max_width);
+                if let Some(positions) = pack(self.pack_method, (width_limit, max_height), slots) {
+                    let used = slots.iter().zip(&positions).fold((1, 1), |(w, h), (s, p)| {
+                        (w.max(p.x + s.0 - spacing), h.max(p.y + s.1 - spacing))
+                    });
+                    let size = Size {
+                        width: used.0,
+                        height: used.1,
+                    };
+                    let better = match &best {
+                        Some((b, _)) => {
+                            (size.width as i64 * size.height as i64)
+                                < (b.width as i64 * b.height as i64)
+                        }
+                        None => true,
+                    };
+                    if better {
+                        best = Some((size, positions));
+                    }
+                }
+                if width_limit == max_width {
+                    break;
+                }
+                width += (width / 8).max(1);
+            }
+        }
+        best.ok_or(too_large)
+    }
+}
+
+impl Default for TextureAtlasBuilder {
+    fn default() -> Self {
+        Self {
+            images: BTreeMap::new(),
+            pack_method: PackMethod::default(),
+            padding: 0,
+            extrude: 0,
+            trim_threshold: None,
+            power_of_two: false,
+            max_size: Self::DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
+/// Packed atlas on the CPU side (see [`TextureAtlasBuilder::build_image`]).
+#[derive(Debug, Clone)]
+pub struct AtlasImage {
+    image: Image,
+    regions: BTreeMap<String, AtlasRegion>,
+}
+
+impl AtlasImage {
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    pub fn regions(&self) -> &BTreeMap<String, AtlasRegion> {
+        &self.regions
+    }
+
+    /// Get the area of the image named `name`.
+    pub fn get(&self, name: &str) -> Option<Rectangle> {
+        self.regions.get(name).map(|r| r.rectangle)
+    }
+
+    /// Export the atlas as a PNG image and a JSON file describing the regions.
+    ///
+    /// The JSON file refers to the image by its path relative to the JSON file
+    /// (or by its absolute path if there is no relative path, e.g., on another drive).
+    #[cfg(feature = "serde")]
+    pub fn export<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        image_path: P,
+        json_path: Q,
+    ) -> Result<(), AtlasError> {
+        let image_path = image_path.as_ref();
+        let json_path = json_path.as_ref();
+        if !self.image.export(image_path) {
+            return Err(AtlasError::ImageExportFailed {
+                path: image_path.to_path_buf(),
+            });
+        }
+        let file = AtlasFile::new(&self.regions, self.image.size(), image_path, json_path)?;
+        std::fs::write(json_path, serde_json::to_string_pretty(&file)?)?;
+        Ok(())
+    }
+
+    /// Load an atlas exported by [`AtlasImage::export`] or [`TextureAtlas::export`].
+    #[cfg(feature = "serde")]
+    pub fn load<P: AsRef<Path>>(json_path: P) -> Result<Self, AtlasError> {
+        let (image_path, regions) = AtlasFile::load(json_path.as_ref())?;
+        let image =
+            Image::load(&image_path).ok_or(AtlasError::ImageLoadFailed { path: image_path })?;
+        Ok(Self { image, regions })
+    }
+}
+
+/// Texture with named regions (see [`TextureAtlasBuilder`]).
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    texture: Texture,
+    regions: BTreeMap<String, AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// Load the atlas texture into GPU memory.
+    pub fn from_image(image: &AtlasImage) -> Result<Self, AtlasError> {
+        let texture =
+            Texture::load_from_image(&image.image).ok_or(AtlasError::TextureLoadFailed)?;
+        Ok(Self {
+            texture,
+            regions: image.regions.clone(),
+        })
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn regions(&self) -> &BTreeMap<String, AtlasRegion> {
+        &self.regions
+    }
+
+    /// Get the area of the image named `name`.
+    pub fn get(&self, name: &str) -> Option<Rectangle> {
+        self.regions.get(name).map(|r| r.rectangle)
+    }
+
+    /// Get the location of the image named `name` (including trimming information).
+    pub fn region(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.get(name)
+    }
+
+    /// Export the atlas as a PNG image (read back from the GPU) and a JSON file describing the regions.
+    #[cfg(feature = "serde")]
+    pub fn export<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        image_path: P,
+        json_path: Q,
+    ) -> Result<(), AtlasError> {
+        let image =
+            Image::load_from_texture(&self.texture).ok_or(AtlasError::ImageCreationFailed)?;
+        AtlasImage {
+            image,
+            regions: self.regions.clone(),
+        }
+        .export(image_path, json_path)
+    }
+
+    /// Load an atlas exported by [`TextureAtlas::export`] or [`AtlasImage::export`].
+    #[cfg(feature = "serde")]
+    pub fn load<P: AsRef<Path>>(json_path: P) -> Result<Self, AtlasError> {
+        let (image_path, regions) = AtlasFile::load(json_path.as_ref())?;
+        let texture = Texture::load(&image_path).ok_or(AtlasError::TextureLoadFailed)?;
+        Ok(Self { texture, regions })
+    }
+}
+
+#[derive(Debug)]
+struct Sprite<'a> {
+    name: &'a String,
+    source_size: Size,
+    area: Rectangle,
+    colors: Vec<Color>,
+}
+
+impl<'a> Sprite<'a> {
+    /// Copy the trimmed area to `dest` (with `border` pixels of extrusion around it).
+    fn blit(&self, atlas: &mut [Color], atlas_width: i32, dest: Rectangle, border: i32) {
+        let (w, h) = (self.area.size.width, self.area.size.height);
+        if w <= 0 || h <= 0 {
+            return;
+        }
+        for y in -border..h + border {
+            for x in -border..w + border {
+                let sx = self.area.position.x + x.clamp(0, w - 1);
+                let sy = self.area.position.y + y.clamp(0, h - 1);
+                let color = self.colors[(sy * self.source_size.width + sx) as usize];
+                let dx = dest.position.x + x;
+                let dy = dest.position.y + y;
+                atlas[(dy * atlas_width + dx) as usize] = color;
+            }
+        }
+    }
+}
+
+fn powers_of_two(max: i32) -> impl Iterator<Item = i32> {
+    (0..31).map(|i| 1 << i).take_while(move |&n| n <= max)
+}
+
+/// Pack the slots into a bin, returning the top-left positions in the order of the slots.
+fn pack(method: PackMethod, bin: (i32, i32), slots: &[(i32, i32)]) -> Option<Vec<Position>> {
+    // Larger slots first.
+    let mut order = (0..slots.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| {
+        let (w, h) = slots[i];
+        std::cmp::Reverse((w.max(h), w as i64 * h as i64))
+    });
+
+    let mut positions = vec![Position { x: 0, y: 0 }; slots.len()];
+    match method {
+        PackMethod::Skyline => {
+            let mut skyline = Skyline::new(bin);
+            for i in order {
+                positions[i] = skyline.insert(slots[i])?;
+            }
+        }
+        PackMethod::MaxRects => {
+            let mut max_rects = MaxRects::new(bin);
+            for i in order {
+                positions[i] = max_rects.insert(slots[i])?;
+            }
+        }
+    }
+    Some(positions)
+}
+
+#[derive(Debug)]
+struct Skyline {
+    bin: (i32, i32),
+
+    // `(x, y, width)` segments from left to right.
+    nodes: Vec<(i32, i32, i32)>,
+}
+
+impl Skyline {
+    fn new(bin: (i32, i32)) -> Self {
+        Self {
+            bin,
+            nodes: vec![(0, 0, bin.0)],
+        }
+    }
+
+    fn insert(&mut self, (w, h): (i32, i32)) -> Option<Position> {
+        if w == 0 || h == 0 {
+            return Some(Position { x: 0, y: 0 });
+        }
+
+        // Bottom-left: the lowest top edge, then the narrowest segment.
+        let mut best: Option<(i32, i32, usize)> = None;
+        for i in 0..self.nodes.len() {
+            if let Some(y) = self.fit(i, w, h) {
+                let key = (y + h, self.nodes[i].2);
+                let better = match best {
+                    Some((bh, bw, _)) => key < (bh, bw),
+                    None => true,
+                };
+                if better {
+                    best = Some((key.0, key.1, i));
+                }
+            }
+        }
+        let (top, _, i) = best?;
+        let x = self.nodes[i].0;
+        let y = top - h;
+
+        self.nodes.insert(i, (x, top, w));
+        let right = x + w;
+        let mut j = i + 1;
+        while j < self.nodes.len() && self.nodes[j].0 < right {
+            let node = &mut self.nodes[j];
+            let end = node.0 + node.2;
+            if end <= right {
+                self.nodes.remove(j);
+            } else {
+                node.2 = end - right;
+                node.0 = right;
+                j += 1;
+            }
+        }
+
+        // Merge neighbors with the same height.
+        let mut k = 0;
+        while k + 1 < self.nodes.len() {
+            if self.nodes[k].1 == self.nodes[k + 1].1 {
+                self.nodes[k].2 += self.nodes[k + 1].2;
+                self.nodes.remove(k + 1);
+            } else {
+                k += 1;
+            }
+        }
+        Some(Position { x, y })
+    }
+
+    /// Get the y-coordinate where a `w`x`h` slot starting at the node `i` fits.
+    fn fit(&self, i: usize, w: i32, h: i32) -> Option<i32> {
+        let x = self.nodes[i].0;
+        if x + w > self.bin.0 {
+            return None;
+        }
+        let mut y = 0;
+        let mut remaining = w;
+        for node in &self.nodes[i..] {
+            if remaining <= 0 {
+                break;
+            }
+            y = y.max(node.1);
+            if y + h > self.bin.1 {
+                return None;
+            }
+            remaining -= node.2;
+        }
+        Some(y)
+    }
+}
+
+#[derive(Debug)]
+struct MaxRects {
+    // `(x, y, width, height)` of the maximal free rectangles.
+    free: Vec<(i32, i32, i32, i32)>,
+}
+
+impl MaxRects {
+    fn new(bin: (i32, i32)) -> Self {
+        Self {
+            free: vec![(0, 0, bin.0, bin.1)],
+        }
+    }
+
+    fn insert(&mut self, (w, h): (i32, i32)) -> Option<Position> {
+        if w == 0 || h == 0 {
+            return Some(Position { x: 0, y: 0 });
+        }
+
+        // Best short side fit (ties are broken by the long side).
+        let (x, y) = self
+            .free
+            .iter()
+            .filter(|f| f.2 >= w && f.3 >= h)
+            .min_by_key(|f| {
+                let (dw, dh) = (f.2 - w, f.3 - h);
+                (dw.min(dh), dw.max(dh), f.1, f.0)
+            })
+            .map(|f| (f.0, f.1))?;
+
+        let placed = (x, y, w, h);
+        let mut split = Vec::new();
+        self.free.retain(|&f| {
+            if !intersects(f, placed) {
+                return true;
+            }
+            if placed.0 > f.0 {
+                split.push((f.0, f.1, placed.0 - f.0, f.3));
+            }
+            if placed.0 + placed.2 < f.0 + f.2 {
+                let x = placed.0 + placed.2;
+                split.push((x, f.1, f.0 + f.2 - x, f.3));
+            }
+            if placed.1 > f.1 {
+                split.push((f.0, f.1, f.2, placed.1 - f.1));
+            }
+            if placed.1 + placed.3 < f.1 + f.3 {
+                let y = placed.1 + placed.3;
+                split.push((f.0, y, f.2, f.1 + f.3 - y));
+            }
+            false
+        });
+        self.free.extend(split);
+
+        // Remove free rectangles contained in others.
+        let free = std::mem::take(&mut self.free);
+        for (i, &a) in free.iter().enumerate() {
+            let redundant = free
+                .iter()
+                .enumerate()
+                .any(|(j, &b)| i != j && contains(b, a) && (a != b || j < i));
+            if !redundant {
+                self.free.push(a);
+            }
+        }
+        Some(Position { x, y })
+    }
+}
+
+fn intersects(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> bool {
+    a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+}
+
+fn contains(outer: (i32, i32, i32, i32), inner: (i32, i32, i32, i32)) -> bool {
+    inner.0 >= outer.0
+        && inner.1 >= outer.1
+        && inner.0 + inner.2 <= outer.0 + outer.2
+        && inner.1 + inner.3 <= outer.1 + outer.3
+}
+
+// JSON representation of an exported atlas.
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct AtlasFile {
+    image: std::path::PathBuf,
+    width: i32,
+    height: i32,
+    regions: BTreeMap<String, AtlasFileRegion>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct AtlasFileRegion {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    source_width: i32,
+    source_height: i32,
+    offset_x: i32,
+    offset_y: i32,
+}
+
+#[cfg(feature = "serde")]
+impl AtlasFile {
+    fn new(
+        regions: &BTreeMap<String, AtlasRegion>,
+        size: Size,
+        image_path: &Path,
+        json_path: &Path,
+    ) -> std::io::Result<Self> {
+        let dir = json_path.parent().unwrap_or_else(|| Path::new(""));
+        let image = relative_path(dir, image_path)?;
+        let regions = regions
+            .iter()
+            .map(|(name, r)| {
+                let region = AtlasFileRegion {
+                    x: r.rectangle.position.x,
+                    y: r.rectangle.position.y,
+                    width: r.rectangle.size.width,
+                    height: r.rectangle.size.height,
+                    source_width: r.source_size.width,
+                    source_height: r.source_size.height,
+                    offset_x: r.offset.x,
+                    offset_y: r.offset.y,
+                };
+                (name.clone(), region)
+            })
+            .collect();
+        Ok(Self {
+            image,
+            width: size.width,
+            height: size.height,
+            regions,
+        })
+    }
+
+    fn load(
+        json_path: &Path,
+    ) -> Result<(std::path::PathBuf, BTreeMap<String, AtlasRegion>), AtlasError> {
+        let file: Self = serde_json::from_str(&std::fs::read_to_string(json_path)?)?;
+        let dir = json_path.parent().unwrap_or_else(|| Path::new(""));
+        let regions = file
+            .regions
+            .into_iter()
+            .map(|(name, r)| {
+                let region = AtlasRegion {
+                    rectangle: Rectangle::new(r.x, r.y, r.width, r.height),
+                    source_size: Size {
+                        width: r.source_width,
+                        height: r.source_height,
+                    },
+                    offset: Position {
+                        x: r.offset_x,
+                        y: r.offset_y,
+                    },
+                };
+                (name, region)
+            })
+            .collect();
+        Ok((dir.join(file.image), regions))
+    }
+}
+
+/// Get the path of `path` relative to `dir` (or the absolute path if there is no such path, e.g., on another drive).
+#[cfg(feature = "serde")]
+fn relative_path(dir: &Path, path: &Path) -> std::io::Result<std::path::PathBuf> {
+    use std::path::Component;
+
+    let cwd = std::env::current_dir()?;
+    let absolute = |p: &Path| {
+        let mut normalized = std::path::PathBuf::new();
+        for component in cwd.join(p).components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                c => normalized.push(c),
+            }
+        }
+        normalized
+    };
+    let (dir, path) = (absolute(dir), absolute(path));
+
+    let mut dir_components = dir.components().peekable();
+    let mut path_components = path.components().peekable();
+    if dir_components.peek() != path_components.peek() {
+        return Ok(path);
+    }
+    while dir_components.peek().is_some() && dir_components.peek() == path_components.peek() {
+        dir_components.next();
+        path_components.next();
+    }
+    let mut relative: std::path::PathBuf = dir_components.map(|_| Component::ParentDir).collect();
+    relative.extend(path_components);
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots() -> Vec<(i32, i32)> {
+        // Deterministic mix of sizes, including duplicates and thin slots.
+        (0..60)
+            .map(|i| (1 + (i * 7) % 23, 1 + (i * 13) % 17))
+            .chain([(64, 1), (1, 64), (32, 32)])
+            .collect()
+    }
+
+    fn check_packing(method: PackMethod) {
+        let bin = (128, 128);
+        let slots = slots();
+        let positions = pack(method, bin, &slots).unwrap();
+        let rects = positions
+            .iter()
+            .zip(&slots)
+            .map(|(p, &(w, h))| (p.x, p.y, w, h))
+            .collect::<Vec<_>>();
+        for (i, &a) in rects.iter().enumerate() {
+            assert!(
+                contains((0, 0, bin.0, bin.1), a),
+                "{:?} is out of bounds",
+                a
+            );
+            for &b in &rects[i + 1..] {
+                assert!(!intersects(a, b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn skyline_packs_without_overlap() {
+        check_packing(PackMethod::Skyline);
+    }
+
+    #[test]
+    fn max_rects_packs_without_overlap() {
+        check_packing(PackMethod::MaxRects);
+    }
+
+    #[test]
+    fn pack_fails_when_slots_do_not_fit() {
+        for method in [PackMethod::Skyline, PackMethod::MaxRects] {
+            assert!(pack(method, (16, 16), &[(17, 1)]).is_none());
+            assert!(pack(method, (16, 16), &[(16, 16), (1, 1)]).is_none());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn relative_path_walks_up_from_dir() {
+        let relative =
+            |dir: &str, path: &str| relative_path(Path::new(dir), Path::new(path)).unwrap();
+        assert_eq!(relative("/a/b", "/a/b/c.png"), Path::new("c.png"));
+        assert_eq!(relative("/a/b", "/a/c/d.png"), Path::new("../c/d.png"));
+        assert_eq!(relative("/a/b/c", "/d.png"), Path::new("../../../d.png"));
+        assert_eq!(relative("a/./b", "a/c/../b/d.png"), Path::new("d.png"));
+    }
+}
@@ -0,0 +1,343 @@
+//! Time-related utilities.
+use std::time::Duration;
+
+/// Fixed-timestep simulation driver.
+///
+/// Frame time is accumulated and consumed in fixed-size steps.
+/// The lag that remains after the steps can be used to interpolate between the last two simulation states.
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    step: Duration,
+    max_steps: usize,
+    accumulator: Duration,
+    stats: LagStats,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_MAX_STEPS: usize = 5;
+
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "step must be a positive duration");
+        Self {
+            step,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: Duration::ZERO,
+            stats: LagStats::default(),
+        }
+    }
+
+    /// Make a timestep running `hz` steps per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero.
+    pub fn from_hz(hz: u32) -> Self {
+        assert!(hz > 0, "hz must be positive");
+        Self::new(Duration::from_secs(1) / hz)
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn set_step(&mut self, step: Duration) {
+        assert!(!step.is_zero(), "step must be a positive duration");
+        self.step = step;
+    }
+
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Set the maximum number of steps to run per frame.
+    ///
+    /// If the lag cannot be consumed within the limit, the remaining whole steps are dropped
+    /// (to avoid the "spiral of death").
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn set_max_steps(&mut self, n: usize) {
+        assert!(n > 0, "max steps must be positive");
+        self.max_steps = n;
+    }
+
+    /// Accumulate `dt` and call `f` once per elapsed step.
+    ///
+    /// Returns the number of steps that were run.
+    pub fn advance<F, E>(&mut self, dt: Duration, mut f: F) -> Result<usize, E>
+    where
+        F: FnMut(Duration) -> Result<(), E>,
+    {
+        self.accumulator += dt;
+        self.stats.max_lag = self.stats.max_lag.max(self.accumulator);
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            f(self.step)?;
+            self.accumulator -= self.step;
+            steps += 1;
+            self.stats.total_steps += 1;
+        }
+        if self.accumulator >= self.step {
+            let dropped = self.accumulator.as_nanos() / self.step.as_nanos();
+            self.stats.dropped_steps += dropped as u64;
+            self.accumulator =
+                Duration::from_nanos((self.accumulator.as_nanos() % self.step.as_nanos()) as u64);
+        }
+
+        self.stats.steps = steps;
+        self.stats.lag = self.accumulator;
+        Ok(steps)
+    }
+
+    /// Get interpolation factor between the previous and the current simulation states [0..1).
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+
+    /// Clear the accumulated lag.
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+    }
+
+    pub fn stats(&self) -> LagStats {
+        self.stats
+    }
+}
+
+/// Accumulated-lag statistics of [`FixedTimestep`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LagStats {
+    /// Steps run in the last frame.
+    pub steps: usize,
+
+    /// Steps run in total.
+    pub total_steps: u64,
+
+    /// Steps dropped in total because of the per-frame limit.
+    pub dropped_steps: u64,
+
+    /// Lag left after the last frame.
+    pub lag: Duration,
+
+    /// Largest lag observed before running steps.
+    pub max_lag: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockMode {
+    /// Follow the wall clock.
+    Real,
+
+    /// Advance only by [`Clock::advance`].
+    Manual,
+
+    /// Follow the wall clock scaled by the given factor (e.g., `0.5` for slow motion).
+    Scaled(f32),
+}
+
+/// Game clock that provides the time reported by [`System::get_time`] and [`System::get_frame_time`].
+///
+/// The clock is updated at the end of each frame.
+///
+/// [`System::get_time`]: crate::System::get_time
+/// [`System::get_frame_time`]: crate::System::get_frame_time
+#[derive(Debug, Clone)]
+pub struct Clock {
+    mode: ClockMode,
+    paused: bool,
+    time: Duration,
+    frame_time: Duration,
+    pending: Duration,
+}
+
+impl Clock {
+    /// Maximum scale of [`ClockMode::Scaled`].
+    pub const MAX_SCALE: f32 = 1000.0;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            mode: ClockMode::Real,
+            paused: false,
+            time: Duration::ZERO,
+            frame_time: Duration::ZERO,
+            pending: Duration::ZERO,
+        }
+    }
+
+    pub fn mode(&self) -> ClockMode {
+        self.mode
+    }
+
+    /// Set the clock mode.
+    ///
+    /// Scales are clamped to `0.0..=MAX_SCALE` (NaN is treated as 0.0).
+    pub fn set_mode(&mut self, mode: ClockMode) {
+        self.mode = match mode {
+            ClockMode::Scaled(scale) if scale.is_nan() => ClockMode::Scaled(0.0),
+            ClockMode::Scaled(scale) => ClockMode::Scaled(scale.clamp(0.0, Self::MAX_SCALE)),
+            mode => mode,
+        };
+    }
+
+    /// Stop game time (frames are still drawn).
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Advance the clock in manual mode (applied at the end of the current frame).
+    pub fn advance(&mut self, dt: Duration) {
+        self.pending += dt;
+    }
+
+    /// Get elapsed game time.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Get game time for last frame drawn (delta time).
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    pub(crate) fn tick(&mut self, real_frame_time: Duration) {
+        let dt = match self.mode {
+            ClockMode::Real => real_frame_time,
+            ClockMode::Manual => std::mem::take(&mut self.pending),
+            ClockMode::Scaled(scale) => real_frame_time.mul_f32(scale),
+        };
+        self.frame_time = if self.paused { Duration::ZERO } else { dt };
+        self.time += self.frame_time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    const STEP: Duration = Duration::from_millis(10);
+
+    fn advance(timestep: &mut FixedTimestep, dt: Duration) -> usize {
+        timestep
+            .advance(dt, |step| {
+                assert_eq!(step, STEP);
+                Ok::<_, ()>(())
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn advance_runs_whole_steps_and_keeps_lag() {
+        let mut timestep = FixedTimestep::new(STEP);
+        assert_eq!(advance(&mut timestep, ms(25)), 2);
+        assert_eq!(timestep.stats().lag, ms(5));
+        assert!((timestep.alpha() - 0.5).abs() < 1e-6);
+
+        assert_eq!(advance(&mut timestep, ms(5)), 1);
+        assert_eq!(timestep.alpha(), 0.0);
+        assert_eq!(advance(&mut timestep, ms(3)), 0);
+
+        timestep.reset();
+        assert_eq!(timestep.alpha(), 0.0);
+        assert_eq!(timestep.stats().total_steps, 3);
+    }
+
+    #[test]
+    fn advance_drops_steps_past_the_limit() {
+        let mut timestep = FixedTimestep::new(STEP);
+        timestep.set_max_steps(2);
+        assert_eq!(advance(&mut timestep, ms(57)), 2);
+        let stats = timestep.stats();
+        assert_eq!(stats.steps, 2);
+        assert_eq!(stats.dropped_steps, 3);
+        assert_eq!(stats.lag, ms(7));
+        assert_eq!(stats.max_lag, ms(57));
+
+        assert_eq!(advance(&mut timestep, ms(3)), 1);
+        assert_eq!(timestep.stats().max_lag, ms(57));
+    }
+
+    #[test]
+    fn advance_stops_at_first_error() {
+        let mut timestep = FixedTimestep::from_hz(100);
+        let mut calls = 0;
+        let result = timestep.advance(ms(30), |_| {
+            calls += 1;
+            if calls == 2 {
+                Err("failed")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err("failed"));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn clock_modes() {
+        let mut clock = Clock::new();
+        clock.tick(ms(16));
+        assert_eq!((clock.time(), clock.frame_time()), (ms(16), ms(16)));
+
+        clock.set_mode(ClockMode::Manual);
+        clock.advance(ms(5));
+        clock.advance(ms(5));
+        clock.tick(ms(16));
+        assert_eq!((clock.time(), clock.frame_time()), (ms(26), ms(10)));
+        clock.tick(ms(16));
+        assert_eq!(clock.frame_time(), Duration::ZERO);
+
+        clock.set_mode(ClockMode::Scaled(0.5));
+        clock.tick(ms(16));
+        assert_eq!(clock.frame_time(), ms(8));
+        clock.pause();
+        clock.tick(ms(16));
+        assert_eq!((clock.time(), clock.frame_time()), (ms(34), Duration::ZERO));
+    }
+
+    #[test]
+    fn clock_scale_is_clamped() {
+        let mut clock = Clock::new();
+        for (scale, expected) in [
+            (f32::INFINITY, Clock::MAX_SCALE),
+            (1e30, Clock::MAX_SCALE),
+            (-1.0, 0.0),
+            (f32::NAN, 0.0),
+        ] {
+            clock.set_mode(ClockMode::Scaled(scale));
+            assert_eq!(clock.mode(), ClockMode::Scaled(expected));
+            clock.tick(ms(16));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "hz must be positive")]
+    fn from_hz_rejects_zero() {
+        FixedTimestep::from_hz(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "max steps must be positive")]
+    fn set_max_steps_rejects_zero() {
+        FixedTimestep::from_hz(60).set_max_steps(0);
+    }
+}
@@ -0,0 +1,8 @@
+use rfraylib::core::drawing::DrawMode;
+use rfraylib::{Draw, Rectangle};
+
+fn begin_scissor<T: Draw>(canvas: &mut T) {
+    canvas.begin_mode(&DrawMode::Scissor(Rectangle::new(0, 0, 10, 10)));
+}
+
+fn main() {}
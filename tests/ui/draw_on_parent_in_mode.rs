@@ -0,0 +1,16 @@
+use rfraylib::{Camera, Color, Draw, Position, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+    let camera = Camera {
+        offset: Position { x: 0, y: 0 },
+        target: Position { x: 0, y: 0 },
+        rotation: 0.0,
+        zoom: 1.0,
+    };
+
+    let mut canvas = system.next_frame();
+    let mut world = canvas.with_camera(camera);
+    canvas.clear_background(Color::BLANK);
+    world.clear_background(Color::BLANK);
+}
@@ -0,0 +1,10 @@
+use rfraylib::{Color, Draw, Position};
+
+struct Anywhere;
+
+impl Draw for Anywhere {}
+
+fn main() {
+    let mut canvas = Anywhere;
+    canvas.draw_pixel(Position { x: 0, y: 0 }, Color::RED);
+}
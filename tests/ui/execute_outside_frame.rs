@@ -0,0 +1,14 @@
+use rfraylib::core::drawing::{DrawCommand, DrawMode};
+use rfraylib::{Color, Position, Rectangle};
+
+fn main() {
+    let command = DrawCommand::Pixel {
+        position: Position { x: 0, y: 0 },
+        color: Color::RED,
+    };
+    command.execute();
+
+    let mode = DrawMode::Scissor(Rectangle::new(0, 0, 10, 10));
+    mode.begin();
+    mode.end();
+}
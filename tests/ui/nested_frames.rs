@@ -0,0 +1,10 @@
+use rfraylib::{Color, Draw, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+
+    let mut canvas = system.next_frame();
+    let mut inner = system.next_frame();
+    canvas.clear_background(Color::BLANK);
+    inner.clear_background(Color::BLANK);
+}
@@ -0,0 +1,12 @@
+use rfraylib::{Color, Draw, RenderTexture, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+    let mut a = RenderTexture::load((64, 64).into()).unwrap();
+    let mut b = RenderTexture::load((64, 64).into()).unwrap();
+
+    let mut canvas = system.next_frame();
+    let mut texture_canvas = canvas.begin_texture_mode(&mut a);
+    texture_canvas.begin_texture_mode(&mut b);
+    texture_canvas.clear_background(Color::BLANK);
+}
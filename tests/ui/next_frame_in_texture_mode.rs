@@ -0,0 +1,11 @@
+use rfraylib::{Color, Draw, RenderTexture, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+    let mut target = RenderTexture::load((64, 64).into()).unwrap();
+
+    let mut texture_canvas = system.create_texture_canvas(&mut target);
+    let mut canvas = system.next_frame();
+    texture_canvas.clear_background(Color::BLANK);
+    canvas.clear_background(Color::BLANK);
+}
@@ -0,0 +1,14 @@
+use rfraylib::core::drawing::WindowCanvas;
+use rfraylib::{Color, Draw, Position, RenderTexture};
+
+// Clones of the target texture do not borrow the target, so this compiles
+// (the texture canvas skips drawing the target into itself at runtime).
+fn draw_target_clone(canvas: &mut WindowCanvas, target: &mut RenderTexture) {
+    let texture = target.texture().clone();
+    let mut texture_canvas = canvas.begin_texture_mode(target);
+    texture_canvas.draw_texture(&texture, Position { x: 0, y: 0 }, Color::WHITE);
+}
+
+fn main() {
+    let _ = draw_target_clone;
+}
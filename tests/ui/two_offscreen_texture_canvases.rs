@@ -0,0 +1,12 @@
+use rfraylib::{Color, Draw, RenderTexture, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+    let mut a = RenderTexture::load((64, 64).into()).unwrap();
+    let mut b = RenderTexture::load((64, 64).into()).unwrap();
+
+    let mut canvas_a = system.create_texture_canvas(&mut a);
+    let mut canvas_b = system.create_texture_canvas(&mut b);
+    canvas_a.clear_background(Color::BLANK);
+    canvas_b.clear_background(Color::BLANK);
+}
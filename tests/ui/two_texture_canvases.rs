@@ -0,0 +1,13 @@
+use rfraylib::{Color, Draw, RenderTexture, SystemBuilder};
+
+fn main() {
+    let mut system = SystemBuilder::new().build().unwrap();
+    let mut a = RenderTexture::load((64, 64).into()).unwrap();
+    let mut b = RenderTexture::load((64, 64).into()).unwrap();
+
+    let mut canvas = system.next_frame();
+    let mut canvas_a = canvas.begin_texture_mode(&mut a);
+    let mut canvas_b = canvas.begin_texture_mode(&mut b);
+    canvas_a.clear_background(Color::BLANK);
+    canvas_b.clear_background(Color::BLANK);
+}